@@ -1,16 +1,23 @@
 use alloc::collections::BTreeMap;
+use std::any::Any;
+use std::collections::HashMap;
 use std::fs;
+use std::panic::{self, AssertUnwindSafe};
 use std::path::Path;
 use std::time::Instant;
 
+use anyhow::{anyhow, Result};
 use log::{debug, trace};
-use plonky2::field::types::PrimeField64;
-use plonky2::iop::witness::PartialWitness;
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::types::{Field, PrimeField64};
+use plonky2::iop::target::Target;
+use plonky2::iop::witness::{PartialWitness, PartitionWitness, Witness};
 use plonky2::plonk::circuit_data::CircuitData;
-use plonky2::plonk::config::{AlgebraicHasher, GenericConfig, GenericHashOut};
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig, GenericHashOut, Hasher};
 use plonky2::plonk::proof::ProofWithPublicInputs;
 use plonky2::plonk::prover::prove_with_partition_witness;
-use plonky2::util::serialization::{Buffer, GateSerializer, IoResult, Read, Write};
+use plonky2::plonk::vars::EvaluationVars;
+use plonky2::util::serialization::{Buffer, GateSerializer, IoError, IoResult, Read, Write};
 use plonky2::util::timing::TimingTree;
 
 use super::config::PlonkParameters;
@@ -18,9 +25,12 @@ use super::input::PublicInput;
 use super::output::PublicOutput;
 use super::serialization::hints::HintSerializer;
 use super::serialization::{GateRegistry, HintRegistry};
-use super::witness::{generate_witness, generate_witness_async};
-use crate::frontend::builder::CircuitIO;
+use super::witness::{generate_witness, generate_witness_async, generate_witness_with_recorder};
+use super::CircuitDiff;
+use crate::frontend::builder::{CircuitIO, PublicInputsLayout};
+use crate::frontend::eth::storage::generators::generator_chain_id;
 use crate::frontend::hint::asynchronous::generator::AsyncHintDataRef;
+use crate::frontend::hint::asynchronous::recording::{HintRecorder, HintRecording};
 use crate::utils::hex;
 use crate::utils::serde::{BufferRead, BufferWrite};
 
@@ -31,6 +41,7 @@ use crate::utils::serde::{BufferRead, BufferWrite};
 pub struct CircuitBuild<L: PlonkParameters<D>, const D: usize> {
     pub data: CircuitData<L::Field, L::Config, D>,
     pub io: CircuitIO<D>,
+    pub public_inputs: PublicInputsLayout,
     pub async_hints: BTreeMap<usize, AsyncHintDataRef<L, D>>,
 }
 
@@ -49,19 +60,34 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuild<L, D> {
         ProofWithPublicInputs<L::Field, L::Config, D>,
         PublicOutput<L, D>,
     )
+    where
+        <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher:
+            AlgebraicHasher<<L as PlonkParameters<D>>::Field>,
+    {
+        self.prove_with_recorder(pw, None).unwrap()
+    }
+
+    fn prove_with_recorder(
+        &self,
+        pw: PartialWitness<L::Field>,
+        recorder: Option<HintRecorder>,
+    ) -> Result<(
+        ProofWithPublicInputs<L::Field, L::Config, D>,
+        PublicOutput<L, D>,
+    )>
     where
         <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher:
             AlgebraicHasher<<L as PlonkParameters<D>>::Field>,
     {
         let start_time = Instant::now();
         trace!("generating witness...");
-        let partition_witness = generate_witness(
+        let partition_witness = generate_witness_with_recorder(
             pw,
             &self.data.prover_only,
             &self.data.common,
             &self.async_hints,
-        )
-        .unwrap();
+            recorder,
+        )?;
         let elapsed_time = start_time.elapsed();
         debug!("Witness generation took {:?}", elapsed_time);
         trace!("finished generating witness");
@@ -71,13 +97,12 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuild<L, D> {
             &self.data.common,
             partition_witness,
             &mut TimingTree::default(),
-        )
-        .unwrap();
+        )?;
         trace!("finished generating proof");
         let output = PublicOutput::from_proof_with_pis(&self.io, &proof_with_pis);
         let elapsed_time = start_time.elapsed();
         debug!("proving took: {:?}", elapsed_time);
-        (proof_with_pis, output)
+        Ok((proof_with_pis, output))
     }
 
     /// Generates a proof for the circuit. The proof can be verified using `verify`.
@@ -97,6 +122,253 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuild<L, D> {
         self.prove_with_partial_witness(pw)
     }
 
+    /// Generates a proof for the circuit and saves the responses of its asynchronous hints and of
+    /// the synchronous generators that fetch data, such as the ethers and beacon generators, to
+    /// `path`. The proof can be regenerated offline from the recording with `prove_with_replay`,
+    /// and the recording can be audited.
+    pub fn prove_with_recording(
+        &self,
+        input: &PublicInput<L, D>,
+        path: impl AsRef<Path>,
+    ) -> Result<(
+        ProofWithPublicInputs<L::Field, L::Config, D>,
+        PublicOutput<L, D>,
+    )>
+    where
+        <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher:
+            AlgebraicHasher<<L as PlonkParameters<D>>::Field>,
+    {
+        let mut pw = PartialWitness::new();
+        self.io.set_witness(&mut pw, input);
+        let recorder = HintRecorder::record();
+        let result = self.prove_with_recorder(pw, Some(recorder.clone()));
+        recorder.recording().save(path)?;
+        result
+    }
+
+    /// Generates a proof for the circuit, answering its asynchronous hints and the generators that
+    /// fetch data from a recording saved by `prove_with_recording` instead of running them.
+    pub fn prove_with_replay(
+        &self,
+        input: &PublicInput<L, D>,
+        path: impl AsRef<Path>,
+    ) -> Result<(
+        ProofWithPublicInputs<L::Field, L::Config, D>,
+        PublicOutput<L, D>,
+    )>
+    where
+        <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher:
+            AlgebraicHasher<<L as PlonkParameters<D>>::Field>,
+    {
+        let mut pw = PartialWitness::new();
+        self.io.set_witness(&mut pw, input);
+        let recorder = HintRecorder::replay(HintRecording::load(path)?);
+        self.prove_with_recorder(pw, Some(recorder))
+    }
+
+    /// Generates the witness for the circuit and checks that it satisfies all constraints without
+    /// computing a proof. On failure, returns an error describing the first violated constraint.
+    pub fn mock_prove(&self, input: &PublicInput<L, D>) -> Result<PublicOutput<L, D>>
+    where
+        <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher:
+            AlgebraicHasher<<L as PlonkParameters<D>>::Field>,
+    {
+        let mut pw = PartialWitness::new();
+        self.io.set_witness(&mut pw, input);
+
+        // Witness generation panics when a copy constraint is violated, so surface it as an error.
+        let witness = panic::catch_unwind(AssertUnwindSafe(|| {
+            generate_witness(
+                pw,
+                &self.data.prover_only,
+                &self.data.common,
+                &self.async_hints,
+            )
+        }))
+        .map_err(|e| anyhow!("copy constraint is not satisfied: {}", panic_message(e)))??;
+
+        self.check_gate_constraints(&witness)?;
+        Ok(PublicOutput::from_witness(&self.io, &witness))
+    }
+
+    /// Returns the sorted ids of the chains the circuit's hints and generators fetch data from. An
+    /// RPC for each of these chains must be configured to generate a proof.
+    pub fn required_chains(&self) -> Vec<u64> {
+        let mut chain_ids = self
+            .async_hints
+            .values()
+            .filter_map(|hint| hint.chain_id())
+            .collect::<Vec<_>>();
+        chain_ids.extend(
+            self.data
+                .prover_only
+                .generators
+                .iter()
+                .filter_map(|generator| generator_chain_id::<L, D>(generator, &self.data.common)),
+        );
+        chain_ids.sort_unstable();
+        chain_ids.dedup();
+        chain_ids
+    }
+
+    /// Evaluates the first `num_columns` selector, constant and sigma polynomials over the
+    /// subgroup, which gives their value at each row.
+    fn fixed_column_values(&self, num_columns: usize) -> Vec<Vec<L::Field>> {
+        self.data
+            .prover_only
+            .constants_sigmas_commitment
+            .polynomials[..num_columns]
+            .iter()
+            .map(|poly| poly.clone().fft().values)
+            .collect()
+    }
+
+    /// Returns the index of the gate at the given row, or `None` if the row is unused. The
+    /// selector of the group containing the row's gate holds the index of that gate.
+    fn gate_index_at(&self, columns: &[Vec<L::Field>], row: usize) -> Option<usize> {
+        let common = &self.data.common;
+        (0..common.selectors_info.num_selectors())
+            .map(|i| columns[i][row].to_canonical_u64() as usize)
+            .find(|index| *index < common.gates.len())
+    }
+
+    /// Returns a human-readable dump of the circuit's gates. Each used row lists the gate type,
+    /// its constants, and the wires its routed wires are copy constrained to.
+    pub fn dump_constraints(&self) -> String {
+        let common = &self.data.common;
+        let num_routed_wires = common.config.num_routed_wires;
+        let columns = self.fixed_column_values(common.num_constants + num_routed_wires);
+        let constants_offset = common.selectors_info.num_selectors() + common.num_lookup_selectors;
+        let sigmas = &columns[common.num_constants..];
+
+        // The sigma value of a wire is `k_column * g^row` for the next wire in its copy cycle.
+        let subgroup = L::Field::two_adic_subgroup(common.degree_bits());
+        let mut wire_positions = HashMap::new();
+        for (column, k) in common.k_is.iter().take(num_routed_wires).enumerate() {
+            for (row, g) in subgroup.iter().enumerate() {
+                wire_positions.insert((*k * *g).to_canonical_u64(), (row, column));
+            }
+        }
+
+        let mut dump = format!(
+            "degree: {}, wires: {}, routed wires: {}\ngates:\n",
+            common.degree(),
+            common.config.num_wires,
+            num_routed_wires
+        );
+        for (i, gate) in common.gates.iter().enumerate() {
+            dump.push_str(&format!("  [{}] {}\n", i, gate.0.id()));
+        }
+        dump.push_str("rows:\n");
+        for row in 0..common.degree() {
+            let Some(gate_index) = self.gate_index_at(&columns, row) else {
+                continue;
+            };
+            let gate = &common.gates[gate_index];
+            if gate.0.num_constraints() == 0 {
+                continue;
+            }
+
+            let constants = columns[constants_offset..constants_offset + gate.0.num_constants()]
+                .iter()
+                .map(|values| values[row].to_canonical_u64().to_string())
+                .collect::<Vec<_>>();
+            let copies = (0..num_routed_wires)
+                .filter_map(|column| {
+                    let (copy_row, copy_column) =
+                        wire_positions[&sigmas[column][row].to_canonical_u64()];
+                    ((copy_row, copy_column) != (row, column))
+                        .then(|| format!("w{}->({}, {})", column, copy_row, copy_column))
+                })
+                .collect::<Vec<_>>();
+            dump.push_str(&format!(
+                "  {}: {} constants=[{}] copies=[{}]\n",
+                row,
+                gate.0.id(),
+                constants.join(", "),
+                copies.join(", ")
+            ));
+        }
+        dump
+    }
+
+    /// Returns the number of rows used by each gate type of the circuit.
+    pub fn gate_counts(&self) -> BTreeMap<String, usize> {
+        let common = &self.data.common;
+        let columns = self.fixed_column_values(common.selectors_info.num_selectors());
+        let mut counts = BTreeMap::new();
+        for row in 0..common.degree() {
+            if let Some(gate_index) = self.gate_index_at(&columns, row) {
+                *counts.entry(common.gates[gate_index].0.id()).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    /// Compares the structure of this circuit with a newer version of it, reporting the gate
+    /// types whose number of rows changed and the changes to the public inputs layout.
+    pub fn diff(&self, other: &Self) -> CircuitDiff {
+        CircuitDiff::new(
+            &self.gate_counts(),
+            &other.gate_counts(),
+            &self.public_inputs,
+            &other.public_inputs,
+        )
+    }
+
+    /// Evaluates the constraints of the gate at every row against the witness.
+    fn check_gate_constraints(&self, witness: &PartitionWitness<L::Field>) -> Result<()> {
+        let common = &self.data.common;
+        let prover_only = &self.data.prover_only;
+
+        let constants = self.fixed_column_values(common.num_constants);
+        let constants_offset = common.selectors_info.num_selectors() + common.num_lookup_selectors;
+
+        let public_inputs = prover_only
+            .public_inputs
+            .iter()
+            .map(|t| witness.get_target(*t))
+            .collect::<Vec<_>>();
+        let public_inputs_hash = <<L::Config as GenericConfig<D>>::InnerHasher as Hasher<
+            L::Field,
+        >>::hash_no_pad(&public_inputs);
+
+        let to_extension = <L::Field as Extendable<D>>::Extension::from_basefield;
+        for row in 0..common.degree() {
+            let Some(gate_index) = self.gate_index_at(&constants, row) else {
+                continue;
+            };
+            let gate = &common.gates[gate_index];
+
+            let local_constants = constants[constants_offset..]
+                .iter()
+                .map(|values| to_extension(values[row]))
+                .collect::<Vec<_>>();
+            let local_wires = (0..common.config.num_wires)
+                .map(|column| {
+                    let value = witness.try_get_target(Target::wire(row, column));
+                    to_extension(value.unwrap_or(L::Field::ZERO))
+                })
+                .collect::<Vec<_>>();
+            let vars = EvaluationVars {
+                local_constants: &local_constants,
+                local_wires: &local_wires,
+                public_inputs_hash: &public_inputs_hash,
+            };
+
+            let constraints = gate.0.eval_unfiltered(vars);
+            if let Some(i) = constraints.iter().position(|c| !c.is_zero()) {
+                return Err(anyhow!(
+                    "constraint {} of gate {} at row {} is not satisfied",
+                    i,
+                    gate.0.id(),
+                    row
+                ));
+            }
+        }
+        Ok(())
+    }
+
     /// Generates a proof for the circuit using a plonky2 partial witness. The proof can be verified
     /// using `verify`.
     pub async fn prove_with_partial_witness_async(
@@ -205,6 +477,9 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuild<L, D> {
             hint_serializer.write_async_hint(&mut buffer, hint_data, &self.data.common)?;
         }
 
+        let public_inputs = bincode::serialize(&self.public_inputs).unwrap();
+        buffer.write_bytes(&public_inputs)?;
+
         Ok(buffer)
     }
 
@@ -224,7 +499,7 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuild<L, D> {
         )?;
 
         let io = buffer.read_bytes()?;
-        let io: CircuitIO<D> = bincode::deserialize(&io).unwrap();
+        let io: CircuitIO<D> = bincode::deserialize(&io).map_err(|_| IoError)?;
 
         let mut async_hints = BTreeMap::new();
         let map_size = buffer.read_usize()?;
@@ -234,9 +509,18 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuild<L, D> {
             async_hints.insert(key, hint_data);
         }
 
+        // Circuits saved before the layout was serialized have no layout.
+        let public_inputs = if buffer.unread_bytes().is_empty() {
+            PublicInputsLayout::default()
+        } else {
+            let bytes = buffer.read_bytes()?;
+            bincode::deserialize(&bytes).map_err(|_| IoError)?
+        };
+
         Ok(CircuitBuild {
             data,
             io,
+            public_inputs,
             async_hints,
         })
     }
@@ -295,10 +579,33 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuild<L, D> {
     }
 }
 
+/// Extracts the message from a panic payload.
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
+    use std::{env, fs, process};
+
+    use async_trait::async_trait;
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::util::serialization::Write;
+    use rand::{thread_rng, Rng};
+    use serde::{Deserialize, Serialize};
 
-    use crate::backend::circuit::CircuitBuild;
+    use crate::backend::circuit::{generate_witness_with_recorder, CircuitBuild};
+    use crate::frontend::builder::PublicInputsLayout;
+    use crate::frontend::eth::storage::generators::EthStorageProofGenerator;
+    use crate::frontend::eth::vars::AddressVariable;
+    use crate::frontend::hint::asynchronous::hint::AsyncHint;
+    use crate::frontend::hint::asynchronous::recording::{HintRecorder, HintRecording};
     use crate::prelude::*;
 
     type L = DefaultParameters;
@@ -356,6 +663,22 @@ pub(crate) mod tests {
         for i in 0..old_output_variables.len() {
             assert_eq!(old_output_variables[i].0, new_output_variables[i].0);
         }
+
+        // Circuits saved without a public inputs layout load with an empty layout, but a layout
+        // that cannot be read is an error.
+        let layout_len = bincode::serialize(&circuit.public_inputs).unwrap().len();
+        let mut legacy_bytes = bytes[..bytes.len() - 8 - layout_len].to_vec();
+        let legacy_circuit =
+            CircuitBuild::<L, D>::deserialize(&legacy_bytes, &gate_serializer, &hint_serializer)
+                .unwrap();
+        assert_eq!(legacy_circuit.public_inputs, PublicInputsLayout::default());
+        legacy_bytes.write_bytes(&[0xff]).unwrap();
+        assert!(CircuitBuild::<L, D>::deserialize(
+            &legacy_bytes,
+            &gate_serializer,
+            &hint_serializer
+        )
+        .is_err());
     }
 
     #[test]
@@ -421,4 +744,284 @@ pub(crate) mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_mock_prove() {
+        // Define a circuit that asserts its two inputs are equal.
+        let mut builder = DefaultBuilder::new();
+        let a = builder.read::<Variable>();
+        let b = builder.read::<Variable>();
+        builder.assert_is_equal(a, b);
+        let c = builder.mul(a, b);
+        builder.write(c);
+
+        let circuit = builder.build();
+
+        // Satisfying inputs pass and produce the output.
+        let mut input = circuit.input();
+        input.write::<Variable>(GoldilocksField::TWO);
+        input.write::<Variable>(GoldilocksField::TWO);
+        let mut output = circuit.mock_prove(&input).unwrap();
+        assert_eq!(
+            output.read::<Variable>(),
+            GoldilocksField::from_canonical_u8(4)
+        );
+
+        // The violated equality is reported.
+        let mut input = circuit.input();
+        input.write::<Variable>(GoldilocksField::TWO);
+        input.write::<Variable>(GoldilocksField::ONE);
+        let err = circuit.mock_prove(&input).unwrap_err();
+        assert!(err.to_string().contains("copy constraint is not satisfied"));
+    }
+
+    #[test]
+    fn test_dump_constraints() {
+        let mut builder = DefaultBuilder::new();
+        let a = builder.read::<Variable>();
+        let b = builder.read::<Variable>();
+        let c = builder.mul(a, b);
+        builder.write(c);
+        let circuit = builder.build();
+
+        let dump = circuit.dump_constraints();
+        assert!(dump.starts_with("degree: "));
+        assert!(dump.contains("ArithmeticGate"));
+        assert!(dump.contains("PublicInputGate"));
+
+        // The multiplication is an arithmetic gate row with constants (1, 0) whose wires are
+        // copy constrained to the inputs and output.
+        assert!(dump.lines().any(|line| line.contains(": ArithmeticGate")
+            && line.contains("constants=[1, 0]")
+            && !line.contains("copies=[]")));
+    }
+
+    #[test]
+    fn test_diff() {
+        let define = |builder: &mut DefaultBuilder| {
+            let a = builder.read::<Variable>();
+            let b = builder.read::<Variable>();
+            let c = builder.add(a, b);
+            builder.write(c);
+        };
+        let mut builder = DefaultBuilder::new();
+        define(&mut builder);
+        let circuit = builder.build();
+
+        // The same circuit with a random access, which adds a gate type and a public input.
+        let mut builder = DefaultBuilder::new();
+        define(&mut builder);
+        let index = builder.read::<Variable>();
+        let a = builder.constant::<Variable>(GoldilocksField::ONE);
+        let b = builder.constant::<Variable>(GoldilocksField::TWO);
+        let selected = Variable(builder.api.random_access(index.0, vec![a.0, b.0]));
+        builder.write(selected);
+        let new_circuit = builder.build();
+
+        assert!(circuit.diff(&circuit).is_empty());
+
+        let diff = circuit.diff(&new_circuit);
+        assert!(diff
+            .added_gates
+            .keys()
+            .any(|gate| gate.starts_with("RandomAccessGate")));
+        assert!(diff.removed_gates.is_empty());
+
+        // The new input shifts the offset of the existing output, which is reported as moved.
+        assert_eq!(diff.added_public_inputs.len(), 3);
+        assert_eq!(diff.removed_public_inputs.len(), 1);
+        assert!(diff.to_string().contains("+ RandomAccessGate"));
+    }
+
+    #[test]
+    fn test_required_chains() {
+        let mut builder = DefaultBuilder::new();
+        let block_hash = builder.read::<Bytes32Variable>();
+        let address = builder.read::<AddressVariable>();
+        let storage_key = builder.read::<Bytes32Variable>();
+
+        // Read the same slot from mainnet and from an L2.
+        builder.chain_id = Some(10);
+        let l2_value = builder.eth_get_storage_at_witness(block_hash, address, storage_key);
+        builder.write(l2_value);
+        builder.chain_id = Some(1);
+        let l1_value = builder.eth_get_storage_at_witness(block_hash, address, storage_key);
+        builder.write(l1_value);
+
+        // The generators that query the chain through ethers are reported too.
+        builder.chain_id = Some(137);
+        let header = builder.eth_get_block_by_hash_witness(block_hash);
+        builder.write(header.parent_hash);
+        builder.chain_id = Some(5);
+        let log = builder.eth_get_transaction_log_witness(storage_key, block_hash, 0);
+        builder.write(log.address);
+
+        let circuit = builder.build();
+        assert_eq!(circuit.required_chains(), vec![1, 5, 10, 137]);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_required_chains_storage_proof() {
+        let mut builder = DefaultBuilder::new();
+        let block_hash = builder.read::<Bytes32Variable>();
+        let address = builder.read::<AddressVariable>();
+        let storage_key = builder.read::<Bytes32Variable>();
+
+        // A storage value proven against the block on an L2, next to an unproven witness read
+        // from mainnet through ethers.
+        builder.chain_id = Some(10);
+        let value = builder.eth_get_storage_at(block_hash, address, storage_key);
+        builder.write(value);
+        builder.chain_id = Some(1);
+        let generator =
+            EthStorageProofGenerator::new(&mut builder, block_hash, address, storage_key);
+        builder.add_simple_generator(generator.clone());
+        builder.write(generator.value);
+
+        let circuit = builder.build();
+        assert_eq!(circuit.required_chains(), vec![1, 10]);
+
+        // The chains are still reported once the circuit is loaded from disk.
+        let gate_serializer = GateRegistry::<L, D>::new();
+        let hint_serializer = HintRegistry::<L, D>::new();
+        let bytes = circuit
+            .serialize(&gate_serializer, &hint_serializer)
+            .unwrap();
+        let circuit =
+            CircuitBuild::<L, D>::deserialize(&bytes, &gate_serializer, &hint_serializer).unwrap();
+        assert_eq!(circuit.required_chains(), vec![1, 10]);
+    }
+
+    /// Adds a random offset to its input, like a hint whose RPC response changes over time.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct RandomOffsetHint;
+
+    #[async_trait]
+    impl<L: PlonkParameters<D>, const D: usize> AsyncHint<L, D> for RandomOffsetHint {
+        async fn hint(
+            &self,
+            input_stream: &mut ValueStream<L, D>,
+            output_stream: &mut ValueStream<L, D>,
+        ) {
+            let value = input_stream.read_value::<U64Variable>();
+            let offset = thread_rng().gen::<u32>() as u64;
+            output_stream.write_value::<U64Variable>(value + offset);
+        }
+    }
+
+    #[test]
+    fn test_prove_with_recording() {
+        let mut builder = DefaultBuilder::new();
+        let a = builder.read::<U64Variable>();
+        let b = builder.read::<U64Variable>();
+        for value in [a, b] {
+            let mut input_stream = VariableStream::new();
+            input_stream.write(&value);
+            let output_stream = builder.async_hint(input_stream, RandomOffsetHint);
+            let shifted = output_stream.read::<U64Variable>(&mut builder);
+            builder.write(shifted);
+        }
+        let circuit = builder.build();
+
+        let mut input = circuit.input();
+        input.write::<U64Variable>(1);
+        input.write::<U64Variable>(2);
+
+        let path = env::temp_dir().join(format!("plonky2x-recording-{}.json", process::id()));
+        let (proof, output) = circuit.prove_with_recording(&input, &path).unwrap();
+        circuit.verify(&proof, &input, &output);
+        let recording = HintRecording::load(&path).unwrap();
+        assert_eq!(recording.responses.len(), 2);
+
+        // Replaying the recording gives the same witness, so the same public inputs and trace.
+        let (replayed_proof, replayed_output) = circuit.prove_with_replay(&input, &path).unwrap();
+        circuit.verify(&replayed_proof, &input, &replayed_output);
+        assert_eq!(replayed_proof.public_inputs, proof.public_inputs);
+        assert_eq!(replayed_proof.proof.wires_cap, proof.proof.wires_cap);
+
+        // The witness generated while recording matches the one replayed from the recording.
+        let generate = |recorder: HintRecorder| {
+            let mut pw = PartialWitness::new();
+            circuit.io.set_witness(&mut pw, &input);
+            generate_witness_with_recorder(
+                pw,
+                &circuit.data.prover_only,
+                &circuit.data.common,
+                &circuit.async_hints,
+                Some(recorder),
+            )
+            .unwrap()
+            .full_witness()
+        };
+        let recorder = HintRecorder::record();
+        let witness = generate(recorder.clone());
+        let replayed_witness = generate(HintRecorder::replay(recorder.recording()));
+        let common = &circuit.data.common;
+        for row in 0..common.degree() {
+            for column in 0..common.config.num_wires {
+                assert_eq!(
+                    witness.get_wire(row, column),
+                    replayed_witness.get_wire(row, column)
+                );
+            }
+        }
+
+        // Replaying fails when a response is missing from the recording.
+        let mut input = circuit.input();
+        input.write::<U64Variable>(3);
+        input.write::<U64Variable>(2);
+        assert!(circuit.prove_with_replay(&input, &path).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_prove_with_replay_without_rpc() {
+        dotenv::dotenv().ok();
+        // Query mainnet under an unused chain id, so that its RPC can be removed before replaying
+        // without affecting the other tests.
+        let chain_id = 1_000_001;
+        let rpc_var = format!("RPC_{}", chain_id);
+        env::set_var(&rpc_var, env::var("RPC_1").unwrap());
+
+        // An asynchronous hint next to the ethers generators, which run synchronously.
+        let mut builder = DefaultBuilder::new();
+        builder.chain_id = Some(chain_id);
+        let block_hash = builder.read::<Bytes32Variable>();
+        let address = builder.read::<AddressVariable>();
+        let location = builder.read::<Bytes32Variable>();
+        let value = builder.eth_get_storage_at_witness(block_hash, address, location);
+        builder.write(value);
+        let header = builder.eth_get_block_by_hash_witness(block_hash);
+        builder.write(header.state_root);
+        let generator = EthStorageProofGenerator::new(&mut builder, block_hash, address, location);
+        builder.add_simple_generator(generator.clone());
+        builder.write(generator.value);
+        let circuit = builder.build();
+
+        // A storage slot at Ethereum block https://etherscan.io/block/17880427
+        let mut input = circuit.input();
+        input.write::<Bytes32Variable>(bytes32!(
+            "0x281dc31bb78779a1ede7bf0f4d2bc5f07ddebc9f9d1155e413d8804384604bbe"
+        ));
+        input.write::<AddressVariable>(address!("0x55032650b14df07b85bF18A3a3eC8E0Af2e028d5"));
+        input.write::<Bytes32Variable>(bytes32!(
+            "0xad3228b676f7d3cd4284a5443f17f1962b36e491b30a40b2405849e597ba5fb5"
+        ));
+
+        let path = env::temp_dir().join(format!("plonky2x-rpc-recording-{}.json", process::id()));
+        let (proof, output) = circuit.prove_with_recording(&input, &path).unwrap();
+        circuit.verify(&proof, &input, &output);
+        let recording = HintRecording::load(&path).unwrap();
+        assert_eq!(recording.responses.len(), 1);
+        assert_eq!(recording.generator_responses.len(), 2);
+
+        // Without an RPC for the chain, running any of the hints or generators would panic.
+        env::remove_var(&rpc_var);
+        let (replayed_proof, replayed_output) = circuit.prove_with_replay(&input, &path).unwrap();
+        circuit.verify(&replayed_proof, &input, &replayed_output);
+        assert_eq!(replayed_proof.public_inputs, proof.public_inputs);
+        fs::remove_file(&path).unwrap();
+    }
 }
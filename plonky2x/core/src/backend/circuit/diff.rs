@@ -0,0 +1,111 @@
+use alloc::collections::BTreeMap;
+use core::fmt;
+
+use crate::frontend::builder::{PublicInputEntry, PublicInputsLayout};
+
+/// The structural differences between two circuits, used to review how a change to a gadget
+/// affects the circuits that use it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CircuitDiff {
+    /// Gate types only used by the new circuit, with their number of rows.
+    pub added_gates: BTreeMap<String, usize>,
+    /// Gate types only used by the old circuit, with their number of rows.
+    pub removed_gates: BTreeMap<String, usize>,
+    /// Gate types used by both circuits with a different number of rows, as `(old, new)`.
+    pub changed_gates: BTreeMap<String, (usize, usize)>,
+    /// Public inputs only in the layout of the new circuit.
+    pub added_public_inputs: Vec<PublicInputEntry>,
+    /// Public inputs only in the layout of the old circuit.
+    pub removed_public_inputs: Vec<PublicInputEntry>,
+}
+
+impl CircuitDiff {
+    /// Compares the gate counts and public inputs layouts of an old and a new circuit.
+    pub fn new(
+        old_gates: &BTreeMap<String, usize>,
+        new_gates: &BTreeMap<String, usize>,
+        old_public_inputs: &PublicInputsLayout,
+        new_public_inputs: &PublicInputsLayout,
+    ) -> Self {
+        let mut diff = Self::default();
+        for (gate, old_count) in old_gates.iter() {
+            match new_gates.get(gate) {
+                None => {
+                    diff.removed_gates.insert(gate.clone(), *old_count);
+                }
+                Some(new_count) if new_count != old_count => {
+                    diff.changed_gates
+                        .insert(gate.clone(), (*old_count, *new_count));
+                }
+                Some(_) => {}
+            }
+        }
+        for (gate, new_count) in new_gates.iter() {
+            if !old_gates.contains_key(gate) {
+                diff.added_gates.insert(gate.clone(), *new_count);
+            }
+        }
+
+        // An entry that moved to a different offset is reported as removed and added.
+        diff.added_public_inputs = new_public_inputs
+            .entries
+            .iter()
+            .filter(|entry| !old_public_inputs.entries.contains(entry))
+            .cloned()
+            .collect();
+        diff.removed_public_inputs = old_public_inputs
+            .entries
+            .iter()
+            .filter(|entry| !new_public_inputs.entries.contains(entry))
+            .cloned()
+            .collect();
+        diff
+    }
+
+    /// Returns true if the circuits have the same gate counts and public inputs layout.
+    pub fn is_empty(&self) -> bool {
+        self.added_gates.is_empty()
+            && self.removed_gates.is_empty()
+            && self.changed_gates.is_empty()
+            && self.added_public_inputs.is_empty()
+            && self.removed_public_inputs.is_empty()
+    }
+}
+
+impl fmt::Display for CircuitDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "circuits are structurally identical");
+        }
+        for (gate, count) in self.added_gates.iter() {
+            writeln!(f, "+ {} x{}", gate, count)?;
+        }
+        for (gate, count) in self.removed_gates.iter() {
+            writeln!(f, "- {} x{}", gate, count)?;
+        }
+        for (gate, (old_count, new_count)) in self.changed_gates.iter() {
+            writeln!(f, "~ {} x{} -> x{}", gate, old_count, new_count)?;
+        }
+        for entry in self.added_public_inputs.iter() {
+            writeln!(
+                f,
+                "+ public input {}: {} at {}..{}",
+                entry.name,
+                entry.ty,
+                entry.offset,
+                entry.offset + entry.len
+            )?;
+        }
+        for entry in self.removed_public_inputs.iter() {
+            writeln!(
+                f,
+                "- public input {}: {} at {}..{}",
+                entry.name,
+                entry.ty,
+                entry.offset,
+                entry.offset + entry.len
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use itertools::Itertools;
+use plonky2::field::types::Field;
 use plonky2::plonk::circuit_data::VerifierCircuitData;
 use plonky2::plonk::proof::ProofWithPublicInputs;
 use serde::{Deserialize, Serialize};
@@ -7,7 +8,7 @@ use super::PlonkParameters;
 use crate::backend::prover::ProofId;
 use crate::frontend::builder::CircuitIO;
 use crate::frontend::vars::{EvmVariable, ValueStream};
-use crate::prelude::{ByteVariable, CircuitVariable};
+use crate::prelude::{ByteVariable, CircuitVariable, Variable};
 
 /// Public inputs to the circuit. In the form of bytes, field elements, or recursive proofs.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -87,6 +88,15 @@ impl<L: PlonkParameters<D>, const D: usize> PublicInput<L, D> {
         };
     }
 
+    /// Writes an array to the public circuit input using field-based serialization, prefixed by
+    /// its length.
+    pub fn write_array<V: CircuitVariable>(&mut self, values: Vec<V::ValueType<L::Field>>) {
+        self.write::<Variable>(L::Field::from_canonical_usize(values.len()));
+        for value in values {
+            self.write::<V>(value);
+        }
+    }
+
     /// Writes a slice of field elements to the public circuit input.
     pub fn write_all(&mut self, value: &[L::Field]) {
         match self {
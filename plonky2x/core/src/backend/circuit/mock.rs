@@ -4,6 +4,9 @@ use std::collections::HashMap;
 use plonky2::iop::witness::{PartialWitness, PartitionWitness};
 use plonky2::plonk::circuit_data::MockCircuitData;
 use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
+use plonky2::util::serialization::{
+    GateSerializer, IoError, IoResult, WitnessGeneratorSerializer, Write,
+};
 
 use super::input::PublicInput;
 use super::output::PublicOutput;
@@ -11,6 +14,9 @@ use super::witness::generate_witness;
 use super::PlonkParameters;
 use crate::frontend::builder::CircuitIO;
 use crate::frontend::hint::asynchronous::generator::AsyncHintDataRef;
+use crate::utils::hash::sha256;
+use crate::utils::hex;
+use crate::utils::serde::BufferWrite;
 
 /// A mock circuit that can be used for testing.
 ///
@@ -19,6 +25,8 @@ use crate::frontend::hint::asynchronous::generator::AsyncHintDataRef;
 #[derive(Debug)]
 pub struct MockCircuitBuild<L: PlonkParameters<D>, const D: usize> {
     pub data: MockCircuitData<L::Field, L::Config, D>,
+    /// The number of gates of the circuit before padding to a power of two.
+    pub num_gates: usize,
     pub io: CircuitIO<D>,
     pub debug_variables: HashMap<usize, String>,
     pub async_hints: BTreeMap<usize, AsyncHintDataRef<L, D>>,
@@ -59,6 +67,30 @@ impl<L: PlonkParameters<D>, const D: usize> MockCircuitBuild<L, D> {
 
         (witness, output)
     }
+
+    /// Returns a digest of the structure of the circuit: its common data, witness generators,
+    /// public inputs, copy constraints and io, as written by the circuit serializers. Unlike the
+    /// circuit digest, it is known without computing the proving key, so it can be used to look up
+    /// a previously built circuit.
+    pub fn structure_digest(
+        &self,
+        gate_serializer: &impl GateSerializer<L::Field, D>,
+        hint_serializer: &impl WitnessGeneratorSerializer<L::Field, D>,
+    ) -> IoResult<String> {
+        let common = &self.data.common;
+        let prover_only = &self.data.prover_only;
+
+        let mut bytes = Vec::new();
+        bytes.write_bytes(&common.to_bytes(gate_serializer)?)?;
+        bytes.write_bytes(&bincode::serialize(&self.io).map_err(|_| IoError)?)?;
+        bytes.write_usize(prover_only.generators.len())?;
+        for generator in prover_only.generators.iter() {
+            hint_serializer.write_generator(&mut bytes, generator, common)?;
+        }
+        bytes.write_target_vec(&prover_only.public_inputs)?;
+        bytes.write_usize_vec(&prover_only.representative_map)?;
+        Ok(hex!(sha256(&bytes)))
+    }
 }
 
 #[cfg(test)]
@@ -122,4 +154,25 @@ pub(crate) mod tests {
         let xor = output.evm_read::<ByteVariable>();
         debug!("{}", xor);
     }
+
+    #[test]
+    fn test_structure_digest() {
+        let gate_serializer = GateRegistry::new();
+        let hint_serializer = HintRegistry::new();
+        let digest = |constant: u64| {
+            let mut builder = DefaultBuilder::new();
+            let a = builder.read::<Variable>();
+            let b = builder.constant::<Variable>(GoldilocksField::from_canonical_u64(constant));
+            let c = builder.add(a, b);
+            builder.write(c);
+            builder
+                .mock_build()
+                .structure_digest(&gate_serializer, &hint_serializer)
+                .unwrap()
+        };
+
+        // The same definition gives the same digest, and changing a constant changes it.
+        assert_eq!(digest(1), digest(1));
+        assert_ne!(digest(1), digest(2));
+    }
 }
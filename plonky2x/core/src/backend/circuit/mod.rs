@@ -1,5 +1,6 @@
 mod build;
 pub mod config;
+mod diff;
 mod input;
 mod mock;
 mod output;
@@ -12,13 +13,15 @@ use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
 
 pub use self::build::CircuitBuild;
 pub use self::config::{DefaultParameters, Groth16WrapperParameters, PlonkParameters};
+pub use self::diff::CircuitDiff;
 pub use self::input::PublicInput;
 pub use self::mock::MockCircuitBuild;
 pub use self::output::PublicOutput;
 pub use self::serialization::{
     CircuitSerializer, DefaultSerializer, GateRegistry, HintRegistry, Serializer,
 };
-pub use self::witness::{generate_witness, generate_witness_async};
+pub use self::witness::{generate_witness, generate_witness_async, generate_witness_with_recorder};
+use crate::frontend::builder::PublicInputsLayout;
 use crate::prelude::CircuitBuilder;
 
 pub trait Circuit: Debug + Clone + Send + Sync + 'static {
@@ -44,6 +47,16 @@ pub trait Circuit: Debug + Clone + Send + Sync + 'static {
     {
     }
 
+    /// Returns the layout of the circuit's public inputs without building the circuit.
+    fn public_inputs_layout<L: PlonkParameters<D>, const D: usize>() -> PublicInputsLayout
+    where
+        <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher: AlgebraicHasher<L::Field>,
+    {
+        let mut builder = CircuitBuilder::<L, D>::new();
+        Self::define(&mut builder);
+        builder.public_inputs_layout()
+    }
+
     // Tests that the circuit can be serialized and deserialized.
     fn test_serialization<L: PlonkParameters<D>, const D: usize>()
     where
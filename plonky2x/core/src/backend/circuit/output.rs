@@ -1,4 +1,5 @@
 use itertools::Itertools;
+use plonky2::field::types::PrimeField64;
 use plonky2::iop::witness::PartitionWitness;
 use plonky2::plonk::proof::ProofWithPublicInputs;
 use serde::{Deserialize, Serialize};
@@ -6,7 +7,7 @@ use serde::{Deserialize, Serialize};
 use super::PlonkParameters;
 use crate::frontend::builder::CircuitIO;
 use crate::frontend::vars::{EvmVariable, ValueStream};
-use crate::prelude::{ByteVariable, CircuitVariable};
+use crate::prelude::{ByteVariable, CircuitVariable, Variable};
 
 /// An output from the circuit. Can either be in the form of bytes, field elements, or proofs.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -79,6 +80,21 @@ impl<L: PlonkParameters<D>, const D: usize> PublicOutput<L, D> {
         }
     }
 
+    /// Reads a length-prefixed array from the public circuit output using field-based
+    /// serialization.
+    pub fn read_array<V: CircuitVariable>(&mut self) -> Vec<V::ValueType<L::Field>> {
+        let len = self.read::<Variable>().to_canonical_u64() as usize;
+        match self {
+            PublicOutput::Elements(output) => assert!(
+                output.len() >= len * V::nb_elements(),
+                "output has fewer elements than an array of length {}",
+                len
+            ),
+            _ => panic!("field io is not enabled"),
+        }
+        (0..len).map(|_| self.read::<V>()).collect()
+    }
+
     /// Reads the entire stream of field elements from the public circuit output.
     pub fn read_all(&self) -> Vec<L::Field> {
         match self {
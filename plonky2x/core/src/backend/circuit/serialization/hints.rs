@@ -1,5 +1,6 @@
 use core::fmt::Debug;
 use core::marker::PhantomData;
+use std::collections::HashSet;
 
 use plonky2::field::extension::Extendable;
 use plonky2::gadgets::arithmetic::EqualityGenerator;
@@ -29,6 +30,8 @@ use plonky2::plonk::circuit_data::CommonCircuitData;
 use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
 use plonky2::recursion::dummy_circuit::DummyProofGenerator;
 use plonky2::util::serialization::{Buffer, IoResult, Read, WitnessGeneratorSerializer, Write};
+use starkyx::chip::ec::edwards::ed25519::params::Ed25519BaseField;
+use starkyx::chip::ec::weierstrass::bn254::Bn254BaseField;
 use starkyx::machine::hash::blake::blake2b::BLAKE2B;
 use starkyx::machine::hash::sha::sha256::SHA256;
 use starkyx::machine::hash::sha::sha512::SHA512;
@@ -38,14 +41,18 @@ use starkyx::plonky2::cubic::mul_gate::MulCubicGenerator;
 use super::registry::{SerializationRegistry, Serializer};
 use super::PlonkParameters;
 use crate as plonky2x;
+use crate::frontend::curta::field::parameters::Secp256k1BaseField;
+use crate::frontend::curta::field::reduce::FieldReduceBatchHint;
+use crate::frontend::ecc::bls12_381::g1::{Bls12381FpDivHint, Bls12381G1DecompressHint};
 use crate::frontend::ecc::curve25519::curta::proof_hint::EcOpProofHint;
 use crate::frontend::ecc::curve25519::curta::result_hint::EcOpResultHint;
 use crate::frontend::eth::beacon::generators::{
     BeaconAllWithdrawalsHint, BeaconBalanceBatchWitnessHint, BeaconBalanceGenerator,
     BeaconBalanceWitnessHint, BeaconBalancesGenerator, BeaconBlockRootsHint, BeaconGraffitiHint,
     BeaconHeaderHint, BeaconHeadersFromOffsetRangeHint, BeaconHistoricalBlockHint,
-    BeaconPartialBalancesHint, BeaconPartialValidatorsHint, BeaconValidatorBatchHint,
-    BeaconValidatorGenerator, BeaconValidatorsGenerator, BeaconValidatorsHint,
+    BeaconPartialBalancesHint, BeaconPartialValidatorsHint, BeaconStateFieldHint,
+    BeaconSyncCommitteeMembershipHint, BeaconTotalBalanceGenerator, BeaconValidatorBatchHint,
+    BeaconValidatorGenerator, BeaconValidatorHint, BeaconValidatorsGenerator, BeaconValidatorsHint,
     BeaconWithdrawalGenerator, BeaconWithdrawalsGenerator, CompressedBeaconValidatorBatchHint,
 };
 use crate::frontend::eth::beacon::vars::{
@@ -53,7 +60,8 @@ use crate::frontend::eth::beacon::vars::{
     BeaconValidatorsVariable, BeaconWithdrawalVariable, BeaconWithdrawalsVariable,
 };
 use crate::frontend::eth::storage::generators::{
-    EthBlockGenerator, EthLogGenerator, EthStorageKeyGenerator, EthStorageProofHint,
+    EthBlockGenerator, EthHeaderHint, EthLogGenerator, EthReceiptHint, EthStorageKeyGenerator,
+    EthStorageMptProofHint, EthStorageProofGenerator, EthStorageProofHint, EthTransactionHint,
 };
 use crate::frontend::hash::curta::digest_hint::HashDigestHint;
 use crate::frontend::hash::curta::proof_hint::HashProofHint;
@@ -65,6 +73,7 @@ use crate::frontend::hint::asynchronous::serializer::AsyncHintSerializer;
 use crate::frontend::hint::simple::hint::Hint;
 use crate::frontend::hint::simple::serializer::SimpleHintSerializer;
 use crate::frontend::hint::synchronous::Async;
+use crate::frontend::uint::modular::U256ModInverseHint;
 use crate::frontend::uint::num::biguint::BigUintDivRemGenerator;
 use crate::frontend::uint::num::u32::gates::add_many_u32::U32AddManyGenerator;
 use crate::frontend::uint::num::u32::gates::arithmetic_u32::U32ArithmeticGenerator;
@@ -373,7 +382,13 @@ where
         r.register_async_hint::<BeaconAllWithdrawalsHint>();
         r.register_async_hint::<BeaconHeaderHint>();
         r.register_async_hint::<BeaconHistoricalBlockHint>();
+        r.register_async_hint::<BeaconStateFieldHint>();
+        r.register_async_hint::<BeaconSyncCommitteeMembershipHint>();
         r.register_async_hint::<EthStorageProofHint<L, D>>();
+        r.register_async_hint::<EthStorageMptProofHint<L, D>>();
+        r.register_async_hint::<EthReceiptHint<L, D>>();
+        r.register_async_hint::<EthHeaderHint<L, D>>();
+        r.register_async_hint::<EthTransactionHint<L, D>>();
         r.register_async_hint::<BeaconValidatorsHint>();
 
         register_powers_of_two!(r, BeaconBalanceBatchWitnessHint);
@@ -381,6 +396,7 @@ where
         register_powers_of_two!(r, CompressedBeaconValidatorBatchHint);
 
         register_powers_of_two_async!(r, BeaconPartialBalancesHint);
+        register_powers_of_two_async!(r, BeaconTotalBalanceGenerator);
         register_powers_of_two_async!(r, BeaconPartialValidatorsHint);
 
         let id = U32RangeCheckGenerator::<L::Field, D>::id();
@@ -398,6 +414,10 @@ where
 
         r.register_hint::<BeaconGraffitiHint>();
 
+        r.register_hint::<FieldReduceBatchHint<Bn254BaseField>>();
+        r.register_hint::<FieldReduceBatchHint<Ed25519BaseField>>();
+        r.register_hint::<FieldReduceBatchHint<Secp256k1BaseField>>();
+
         r.register_hint::<HashDigestHint<SHA256, 64, false, 8>>();
         r.register_async_hint::<Async<HashDigestHint<SHA256, 64, false, 8>>>();
 
@@ -422,6 +442,11 @@ where
         r.register_hint::<EcOpResultHint>();
         r.register_async_hint::<Async<EcOpResultHint>>();
 
+        r.register_hint::<Bls12381FpDivHint>();
+        r.register_hint::<Bls12381G1DecompressHint>();
+
+        r.register_hint::<U256ModInverseHint>();
+
         let dummy_proof_generator_id =
             DummyProofGenerator::<L::Field, L::Config, D>::default().id();
         r.register_simple::<DummyProofGenerator<L::Field, L::Config, D>>(dummy_proof_generator_id);
@@ -452,6 +477,72 @@ where
     }
 }
 
+/// A registry of the synchronous generators and hints that fetch external data, such as the ethers
+/// and beacon generators, so that they are recorded and replayed like the asynchronous hints.
+///
+/// Generators are identified by their id without generic arguments, so that a hint is covered for
+/// every batch size.
+#[derive(Debug, Clone)]
+pub struct FetchingGeneratorRegistry<L: PlonkParameters<D>, const D: usize> {
+    ids: HashSet<String>,
+    _marker: PhantomData<L>,
+}
+
+impl<L: PlonkParameters<D>, const D: usize> FetchingGeneratorRegistry<L, D> {
+    /// Registers a simple generator with the given id.
+    pub fn register_simple(&mut self, id: String) {
+        self.ids.insert(strip_generic_arguments(&id).to_string());
+    }
+
+    /// Registers a hint.
+    pub fn register_hint<H: Hint<L, D>>(&mut self) {
+        self.ids
+            .insert(strip_generic_arguments(&H::id()).to_string());
+    }
+
+    /// Returns whether the generator with the given id fetches external data.
+    pub fn contains(&self, id: &str) -> bool {
+        self.ids.contains(strip_generic_arguments(id))
+    }
+
+    /// Creates a new registry with all the generators of Plonky2x that fetch external data. A new
+    /// generator that calls `get_rpc_client` or creates a `BeaconClient` must be registered here.
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        let mut r = Self {
+            ids: HashSet::new(),
+            _marker: PhantomData,
+        };
+
+        r.register_simple(EthBlockGenerator::<L, D>::id());
+        r.register_simple(EthLogGenerator::<L, D>::id());
+        r.register_simple(EthStorageProofGenerator::<L, D>::id());
+
+        r.register_simple(BeaconBalanceGenerator::<L, D>::id());
+        r.register_simple(BeaconBalancesGenerator::<L, D>::id());
+        r.register_simple(BeaconValidatorGenerator::<L, D>::id());
+        r.register_simple(BeaconValidatorsGenerator::<L, D>::id());
+        r.register_simple(BeaconWithdrawalGenerator::<L, D>::id());
+        r.register_simple(BeaconWithdrawalsGenerator::<L, D>::id());
+
+        r.register_hint::<BeaconBalanceWitnessHint>();
+        r.register_hint::<BeaconBalanceBatchWitnessHint<1>>();
+        r.register_hint::<BeaconBlockRootsHint>();
+        r.register_hint::<BeaconGraffitiHint>();
+        r.register_hint::<BeaconHeadersFromOffsetRangeHint<1>>();
+        r.register_hint::<BeaconValidatorHint>();
+        r.register_hint::<BeaconValidatorBatchHint<1>>();
+        r.register_hint::<CompressedBeaconValidatorBatchHint<1>>();
+
+        r
+    }
+}
+
+/// Strips the generic arguments from a generator id, e.g. `Hint<2>` becomes `Hint`.
+fn strip_generic_arguments(id: &str) -> &str {
+    id.split('<').next().unwrap()
+}
+
 impl<L: PlonkParameters<D>, const D: usize> WitnessGeneratorSerializer<L::Field, D>
     for HintRegistry<L, D>
 {
@@ -534,12 +625,20 @@ impl<L: PlonkParameters<D>, const D: usize> HintSerializer<L, D> for HintRegistr
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
+    use std::path::Path;
+
     use plonky2::field::goldilocks_field::GoldilocksField;
     use plonky2::iop::generator::{ConstantGenerator, SimpleGenerator, WitnessGeneratorRef};
     use plonky2::util::serialization::{Buffer, WitnessGeneratorSerializer};
 
-    use crate::backend::circuit::serialization::hints::HintRegistry;
+    use crate::backend::circuit::serialization::hints::{FetchingGeneratorRegistry, HintRegistry};
     use crate::backend::circuit::DefaultParameters;
+    use crate::frontend::eth::beacon::generators::BeaconBalanceBatchWitnessHint;
+    use crate::frontend::eth::storage::generators::EthStorageProofHint;
+    use crate::frontend::hash::keccak::keccak256::Keccak256Generator;
+    use crate::frontend::hint::asynchronous::hint::AsyncHint;
+    use crate::frontend::hint::simple::hint::Hint;
     use crate::prelude::CircuitBuilder;
 
     type L = DefaultParameters;
@@ -564,4 +663,75 @@ mod tests {
         let read_generator = registry.read_generator(&mut buffer, &common_data).unwrap();
         assert_eq!(raw_generator, read_generator);
     }
+
+    #[test]
+    fn test_async_hint_serialization_layout() {
+        // Asynchronous hints are serialized with bincode, which cannot skip missing fields, so the
+        // RPC settings live in the shared clients rather than in the hints. A storage proof hint
+        // is still serialized as its chain id alone, so circuits saved before can be loaded.
+        let bytes = bincode::serialize(&10u64).unwrap();
+        let hint: EthStorageProofHint<L, D> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(AsyncHint::<L, D>::chain_id(&hint), Some(10));
+        assert_eq!(bincode::serialize(&hint).unwrap(), bytes);
+    }
+
+    /// Collects the types of the synchronous generators and hints in `dir` that create an RPC
+    /// client in the body of their implementation.
+    fn fetching_generator_types(dir: &Path, types: &mut Vec<String>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                fetching_generator_types(&path, types);
+                continue;
+            }
+            if path.extension().map_or(true, |extension| extension != "rs") {
+                continue;
+            }
+            let source = fs::read_to_string(&path).unwrap();
+            for block in source.split("\nimpl").skip(1) {
+                // The header of a top-level impl block, and its body up to the closing brace.
+                let Some((header, body)) = block.split_once('{') else {
+                    continue;
+                };
+                let body = body.split("\n}").next().unwrap();
+                let is_generator = header.contains("SimpleGenerator<")
+                    || (header.contains("Hint<L, D>") && !header.contains("AsyncHint<"));
+                if is_generator
+                    && (body.contains("get_rpc_client") || body.contains("BeaconClient"))
+                {
+                    let ty = header.rsplit(" for ").next().unwrap().trim_start();
+                    let ty = ty.split(|c: char| c == '<' || c.is_whitespace()).next();
+                    types.push(ty.unwrap().to_string());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fetching_generator_registry() {
+        let mut types = Vec::new();
+        fetching_generator_types(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("src"),
+            &mut types,
+        );
+        assert!(types.contains(&"EthBlockGenerator".to_string()));
+        assert!(types.contains(&"BeaconBalanceBatchWitnessHint".to_string()));
+
+        // Every generator that fetches data must be registered, or it would run while replaying.
+        let registry = FetchingGeneratorRegistry::<L, D>::new();
+        for ty in types {
+            assert!(
+                registry
+                    .ids
+                    .iter()
+                    .any(|id| id.rsplit("::").next() == Some(ty.as_str())),
+                "{} fetches external data but is not in the FetchingGeneratorRegistry",
+                ty
+            );
+        }
+
+        let id = <BeaconBalanceBatchWitnessHint<512> as Hint<L, D>>::id();
+        assert!(registry.contains(&id));
+        assert!(!registry.contains(&Keccak256Generator::<L, D>::id()));
+    }
 }
@@ -18,9 +18,11 @@ use starkyx::maybe_rayon::rayon;
 use tokio::sync::mpsc::unbounded_channel;
 use tokio::sync::oneshot;
 
+use super::serialization::hints::FetchingGeneratorRegistry;
 use super::PlonkParameters;
 use crate::frontend::hint::asynchronous::generator::{AsyncHintDataRef, AsyncHintRef, HintPoll};
 use crate::frontend::hint::asynchronous::handler::HintHandler;
+use crate::frontend::hint::asynchronous::recording::HintRecorder;
 
 /// Given a `PartialWitness` that has only inputs set, populates the rest of the witness using the
 /// given set of generators.
@@ -29,6 +31,18 @@ pub fn generate_witness<'a, L: PlonkParameters<D>, const D: usize>(
     prover_data: &'a ProverOnlyCircuitData<L::Field, L::Config, D>,
     common_data: &'a CommonCircuitData<L::Field, D>,
     async_generator_refs: &'a BTreeMap<usize, AsyncHintDataRef<L, D>>,
+) -> Result<PartitionWitness<'a, L::Field>> {
+    generate_witness_with_recorder(inputs, prover_data, common_data, async_generator_refs, None)
+}
+
+/// Like `generate_witness`, but records the responses of the asynchronous hints and of the
+/// synchronous generators that fetch external data, or replays them from a recording.
+pub fn generate_witness_with_recorder<'a, L: PlonkParameters<D>, const D: usize>(
+    inputs: PartialWitness<L::Field>,
+    prover_data: &'a ProverOnlyCircuitData<L::Field, L::Config, D>,
+    common_data: &'a CommonCircuitData<L::Field, D>,
+    async_generator_refs: &'a BTreeMap<usize, AsyncHintDataRef<L, D>>,
+    recorder: Option<HintRecorder>,
 ) -> Result<PartitionWitness<'a, L::Field>> {
     // If async hints are present, set up the a handler and initialize the generators with the
     // handler's communication channel.
@@ -38,7 +52,7 @@ pub fn generate_witness<'a, L: PlonkParameters<D>, const D: usize>(
         false => {
             let (tx, rx) = unbounded_channel();
             // Initialize the hint handler.
-            let mut hint_handler = HintHandler::<L, D>::new(rx);
+            let mut hint_handler = HintHandler::<L, D>::new(rx).with_recorder(recorder.clone());
 
             // Spawn a runtime and run the hint handler.
             let rt = tokio::runtime::Runtime::new().expect("Failed to create runtime");
@@ -63,6 +77,7 @@ pub fn generate_witness<'a, L: PlonkParameters<D>, const D: usize>(
         common_data,
         async_generators,
         rx_handler_error,
+        recorder,
     )
 }
 
@@ -105,6 +120,7 @@ pub async fn generate_witness_async<'a, L: PlonkParameters<D>, const D: usize>(
             common_data,
             async_generators,
             rx_handler_error,
+            None,
         )
     })
 }
@@ -116,6 +132,7 @@ fn fill_witness_values<'a, L: PlonkParameters<D>, const D: usize>(
     common_data: &'a CommonCircuitData<L::Field, D>,
     mut async_generators: BTreeMap<usize, AsyncHintRef<L, D>>,
     mut rx_handler_error: oneshot::Receiver<Error>,
+    recorder: Option<HintRecorder>,
 ) -> Result<PartitionWitness<'a, L::Field>> {
     let config = &common_data.config;
     let generators = &prover_data.generators;
@@ -129,6 +146,9 @@ fn fill_witness_values<'a, L: PlonkParameters<D>, const D: usize>(
     let mut generator_is_expired = vec![false; generators.len()];
     let mut remaining_generators = generators.len();
 
+    // The synchronous generators that fetch external data are recorded and replayed too.
+    let fetching_generators = FetchingGeneratorRegistry::<L, D>::new();
+
     let mut buffer = GeneratedValues::empty();
     let mut witness = PartitionWitness::new(
         config.num_wires,
@@ -166,7 +186,13 @@ fn fill_witness_values<'a, L: PlonkParameters<D>, const D: usize>(
                     }
                 }
             } else {
-                let finished = generators[generator_idx].0.run(&witness, &mut buffer);
+                let generator = &generators[generator_idx];
+                let finished = match &recorder {
+                    Some(recorder) if fetching_generators.contains(&generator.0.id()) => {
+                        recorder.run_generator::<L, D>(generator, &witness, &mut buffer)?
+                    }
+                    _ => generator.0.run(&witness, &mut buffer),
+                };
                 if finished {
                     generator_is_expired[generator_idx] = true;
                     remaining_generators -= 1;
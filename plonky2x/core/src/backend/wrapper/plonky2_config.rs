@@ -17,7 +17,7 @@ use crate::backend::wrapper::poseidon_bn128::{permution, GOLDILOCKS_ELEMENTS, RA
 use crate::backend::wrapper::utils::{Fr, FrRepr};
 
 /// Configuration using Poseidon BN128 over the Goldilocks field.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct PoseidonBN128GoldilocksConfig;
 impl GenericConfig<2> for PoseidonBN128GoldilocksConfig {
     type F = GoldilocksField;
@@ -1,19 +1,29 @@
 use std::fs::{self, File};
 use std::path::Path;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use log::{debug, info};
 use plonky2::iop::witness::{PartialWitness, WitnessWrite};
 use plonky2::plonk::circuit_data::{
-    CommonCircuitData, VerifierCircuitTarget, VerifierOnlyCircuitData,
+    CommonCircuitData, VerifierCircuitData, VerifierCircuitTarget, VerifierOnlyCircuitData,
 };
 use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
 use plonky2::plonk::proof::{ProofWithPublicInputs, ProofWithPublicInputsTarget};
+use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use crate::backend::circuit::{CircuitBuild, PlonkParameters};
+use crate::backend::circuit::{CircuitBuild, GateRegistry, PlonkParameters};
 use crate::frontend::builder::CircuitBuilder;
 use crate::frontend::vars::{ByteVariable, CircuitVariable, Variable};
+
+/// The files in which `WrappedOutput::save` stores the data read by the gnark verifier.
+const COMMON_DATA_FILE: &str = "common_circuit_data.json";
+const VERIFIER_DATA_FILE: &str = "verifier_only_circuit_data.json";
+
+/// The file in which `WrappedOutput::save` stores the common data in plonky2's binary format,
+/// since plonky2 cannot read it back from JSON.
+const COMMON_DATA_BINARY_FILE: &str = "common_circuit_data.bin";
+
 #[derive(Debug)]
 pub struct WrappedCircuit<
     InnerParameters: PlonkParameters<D>,
@@ -234,14 +244,29 @@ impl<L: PlonkParameters<D>, const D: usize> WrappedOutput<L, D> {
         if !path.as_ref().exists() {
             fs::create_dir_all(&path)?;
         }
-        let common_data_file = File::create(path.as_ref().join("common_circuit_data.json"))?;
+        let common_data_file = File::create(path.as_ref().join(COMMON_DATA_FILE))?;
         serde_json::to_writer(&common_data_file, &self.common_data)?;
-        info!("Succesfully wrote common circuit data to common_circuit_data.json");
+        info!(
+            "Succesfully wrote common circuit data to {}",
+            COMMON_DATA_FILE
+        );
+
+        let common_data_bytes = self
+            .common_data
+            .to_bytes(&GateRegistry::<L, D>::new())
+            .map_err(|_| anyhow!("failed to serialize common circuit data"))?;
+        fs::write(
+            path.as_ref().join(COMMON_DATA_BINARY_FILE),
+            common_data_bytes,
+        )?;
+        info!(
+            "Succesfully wrote common circuit data to {}",
+            COMMON_DATA_BINARY_FILE
+        );
 
-        let verifier_data_file =
-            File::create(path.as_ref().join("verifier_only_circuit_data.json"))?;
+        let verifier_data_file = File::create(path.as_ref().join(VERIFIER_DATA_FILE))?;
         serde_json::to_writer(&verifier_data_file, &self.verifier_data)?;
-        info!("Succesfully wrote verifier data to verifier_only_circuit_data.json");
+        info!("Succesfully wrote verifier data to {}", VERIFIER_DATA_FILE);
 
         let proof_file = File::create(path.as_ref().join("proof_with_public_inputs.json"))?;
         serde_json::to_writer(&proof_file, &self.proof)?;
@@ -251,8 +276,56 @@ impl<L: PlonkParameters<D>, const D: usize> WrappedOutput<L, D> {
     }
 }
 
+/// Loads the verifier key of a wrapper circuit from the directory a `WrappedOutput` was saved to,
+/// i.e. from the `verifier_only_circuit_data.json` that the gnark verifier proves against and
+/// whose circuit digest the on-chain verifier checks, so wrapped proofs can be verified without
+/// rebuilding the circuit.
+///
+/// The common data is read from its binary serialization, and checked to match the
+/// `common_circuit_data.json` that the gnark verifier is compiled from.
+pub fn load_wrapper_vk<L: PlonkParameters<D>, const D: usize, P: AsRef<Path>>(
+    path: P,
+) -> Result<VerifierCircuitData<L::Field, L::Config, D>>
+where
+    VerifierOnlyCircuitData<L::Config, D>: DeserializeOwned,
+{
+    let common_data_bytes = fs::read(path.as_ref().join(COMMON_DATA_BINARY_FILE))?;
+    let common = CommonCircuitData::from_bytes(common_data_bytes, &GateRegistry::<L, D>::new())
+        .map_err(|_| anyhow!("failed to deserialize common circuit data"))?;
+    let saved_common_data = fs::read_to_string(path.as_ref().join(COMMON_DATA_FILE))?;
+    if saved_common_data != serde_json::to_string(&common)? {
+        return Err(anyhow!(
+            "{} does not match {}",
+            COMMON_DATA_BINARY_FILE,
+            COMMON_DATA_FILE
+        ));
+    }
+
+    let verifier_data_file = File::open(path.as_ref().join(VERIFIER_DATA_FILE))?;
+    let verifier_only: VerifierOnlyCircuitData<L::Config, D> =
+        serde_json::from_reader(verifier_data_file)?;
+    Ok(VerifierCircuitData {
+        verifier_only,
+        common,
+    })
+}
+
+/// Verifies a wrapped proof against `vk`, checking that its public inputs are `public_inputs`.
+pub fn verify_wrapped<L: PlonkParameters<D>, const D: usize>(
+    vk: &VerifierCircuitData<L::Field, L::Config, D>,
+    proof: &ProofWithPublicInputs<L::Field, L::Config, D>,
+    public_inputs: &[L::Field],
+) -> Result<()> {
+    let mut proof = proof.clone();
+    proof.public_inputs = public_inputs.to_vec();
+    vk.verify(proof)
+}
+
 #[cfg(test)]
 mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::Field;
+
     use super::*;
     use crate::backend::circuit::{DefaultParameters, Groth16WrapperParameters};
     use crate::utils;
@@ -314,4 +387,58 @@ mod tests {
         let wrapped_proof = wrapped_circuit.prove(&proof).unwrap();
         wrapped_proof.save(path).unwrap();
     }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_verify_wrapped_with_loaded_vk() {
+        const D: usize = 2;
+        type InnerParameters = DefaultParameters;
+        type OuterParameters = Groth16WrapperParameters;
+
+        utils::setup_logger();
+
+        let mut builder = CircuitBuilder::<InnerParameters, D>::new();
+        let a = builder.evm_read::<ByteVariable>();
+        let b = builder.evm_read::<ByteVariable>();
+        let c = builder.xor(a, b);
+        builder.evm_write(c);
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.evm_write::<ByteVariable>(3u8);
+        input.evm_write::<ByteVariable>(5u8);
+        let (proof, _output) = circuit.prove(&input);
+
+        let wrapped_circuit = WrappedCircuit::<InnerParameters, OuterParameters, D>::build(circuit);
+        let wrapped_proof = wrapped_circuit.prove(&proof).unwrap();
+        let path = std::env::temp_dir().join(format!("plonky2x-wrapper-{}", std::process::id()));
+        wrapped_proof.save(&path).unwrap();
+        drop(wrapped_circuit);
+
+        // The key is loaded from the saved files alone.
+        let vk = load_wrapper_vk::<OuterParameters, D, _>(&path).unwrap();
+        assert_eq!(vk.verifier_only, wrapped_proof.verifier_data);
+        assert_eq!(vk.common, wrapped_proof.common_data);
+        let public_inputs = wrapped_proof.proof.public_inputs.clone();
+        verify_wrapped::<OuterParameters, D>(&vk, &wrapped_proof.proof, &public_inputs).unwrap();
+
+        // The public inputs are the truncated input and output hashes, so changing any byte of
+        // them must be rejected.
+        let mut tampered_inputs = public_inputs;
+        tampered_inputs[0] += GoldilocksField::ONE;
+        assert!(
+            verify_wrapped::<OuterParameters, D>(&vk, &wrapped_proof.proof, &tampered_inputs)
+                .is_err()
+        );
+
+        // A binary common data that differs from the JSON the gnark verifier reads is rejected.
+        let common_data_path = path.join(COMMON_DATA_FILE);
+        let mut common_data: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&common_data_path).unwrap()).unwrap();
+        common_data["num_public_inputs"] = (wrapped_proof.common_data.num_public_inputs + 1).into();
+        fs::write(&common_data_path, common_data.to_string()).unwrap();
+        assert!(load_wrapper_vk::<OuterParameters, D, _>(&path).is_err());
+
+        fs::remove_dir_all(path).unwrap();
+    }
 }
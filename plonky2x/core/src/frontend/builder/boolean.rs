@@ -17,6 +17,15 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         // "one" will be within boolean range.
         BoolVariable::from_variables_unsafe(&[one])
     }
+
+    /// Returns a constant boolean with the given value.
+    pub fn constant_bool(&mut self, value: bool) -> BoolVariable {
+        if value {
+            self._true()
+        } else {
+            self._false()
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,56 @@
+use super::CircuitBuilder;
+use crate::backend::circuit::PlonkParameters;
+
+/// An auto-incrementing index, e.g. to assign positions to leaves as they are added to a tree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Counter {
+    count: usize,
+}
+
+impl Counter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the next index and advances the counter.
+    pub fn increment(&mut self) -> usize {
+        let index = self.count;
+        self.count += 1;
+        index
+    }
+
+    /// The number of indices handed out so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Returns a sequence number that is unique within this builder, e.g. to tag watched
+    /// variables so that they can be told apart in the logs.
+    pub fn counter(&mut self) -> usize {
+        self.counter.increment()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::DefaultBuilder;
+
+    #[test]
+    fn test_counter() {
+        let mut counter = Counter::new();
+        assert_eq!(counter.count(), 0);
+        for i in 0..5 {
+            assert_eq!(counter.increment(), i);
+            assert_eq!(counter.count(), i + 1);
+        }
+
+        let mut builder = DefaultBuilder::new();
+        let first = builder.counter();
+        let second = builder.counter();
+        let third = builder.counter();
+        assert!(first < second && second < third);
+    }
+}
@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 use super::CircuitBuilder;
 use crate::backend::circuit::{PlonkParameters, PublicInput};
 use crate::frontend::vars::EvmVariable;
-use crate::prelude::{ByteVariable, CircuitVariable, Variable};
+use crate::prelude::{ArrayVariable, ByteVariable, CircuitVariable, Field, Variable};
 use crate::utils::serde::{
     deserialize_proof_with_pis_target_option, deserialize_proof_with_pis_target_vec,
     deserialize_verifier_circuit_target_option, serialize_proof_with_pis_target_option,
@@ -53,6 +53,55 @@ pub struct CyclicProofIO<const D: usize> {
     pub closed: bool,
 }
 
+/// Whether a public input entry is read or written by the circuit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PublicInputKind {
+    Input,
+    Output,
+}
+
+/// A single variable registered as a public input, located by its field element offset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicInputEntry {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub kind: PublicInputKind,
+    pub offset: usize,
+    pub len: usize,
+}
+
+/// An ordered description of the public inputs of a circuit, used to generate bindings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicInputsLayout {
+    pub entries: Vec<PublicInputEntry>,
+}
+
+impl PublicInputsLayout {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+/// Strips the module paths from a type name (e.g. `ArrayVariable<U64Variable, 4>`).
+fn short_type_name<V>() -> String {
+    let full = std::any::type_name::<V>();
+    let mut name = String::new();
+    let mut path = String::new();
+    for c in full.chars() {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            path.push(c);
+        } else {
+            name.push_str(path.rsplit("::").next().unwrap());
+            path.clear();
+            name.push(c);
+        }
+    }
+    name.push_str(path.rsplit("::").next().unwrap());
+    name
+}
+
 /// A schema for what the inputs and outputs are for a circuit.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(clippy::large_enum_variant)]
@@ -249,6 +298,52 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         }
     }
 
+    /// Records a variable in the public inputs layout. Offsets are relative to the start of the
+    /// inputs or outputs and are resolved in `public_inputs_layout`.
+    fn record_io<V>(&mut self, kind: PublicInputKind, len: usize) {
+        let (offset, count) = self
+            .io_layout
+            .iter()
+            .filter(|e| e.kind == kind)
+            .fold((0, 0), |(offset, count), e| (offset + e.len, count + 1));
+        let name = match kind {
+            PublicInputKind::Input => format!("input_{}", count),
+            PublicInputKind::Output => format!("output_{}", count),
+        };
+        self.io_layout.push(PublicInputEntry {
+            name,
+            ty: short_type_name::<V>(),
+            kind,
+            offset,
+            len,
+        });
+    }
+
+    /// Returns the layout of the public inputs registered so far.
+    pub fn public_inputs_layout(&self) -> PublicInputsLayout {
+        // Inputs are only registered as public inputs if they are not read from a proof.
+        let inputs_are_public = !matches!(self.io, CircuitIO::RecursiveProofs(_));
+        let nb_input_elements = self
+            .io_layout
+            .iter()
+            .filter(|e| e.kind == PublicInputKind::Input)
+            .map(|e| e.len)
+            .sum::<usize>();
+        let entries = self
+            .io_layout
+            .iter()
+            .filter(|e| inputs_are_public || e.kind == PublicInputKind::Output)
+            .map(|e| {
+                let mut entry = e.clone();
+                if inputs_are_public && entry.kind == PublicInputKind::Output {
+                    entry.offset += nb_input_elements;
+                }
+                entry
+            })
+            .collect();
+        PublicInputsLayout { entries }
+    }
+
     // @audit
     pub fn read<V: CircuitVariable>(&mut self) -> V {
         self.try_init_field_io();
@@ -259,9 +354,26 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
             CircuitIO::CyclicProof(ref mut io) => io.input.extend(variable.variables()),
             _ => panic!("field io is not enabled"),
         }
+        self.record_io::<V>(PublicInputKind::Input, V::nb_elements());
         variable
     }
 
+    /// Reads a variable and labels it with `name` in the public inputs layout.
+    pub fn read_named<V: CircuitVariable>(&mut self, name: &str) -> V {
+        let variable = self.read::<V>();
+        self.io_layout.last_mut().unwrap().name = name.to_string();
+        variable
+    }
+
+    /// Reads a length-prefixed array of `N` variables. The circuit checks that the length prefix
+    /// supplied with the input is `N`.
+    pub fn read_array<V: CircuitVariable, const N: usize>(&mut self) -> ArrayVariable<V, N> {
+        let len = self.read::<Variable>();
+        let expected_len = self.constant::<Variable>(L::Field::from_canonical_usize(N));
+        self.assert_is_equal(len, expected_len);
+        self.read::<ArrayVariable<V, N>>()
+    }
+
     // @audit
     pub fn evm_read<V: EvmVariable>(&mut self) -> V {
         self.try_init_evm_io();
@@ -275,6 +387,17 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
             CircuitIO::Bytes(ref mut io) => io.input.extend(bytes),
             _ => panic!("evm io is not enabled"),
         }
+        self.record_io::<V>(
+            PublicInputKind::Input,
+            nb_bytes * ByteVariable::nb_elements(),
+        );
+        variable
+    }
+
+    /// Reads an evm variable and labels it with `name` in the public inputs layout.
+    pub fn evm_read_named<V: EvmVariable>(&mut self, name: &str) -> V {
+        let variable = self.evm_read::<V>();
+        self.io_layout.last_mut().unwrap().name = name.to_string();
         variable
     }
 
@@ -316,16 +439,38 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
             CircuitIO::CyclicProof(ref mut io) => io.output.extend(variable.variables()),
             _ => panic!("field io is not enabled"),
         }
+        self.record_io::<V>(PublicInputKind::Output, V::nb_elements());
+    }
+
+    /// Writes a variable and labels it with `name` in the public inputs layout.
+    pub fn write_named<V: CircuitVariable>(&mut self, variable: V, name: &str) {
+        self.write(variable);
+        self.io_layout.last_mut().unwrap().name = name.to_string();
+    }
+
+    /// Writes an array of variables prefixed by its length.
+    pub fn write_array<V: CircuitVariable, const N: usize>(&mut self, array: ArrayVariable<V, N>) {
+        let len = self.constant::<Variable>(L::Field::from_canonical_usize(N));
+        self.write(len);
+        self.write(array);
     }
 
     // @audit
     pub fn evm_write<V: EvmVariable>(&mut self, variable: V) {
         self.try_init_evm_io();
         let bytes = variable.encode(self);
+        let len = bytes.len() * ByteVariable::nb_elements();
         match self.io {
             CircuitIO::Bytes(ref mut io) => io.output.extend(bytes),
             _ => panic!("evm io is not enabled"),
         }
+        self.record_io::<V>(PublicInputKind::Output, len);
+    }
+
+    /// Writes an evm variable and labels it with `name` in the public inputs layout.
+    pub fn evm_write_named<V: EvmVariable>(&mut self, variable: V, name: &str) {
+        self.evm_write(variable);
+        self.io_layout.last_mut().unwrap().name = name.to_string();
     }
 
     // @audit
@@ -335,5 +480,68 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
             CircuitIO::RecursiveProofs(ref mut io) => io.output.extend(variable.variables()),
             _ => panic!("proof io is not enabled"),
         }
+        self.record_io::<V>(PublicInputKind::Output, V::nb_elements());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::eth::vars::AddressVariable;
+    use crate::prelude::{DefaultBuilder, GoldilocksField, U256Variable, U64Variable};
+
+    #[test]
+    fn test_public_inputs_layout() {
+        let mut builder = DefaultBuilder::new();
+        let _ = builder.evm_read_named::<AddressVariable>("owner");
+        let balance = builder.evm_read::<U256Variable>();
+        builder.evm_write_named(balance, "balance");
+
+        let layout = builder.public_inputs_layout();
+        let expected = vec![
+            ("owner", "AddressVariable", PublicInputKind::Input, 0, 160),
+            ("input_1", "U256Variable", PublicInputKind::Input, 160, 256),
+            ("balance", "U256Variable", PublicInputKind::Output, 416, 256),
+        ];
+        assert_eq!(layout.entries.len(), expected.len());
+        for (entry, (name, ty, kind, offset, len)) in layout.entries.iter().zip(expected) {
+            assert_eq!(entry.name, name);
+            assert_eq!(entry.ty, ty);
+            assert_eq!(entry.kind, kind);
+            assert_eq!(entry.offset, offset);
+            assert_eq!(entry.len, len);
+        }
+
+        let json = layout.to_json();
+        let decoded: PublicInputsLayout = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, layout);
+    }
+
+    #[test]
+    fn test_array_io() {
+        let mut builder = DefaultBuilder::new();
+        let values = builder.read_array::<U64Variable, 5>();
+        let doubled = values
+            .as_vec()
+            .into_iter()
+            .map(|v| builder.add(v, v))
+            .collect::<Vec<_>>();
+        builder.write_array(ArrayVariable::<U64Variable, 5>::new(doubled));
+
+        let circuit = builder.build();
+
+        let mut input = circuit.input();
+        input.write_array::<U64Variable>(vec![1, 2, 3, 4, 5]);
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        assert_eq!(output.read_array::<U64Variable>(), vec![2, 4, 6, 8, 10]);
+
+        // An array whose length prefix does not match the circuit's expectation is rejected.
+        let mut input = circuit.input();
+        input.write::<Variable>(GoldilocksField::from_canonical_usize(4));
+        for value in [1, 2, 3, 4, 5] {
+            input.write::<U64Variable>(value);
+        }
+        assert!(circuit.mock_prove(&input).is_err());
     }
 }
@@ -1,34 +1,42 @@
 mod boolean;
+pub mod counter;
 pub mod io;
 pub mod permutation;
 mod proof;
 pub mod watch;
 
 use alloc::collections::BTreeMap;
+use core::fmt::Debug;
 use std::collections::HashMap;
 use std::env;
+use std::path::Path;
 
 use backtrace::Backtrace;
 use ethers::providers::{Http, Middleware, Provider};
 use ethers::types::U256;
 use itertools::Itertools;
+use log::debug;
 use plonky2::iop::generator::{SimpleGenerator, WitnessGeneratorRef};
 use plonky2::iop::target::{BoolTarget, Target};
 use plonky2::plonk::circuit_builder::CircuitBuilder as CircuitAPI;
 use plonky2::plonk::circuit_data::CircuitConfig;
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
 use starkyx::machine::hash::blake::blake2b::BLAKE2B;
 use starkyx::machine::hash::sha::sha256::SHA256;
 use starkyx::machine::hash::sha::sha512::SHA512;
 use tokio::runtime::Runtime;
 
-pub use self::io::CircuitIO;
+use self::counter::Counter;
+pub use self::io::{CircuitIO, PublicInputEntry, PublicInputKind, PublicInputsLayout};
 use super::ecc::curve25519::curta::accelerator::EcOpAccelerator;
 use super::hash::blake2::curta::BLAKE2BAccelerator;
 use super::hash::sha::sha256::curta::SHA256Accelerator;
 use super::hash::sha::sha512::curta::SHA512Accelerator;
 use super::hint::HintGenerator;
 use super::vars::EvmVariable;
-use crate::backend::circuit::{CircuitBuild, DefaultParameters, MockCircuitBuild, PlonkParameters};
+use crate::backend::circuit::{
+    CircuitBuild, DefaultParameters, GateRegistry, HintRegistry, MockCircuitBuild, PlonkParameters,
+};
 use crate::frontend::hint::asynchronous::generator::AsyncHintDataRef;
 use crate::frontend::vars::{BoolVariable, CircuitVariable, Variable};
 use crate::prelude::ArrayVariable;
@@ -43,6 +51,10 @@ pub struct CircuitBuilder<L: PlonkParameters<D>, const D: usize> {
     pub beacon_client: Option<BeaconClient>,
     pub debug: bool,
     pub debug_variables: HashMap<usize, String>,
+    /// Whether hints added with `hint` verify that they consumed their whole input stream.
+    pub hint_input_check: bool,
+    pub(crate) counter: Counter,
+    pub(crate) io_layout: Vec<PublicInputEntry>,
     pub(crate) hints: Vec<Box<dyn HintGenerator<L, D>>>,
     pub(crate) async_hints: Vec<AsyncHintDataRef<L, D>>,
     pub(crate) async_hints_indices: Vec<usize>,
@@ -76,6 +88,9 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
             chain_id: None,
             debug: false,
             debug_variables: HashMap::new(),
+            hint_input_check: false,
+            counter: Counter::new(),
+            io_layout: Vec::new(),
             hints: Vec::new(),
             async_hints: Vec::new(),
             async_hints_indices: Vec::new(),
@@ -97,6 +112,13 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         self.debug = true;
     }
 
+    /// Checks that every subsequent hint reads its whole input stream, so that a hint reading
+    /// its inputs with the wrong length fails witness generation instead of silently producing a
+    /// wrong witness.
+    pub fn set_hint_input_check(&mut self) {
+        self.hint_input_check = true;
+    }
+
     pub fn debug_target(&mut self, target: Target) {
         if !self.debug {
             return;
@@ -242,25 +264,65 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
     /// Build the circuit.
     pub fn build(mut self) -> CircuitBuild<L, D> {
         self.pre_build();
+        let public_inputs = self.public_inputs_layout();
         let data = self.api.build();
         let async_hints = Self::async_hint_map(&data.prover_only.generators, self.async_hints);
         CircuitBuild {
             data,
             io: self.io,
+            public_inputs,
             async_hints,
         }
     }
 
+    /// Builds the circuit defined by `define`, reusing the circuit cached in `cache_dir` if it was
+    /// built before. Computing the proving key dominates the build time, so an unchanged circuit
+    /// is only built once across process restarts.
+    ///
+    /// The circuit digest is only known once the proving key is computed, so the cache is keyed
+    /// by the structure digest of a mock build instead. On a cache miss, `define` is called again
+    /// on a fresh builder to build and save the circuit.
+    pub fn build_cached<F>(cache_dir: &str, define: F) -> CircuitBuild<L, D>
+    where
+        F: Fn(&mut Self),
+        <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher: AlgebraicHasher<L::Field>,
+    {
+        let mut builder = Self::new();
+        define(&mut builder);
+        let gate_serializer = GateRegistry::<L, D>::new();
+        let hint_serializer = HintRegistry::<L, D>::new();
+        let digest = builder
+            .mock_build()
+            .structure_digest(&gate_serializer, &hint_serializer)
+            .expect("failed to serialize circuit");
+        let path = format!("{}/{}.circuit", cache_dir, digest);
+
+        if Path::new(&path).exists() {
+            debug!("loading cached circuit from {}", path);
+            return CircuitBuild::<L, D>::load(&path, &gate_serializer, &hint_serializer)
+                .expect("failed to load cached circuit");
+        }
+
+        let mut builder = Self::new();
+        define(&mut builder);
+        let circuit = builder.build();
+        circuit.save(&path, &gate_serializer, &hint_serializer);
+        debug!("saved circuit to {}", path);
+        circuit
+    }
+
     /// Try to build the circuit, returning data and success. If it fails due to unexpected cyclic
     /// common_data, if will still return the data and success as false.
     pub fn try_build(mut self) -> (CircuitBuild<L, D>, bool) {
         self.pre_build();
+        let public_inputs = self.public_inputs_layout();
         let (data, success) = self.api.try_build_with_options(true);
         let async_hints = Self::async_hint_map(&data.prover_only.generators, self.async_hints);
         (
             CircuitBuild {
                 data,
                 io: self.io,
+                public_inputs,
                 async_hints,
             },
             success,
@@ -269,11 +331,13 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
 
     pub fn mock_build(mut self) -> MockCircuitBuild<L, D> {
         self.pre_build();
+        let num_gates = self.api.num_gates();
         let mock_data = self.api.mock_build();
         let async_hints = Self::async_hint_map(&mock_data.prover_only.generators, self.async_hints);
 
         MockCircuitBuild {
             data: mock_data,
+            num_gates,
             io: self.io,
             debug_variables: self.debug_variables,
             async_hints,
@@ -310,6 +374,21 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         V::constant(self, value)
     }
 
+    /// Initializes an integer variable with a constant value given as a decimal string. Panics if
+    /// the string is not a decimal integer or the value does not fit in the variable, which is at
+    /// most 2^256 - 1.
+    pub fn constant_from_dec_str<V: CircuitVariable>(&mut self, value: &str) -> V
+    where
+        V::ValueType<L::Field>: TryFrom<U256>,
+        <V::ValueType<L::Field> as TryFrom<U256>>::Error: Debug,
+    {
+        let parsed = U256::from_dec_str(value)
+            .unwrap_or_else(|e| panic!("invalid decimal constant {}: {:?}", value, e));
+        let value = V::ValueType::<L::Field>::try_from(parsed)
+            .unwrap_or_else(|e| panic!("decimal constant {} is out of range: {:?}", value, e));
+        V::constant(self, value)
+    }
+
     /// Initializes an array of variables with a constant value in the circuit.
     pub fn constant_array<V: CircuitVariable, const N: usize>(
         &mut self,
@@ -372,6 +451,14 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         }
     }
 
+    /// Fails if i1 != i2 when the `debug-constraints` feature is enabled, and adds no constraints
+    /// otherwise. Use it for redundant checks that are only worth their cost during development.
+    pub fn debug_assert_equal<V: CircuitVariable>(&mut self, i1: V, i2: V) {
+        if cfg!(feature = "debug-constraints") {
+            self.assert_is_equal(i1, i2);
+        }
+    }
+
     /// Returns 1 if i1 == i2 and 0 otherwise as a BoolVariable.
     #[must_use]
     pub fn is_equal<V: CircuitVariable>(&mut self, i1: V, i2: V) -> BoolVariable {
@@ -411,8 +498,14 @@ impl<L: PlonkParameters<D>, const D: usize> Default for CircuitBuilder<L, D> {
 #[cfg(test)]
 pub(crate) mod tests {
 
+    use std::thread::sleep;
+    use std::time::Duration;
+    use std::{env, fs, process};
+
+    use ethers::types::U256;
     use log::debug;
 
+    use crate::backend::circuit::CircuitBuild;
     use crate::prelude::*;
     use crate::utils;
 
@@ -473,4 +566,128 @@ pub(crate) mod tests {
         let xor = output.evm_read::<ByteVariable>();
         debug!("{}", xor);
     }
+
+    #[test]
+    fn test_constant_from_dec_str() {
+        let mut builder = DefaultBuilder::new();
+        let values = [
+            ("1000000000000000000", U256::exp10(18)),
+            ("0", U256::zero()),
+            (
+                "115792089237316195423570985008687907853269984665640564039457584007913129639935",
+                U256::MAX,
+            ),
+        ];
+        for (value, expected) in values {
+            let parsed = builder.constant_from_dec_str::<U256Variable>(value);
+            let expected = builder.constant::<U256Variable>(expected);
+            builder.assert_is_equal(parsed, expected);
+        }
+        let small = builder.constant_from_dec_str::<U64Variable>("12345");
+        let expected = builder.constant::<U64Variable>(12345);
+        builder.assert_is_equal(small, expected);
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_constant_from_dec_str_overflow() {
+        let mut builder = DefaultBuilder::new();
+        builder.constant_from_dec_str::<U256Variable>(
+            "115792089237316195423570985008687907853269984665640564039457584007913129639936",
+        );
+    }
+
+    #[test]
+    fn test_build_cached() {
+        utils::setup_logger();
+        let cache_dir = env::temp_dir().join(format!("plonky2x-build-cached-{}", process::id()));
+        let cache_dir = cache_dir.to_str().unwrap();
+
+        let define = |builder: &mut DefaultBuilder| {
+            let a = builder.read::<Variable>();
+            let b = builder.read::<Variable>();
+            let c = builder.add(a, b);
+            builder.write(c);
+        };
+
+        // The first build saves the circuit to the cache.
+        let circuit = DefaultBuilder::build_cached(cache_dir, define);
+        let entries = fs::read_dir(cache_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect::<Vec<_>>();
+        assert_eq!(entries.len(), 1);
+        let modified = fs::metadata(&entries[0]).unwrap().modified().unwrap();
+
+        // The second build loads it without rewriting the cache file.
+        sleep(Duration::from_millis(10));
+        let cached_circuit = DefaultBuilder::build_cached(cache_dir, define);
+        assert_eq!(
+            fs::metadata(&entries[0]).unwrap().modified().unwrap(),
+            modified
+        );
+        assert_eq!(cached_circuit.id(), circuit.id());
+
+        let mut input = cached_circuit.input();
+        input.write::<Variable>(GoldilocksField::TWO);
+        input.write::<Variable>(GoldilocksField::TWO);
+        let (proof, mut output) = cached_circuit.prove(&input);
+        cached_circuit.verify(&proof, &input, &output);
+        assert_eq!(
+            output.read::<Variable>(),
+            GoldilocksField::from_canonical_u8(4)
+        );
+
+        fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    fn debug_assert_equal_circuit() -> CircuitBuild<DefaultParameters, 2> {
+        let mut builder = DefaultBuilder::new();
+        let a = builder.read::<Variable>();
+        let b = builder.read::<Variable>();
+        // Cross-check the sum against a redundant computation.
+        let sum = builder.add(a, b);
+        let double = builder.mul(a, b);
+        builder.debug_assert_equal(sum, double);
+        builder.write(sum);
+        builder.build()
+    }
+
+    #[test]
+    #[cfg(feature = "debug-constraints")]
+    fn test_debug_assert_equal_enabled() {
+        let circuit = debug_assert_equal_circuit();
+
+        // 2 + 2 == 2 * 2, but 1 + 3 != 1 * 3.
+        let mut input = circuit.input();
+        input.write::<Variable>(GoldilocksField::TWO);
+        input.write::<Variable>(GoldilocksField::TWO);
+        assert!(circuit.mock_prove(&input).is_ok());
+
+        let mut input = circuit.input();
+        input.write::<Variable>(GoldilocksField::ONE);
+        input.write::<Variable>(GoldilocksField::from_canonical_u8(3));
+        assert!(circuit.mock_prove(&input).is_err());
+    }
+
+    #[test]
+    #[cfg(not(feature = "debug-constraints"))]
+    fn test_debug_assert_equal_disabled() {
+        let circuit = debug_assert_equal_circuit();
+
+        // The mismatch goes unnoticed, since the assertion adds no constraints.
+        let mut input = circuit.input();
+        input.write::<Variable>(GoldilocksField::ONE);
+        input.write::<Variable>(GoldilocksField::from_canonical_u8(3));
+        let mut output = circuit.mock_prove(&input).unwrap();
+        assert_eq!(
+            output.read::<Variable>(),
+            GoldilocksField::from_canonical_u8(4)
+        );
+    }
 }
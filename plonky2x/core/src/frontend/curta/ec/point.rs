@@ -1,4 +1,5 @@
 pub use curve25519_dalek::edwards::CompressedEdwardsY;
+use starkyx::chip::ec::edwards::ed25519::params::Ed25519;
 use starkyx::chip::ec::point::{AffinePoint, AffinePointRegister};
 use starkyx::chip::ec::EllipticCurve;
 use starkyx::chip::register::Register;
@@ -76,6 +77,39 @@ impl<E: EllipticCurve> AffinePointVariable<E> {
     }
 }
 
+/// Curves where negating an affine point negates exactly one of its coordinates.
+pub trait AffineNegation: EllipticCurve {
+    /// Whether negation negates the x coordinate (twisted Edwards curves) or the y coordinate
+    /// (short Weierstrass curves).
+    const NEGATES_X: bool;
+}
+
+impl AffineNegation for Ed25519 {
+    const NEGATES_X: bool = true;
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Returns `-point` if `cond` is true and `point` otherwise.
+    pub fn conditional_negate_point<E: AffineNegation>(
+        &mut self,
+        cond: BoolVariable,
+        point: AffinePointVariable<E>,
+    ) -> AffinePointVariable<E> {
+        let negated = if E::NEGATES_X {
+            AffinePointVariable {
+                x: self.curta_field_neg(&point.x),
+                y: point.y.clone(),
+            }
+        } else {
+            AffinePointVariable {
+                x: point.x.clone(),
+                y: self.curta_field_neg(&point.y),
+            }
+        };
+        self.select(cond, negated, point)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CompressedEdwardsYVariable(pub Bytes32Variable);
 
@@ -117,3 +151,50 @@ impl CircuitVariable for CompressedEdwardsYVariable {
         Self(Bytes32Variable::from_variables_unsafe(variables))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use starkyx::chip::ec::edwards::ed25519::params::{Ed25519, Ed25519Parameters};
+    use starkyx::chip::ec::edwards::EdwardsParameters;
+    use starkyx::chip::ec::point::AffinePoint;
+    use starkyx::chip::ec::EllipticCurveParameters;
+    use starkyx::chip::field::parameters::FieldParameters;
+
+    use super::AffinePointVariable;
+    use crate::prelude::{BoolVariable, CircuitBuilder, DefaultParameters};
+
+    type L = DefaultParameters;
+    const D: usize = 2;
+
+    #[test]
+    fn test_conditional_negate_point() {
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let cond = builder.read::<BoolVariable>();
+        let point = builder.read::<AffinePointVariable<Ed25519>>();
+        let result = builder.conditional_negate_point(cond, point);
+        builder.write(result);
+
+        let circuit = builder.build();
+
+        let (generator_x, generator_y) = Ed25519Parameters::generator();
+        let modulus = <Ed25519 as EllipticCurveParameters>::BaseField::modulus();
+        let negated_x = &modulus - &generator_x;
+
+        for cond in [true, false] {
+            let mut inputs = circuit.input();
+            inputs.write::<BoolVariable>(cond);
+            inputs.write::<AffinePointVariable<Ed25519>>(AffinePoint::new(
+                generator_x.clone(),
+                generator_y.clone(),
+            ));
+
+            let (proof, mut output) = circuit.prove(&inputs);
+            circuit.verify(&proof, &inputs, &output);
+
+            let result = output.read::<AffinePointVariable<Ed25519>>();
+            let expected_x = if cond { &negated_x } else { &generator_x };
+            assert_eq!(&result.x, expected_x);
+            assert_eq!(result.y, generator_y);
+        }
+    }
+}
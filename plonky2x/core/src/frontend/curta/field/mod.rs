@@ -1 +1,3 @@
+pub mod parameters;
+pub mod reduce;
 pub mod variable;
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use starkyx::chip::field::parameters::{FieldParameters, MAX_NB_LIMBS};
+
+/// The base field of secp256k1, with modulus `2^256 - 2^32 - 977`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Secp256k1BaseField;
+
+impl FieldParameters for Secp256k1BaseField {
+    const NB_BITS_PER_LIMB: usize = 16;
+    const NB_LIMBS: usize = 16;
+    const NB_WITNESS_LIMBS: usize = 2 * Self::NB_LIMBS - 2;
+    const MODULUS: [u16; MAX_NB_LIMBS] = [
+        0xfc2f, 0xffff, 0xfffe, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff,
+        0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ];
+    const WITNESS_OFFSET: usize = 1usize << 20;
+}
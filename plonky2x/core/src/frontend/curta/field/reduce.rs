@@ -0,0 +1,215 @@
+//! Reduction of field elements whose 16-bit limbs have overflowed, e.g. after limb-wise additions.
+//!
+//! The quotients and remainders of a whole batch of values are computed by a single hint, which
+//! is much cheaper to generate than one hint per value when many values need to be reduced.
+//!
+//! This is meant for `FieldVariable`s combined in the circuit, e.g. limb-wise sums of a few
+//! elements, whose quotients are small. It is not used by the elliptic curve gadgets: the
+//! curve25519 operations reduce inside the curta STARK, the BLS12-381 operations use the 32-bit
+//! `BigUintTarget` gadgets, and the eddsa scalars are reduced from 512-bit digests, whose
+//! quotients do not fit in a single field element. There are no secp256k1 or bn254 field gadgets
+//! over `FieldVariable` to use it in yet, so they should reduce their limb-wise sums with it once
+//! they are added.
+
+use core::marker::PhantomData;
+
+use num::{BigUint, One, ToPrimitive};
+use serde::{Deserialize, Serialize};
+use starkyx::chip::field::parameters::FieldParameters;
+
+use super::variable::FieldVariable;
+use crate::frontend::hint::simple::hint::Hint;
+use crate::prelude::*;
+
+/// Returns the value of little-endian 16-bit limbs which may exceed 16 bits.
+fn limbs_to_biguint<F: RichField>(limbs: &[F]) -> BigUint {
+    limbs.iter().rev().fold(BigUint::from(0u32), |acc, limb| {
+        (acc << 16) + BigUint::from(limb.to_canonical_u64())
+    })
+}
+
+/// Input: (values: [[u64; P::NB_LIMBS]; nb_values])
+/// Output: ([(quotient: Variable, remainder: FieldVariable<P>); nb_values])
+///
+/// Divides each value by the modulus of `P`, where the limbs of a value may exceed 16 bits.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldReduceBatchHint<P> {
+    nb_values: usize,
+    _marker: PhantomData<P>,
+}
+
+impl<P> FieldReduceBatchHint<P> {
+    pub fn new(nb_values: usize) -> Self {
+        Self {
+            nb_values,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize, P: FieldParameters> Hint<L, D>
+    for FieldReduceBatchHint<P>
+{
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        let modulus = P::modulus();
+        for _ in 0..self.nb_values {
+            let value = limbs_to_biguint(input_stream.read_exact(P::NB_LIMBS));
+            let quotient = &value / &modulus;
+            let remainder = value % &modulus;
+            output_stream.write_value::<Variable>(L::Field::from_canonical_u64(
+                quotient.to_u64().expect("quotient does not fit in a u64"),
+            ));
+            output_stream.write_value::<FieldVariable<P>>(remainder);
+        }
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Reduces a batch of values whose limbs are at most `limb_bits` bits, such as limb-wise sums
+    /// of field elements, modulo the field `P`.
+    ///
+    /// All quotients and remainders are witnessed by one hint, and each value is constrained to
+    /// equal `quotient * modulus + remainder` for a valid field element `remainder`. The limbs
+    /// must be small enough that the quotient fits in a single field element.
+    pub fn curta_field_reduce_batch<P: FieldParameters>(
+        &mut self,
+        values: &[FieldVariable<P>],
+        limb_bits: usize,
+    ) -> Vec<FieldVariable<P>> {
+        // Only support 16-bit limbs.
+        assert!(P::NB_BITS_PER_LIMB == 16);
+        assert!((16..48).contains(&limb_bits));
+
+        // The largest quotient is the one of the value whose limbs are all maximal.
+        let max_limbs = vec![L::Field::from_canonical_u64((1 << limb_bits) - 1); P::NB_LIMBS];
+        let max_quotient = limbs_to_biguint(&max_limbs) / P::modulus();
+        let quotient_bits = (max_quotient + BigUint::one()).bits() as usize;
+        assert!(
+            quotient_bits + 18 < 64,
+            "the quotients of {}-bit limbs do not fit in a field element",
+            limb_bits
+        );
+
+        let mut input_stream = VariableStream::new();
+        for value in values.iter() {
+            input_stream.write(value);
+        }
+        let output_stream = self.hint(input_stream, FieldReduceBatchHint::<P>::new(values.len()));
+
+        let modulus_limbs = FieldVariable::<P>::elements::<L::Field>(P::modulus());
+        let mut reduced = Vec::new();
+        for value in values.iter() {
+            let quotient = output_stream.read::<Variable>(self);
+            let remainder = output_stream.read::<FieldVariable<P>>(self);
+            self.api.range_check(quotient.0, quotient_bits);
+
+            // Normalize the value to 16-bit limbs and compute `quotient * modulus + remainder`
+            // with 16-bit limbs, propagating the carries of both, and check that they match.
+            let mut value_carry = self.zero::<Variable>();
+            let mut product_carry = self.zero::<Variable>();
+            for ((limb, remainder_limb), modulus_limb) in value
+                .variables()
+                .iter()
+                .zip(remainder.variables())
+                .zip(modulus_limbs.iter())
+            {
+                let value_sum = self.add(*limb, value_carry);
+                let value_bits = self.api.split_le(value_sum.0, limb_bits + 1);
+                let value_limb = self.api.le_sum(value_bits[..16].iter());
+                value_carry = Variable(self.api.le_sum(value_bits[16..].iter()));
+
+                let modulus_limb = self.constant::<Variable>(*modulus_limb);
+                let mut product_sum = self.mul(quotient, modulus_limb);
+                product_sum = self.add(product_sum, remainder_limb);
+                product_sum = self.add(product_sum, product_carry);
+                let product_bits = self.api.split_le(product_sum.0, quotient_bits + 18);
+                let product_limb = self.api.le_sum(product_bits[..16].iter());
+                product_carry = Variable(self.api.le_sum(product_bits[16..].iter()));
+
+                self.api.connect(value_limb, product_limb);
+            }
+            self.assert_is_equal(value_carry, product_carry);
+
+            remainder.assert_is_valid(self);
+            reduced.push(remainder);
+        }
+        reduced
+    }
+
+    /// Reduces a single value whose limbs are at most `limb_bits` bits modulo the field `P`.
+    pub fn curta_field_reduce<P: FieldParameters>(
+        &mut self,
+        value: &FieldVariable<P>,
+        limb_bits: usize,
+    ) -> FieldVariable<P> {
+        self.curta_field_reduce_batch(&[value.clone()], limb_bits)
+            .pop()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::frontend::curta::field::parameters::Secp256k1BaseField;
+
+    type L = DefaultParameters;
+    const D: usize = 2;
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_curta_field_reduce_batch() {
+        env_logger::try_init().unwrap_or_default();
+
+        let nb_values = 16;
+        let mut builder = CircuitBuilder::<L, D>::new();
+
+        // The limb-wise sums of pairs of field elements, whose limbs have up to 17 bits.
+        let mut sums = Vec::new();
+        for _ in 0..nb_values {
+            let a = builder.read::<FieldVariable<Secp256k1BaseField>>();
+            let b = builder.read::<FieldVariable<Secp256k1BaseField>>();
+            let limbs = a
+                .variables()
+                .iter()
+                .zip(b.variables())
+                .map(|(x, y)| builder.add(*x, y))
+                .collect();
+            sums.push(FieldVariable::<Secp256k1BaseField>::new(limbs));
+        }
+
+        for reduced in builder.curta_field_reduce_batch(&sums, 17) {
+            builder.write(reduced);
+        }
+
+        let circuit = builder.build();
+        let modulus = Secp256k1BaseField::modulus();
+        let mut rng = thread_rng();
+        let mut input = circuit.input();
+        let mut expected = Vec::new();
+        for i in 0..nb_values {
+            // Include the largest element so that at least one sum overflows the modulus.
+            let a = if i == 0 {
+                &modulus - BigUint::one()
+            } else {
+                BigUint::from_bytes_le(&rng.gen::<[u8; 32]>()) % &modulus
+            };
+            let b = BigUint::from_bytes_le(&rng.gen::<[u8; 32]>()) % &modulus;
+            // Each value is reduced on its own, outside of the circuit.
+            expected.push((&a + &b) % &modulus);
+            input.write::<FieldVariable<Secp256k1BaseField>>(a);
+            input.write::<FieldVariable<Secp256k1BaseField>>(b);
+        }
+
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        for expected in expected.iter() {
+            assert_eq!(
+                output.read::<FieldVariable<Secp256k1BaseField>>(),
+                *expected
+            );
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use core::marker::PhantomData;
 
 use itertools::Itertools;
-use num::One;
+use num::{One, Zero};
 use num_bigint::BigUint;
 use starkyx::chip::field::parameters::FieldParameters;
 use starkyx::chip::utils::digits_to_biguint;
@@ -110,6 +110,42 @@ impl<P: FieldParameters> CircuitVariable for FieldVariable<P> {
     }
 }
 
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Computes `-a` in the field `P`. Assumes that `a` is a valid field element.
+    pub fn curta_field_neg<P: FieldParameters>(
+        &mut self,
+        a: &FieldVariable<P>,
+    ) -> FieldVariable<P> {
+        // Only support 16-bit limbs.
+        assert!(P::NB_BITS_PER_LIMB == 16);
+
+        let modulus_limbs = FieldVariable::<P>::elements::<L::Field>(P::modulus());
+        let base = self.constant::<Variable>(L::Field::from_canonical_u32(1 << 16));
+        let one = self.one::<Variable>();
+
+        // Compute `modulus - a` limb by limb. Each `2^16 + modulus_i - a_i - borrow` fits in 17
+        // bits, where the top bit is set iff the limb did not need to borrow.
+        let mut borrow = self.zero::<Variable>();
+        let mut limbs = Vec::new();
+        for (limb, modulus_limb) in a.limbs.iter().zip(modulus_limbs) {
+            let modulus_limb = self.constant::<Variable>(modulus_limb);
+            let mut diff = self.add(base, modulus_limb);
+            diff = self.sub(diff, *limb);
+            diff = self.sub(diff, borrow);
+
+            let bits = self.api.split_le(diff.0, 17);
+            limbs.push(Variable(self.api.le_sum(bits[..16].iter())));
+            let no_borrow = Variable(bits[16].target);
+            borrow = self.sub(one, no_borrow);
+        }
+
+        // The negation of zero is zero rather than the modulus.
+        let zero = self.constant::<FieldVariable<P>>(BigUint::zero());
+        let is_zero = self.is_equal(a.clone(), zero.clone());
+        self.select(is_zero, zero, FieldVariable::new(limbs))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use num::{One, Zero};
@@ -142,6 +178,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_curta_field_neg() {
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let a = builder.read::<FieldVariable<Bn254BaseField>>();
+        let neg_a = builder.curta_field_neg(&a);
+        builder.write(neg_a);
+
+        let circuit = builder.build();
+
+        let modulus = Bn254BaseField::modulus();
+        let test_cases = [
+            (BigUint::zero(), BigUint::zero()),
+            (BigUint::one(), &modulus - BigUint::one()),
+            (&modulus - BigUint::one(), BigUint::one()),
+        ];
+
+        for (value, expected) in test_cases.iter() {
+            let mut inputs = circuit.input();
+            inputs.write::<FieldVariable<Bn254BaseField>>(value.clone());
+
+            let (proof, mut output) = circuit.prove(&inputs);
+            circuit.verify(&proof, &inputs, &output);
+            assert_eq!(output.read::<FieldVariable<Bn254BaseField>>(), *expected);
+        }
+    }
+
     #[test]
     #[should_panic]
     fn test_assert_is_not_valid() {
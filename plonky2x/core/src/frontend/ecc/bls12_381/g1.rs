@@ -0,0 +1,501 @@
+//! Affine point addition on the G1 group of BLS12-381.
+//!
+//! The base field of BLS12-381 is 381 bits, so all arithmetic is done with non-native big integer
+//! gadgets over 32-bit limbs. Points are represented in affine coordinates together with a flag
+//! for the point at infinity, which is the identity of the group.
+
+use num::{BigUint, Num, Zero};
+use plonky2x_derive::CircuitVariable;
+use serde::{Deserialize, Serialize};
+
+use crate::frontend::eth::vars::BLSPubkeyVariable;
+use crate::frontend::hint::simple::hint::Hint;
+use crate::frontend::uint::num::biguint::{BigUintTarget, CircuitBuilderBiguint};
+use crate::frontend::uint::num::u32::gadgets::arithmetic_u32::U32Target;
+use crate::frontend::vars::EvmVariable;
+use crate::prelude::*;
+
+/// The modulus of the BLS12-381 base field.
+pub const BLS12_381_BASE_MODULUS: &str = "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab";
+
+/// The number of 32-bit limbs of an element of the BLS12-381 base field.
+pub const BLS12_381_FP_LIMBS: usize = 12;
+
+/// An element of the BLS12-381 base field as little-endian 32-bit limbs.
+pub type Bls12381FpVariable = ArrayVariable<U32Variable, BLS12_381_FP_LIMBS>;
+
+/// A point of the BLS12-381 G1 group in affine coordinates.
+///
+/// The coordinates are expected to be reduced modulo the base field, and are ignored when
+/// `is_infinity` is set.
+#[derive(Debug, Clone, CircuitVariable)]
+#[value_name(G1PointValue)]
+pub struct G1PointVariable {
+    pub x: Bls12381FpVariable,
+    pub y: Bls12381FpVariable,
+    pub is_infinity: BoolVariable,
+}
+
+/// Returns the modulus of the BLS12-381 base field.
+pub fn bls12_381_base_modulus() -> BigUint {
+    BigUint::from_str_radix(BLS12_381_BASE_MODULUS, 16).unwrap()
+}
+
+/// Input: (numerator: fp, denominator: fp)
+/// Output: (quotient: fp)
+///
+/// Computes `numerator / denominator` in the BLS12-381 base field, or zero if the denominator is
+/// zero.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bls12381FpDivHint;
+
+impl<L: PlonkParameters<D>, const D: usize> Hint<L, D> for Bls12381FpDivHint {
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        let modulus = bls12_381_base_modulus();
+        let numerator = BigUint::from_slice(&input_stream.read_value::<Bls12381FpVariable>());
+        let denominator = BigUint::from_slice(&input_stream.read_value::<Bls12381FpVariable>());
+
+        let quotient = if (&denominator % &modulus).is_zero() {
+            BigUint::zero()
+        } else {
+            let inverse = denominator.modpow(&(&modulus - 2u32), &modulus);
+            numerator * inverse % &modulus
+        };
+        let mut limbs = quotient.to_u32_digits();
+        limbs.resize(BLS12_381_FP_LIMBS, 0);
+        output_stream.write_value::<Bls12381FpVariable>(limbs);
+    }
+}
+
+/// Input: (x: fp, y_is_larger: bool)
+/// Output: (y: fp)
+///
+/// Computes the square root `y` of `x^3 + 4` in the BLS12-381 base field, taking the root larger
+/// than `(p - 1) / 2` if `y_is_larger` is set and the smaller one otherwise. Outputs zero if
+/// `x^3 + 4` is not a square.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bls12381G1DecompressHint;
+
+impl<L: PlonkParameters<D>, const D: usize> Hint<L, D> for Bls12381G1DecompressHint {
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        let modulus = bls12_381_base_modulus();
+        let x = BigUint::from_slice(&input_stream.read_value::<Bls12381FpVariable>());
+        let y_is_larger = input_stream.read_value::<BoolVariable>();
+
+        // The modulus is 3 mod 4, so a square root of `a` is `a^((p + 1) / 4)`.
+        let y_squared = (x.modpow(&BigUint::from(3u32), &modulus) + 4u32) % &modulus;
+        let mut y = y_squared.modpow(&((&modulus + 1u32) / 4u32), &modulus);
+        if &y * &y % &modulus != y_squared {
+            y = BigUint::zero();
+        } else if !y.is_zero() && (y > (&modulus - 1u32) / 2u32) != y_is_larger {
+            y = &modulus - y;
+        }
+        let mut limbs = y.to_u32_digits();
+        limbs.resize(BLS12_381_FP_LIMBS, 0);
+        output_stream.write_value::<Bls12381FpVariable>(limbs);
+    }
+}
+
+fn fp_to_biguint(x: &Bls12381FpVariable) -> BigUintTarget {
+    BigUintTarget {
+        limbs: x.as_slice().iter().map(|l| U32Target::from(*l)).collect(),
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    fn bls12_381_fp_from_biguint(&mut self, x: &BigUintTarget) -> Bls12381FpVariable {
+        assert!(x.num_limbs() <= BLS12_381_FP_LIMBS);
+        let zero = self.zero::<U32Variable>();
+        let mut limbs = x
+            .limbs
+            .iter()
+            .map(|l| U32Variable::from(*l))
+            .collect::<Vec<_>>();
+        limbs.resize(BLS12_381_FP_LIMBS, zero);
+        ArrayVariable::new(limbs)
+    }
+
+    fn bls12_381_fp_add(
+        &mut self,
+        a: &BigUintTarget,
+        b: &BigUintTarget,
+        modulus: &BigUintTarget,
+    ) -> BigUintTarget {
+        let sum = self.api.add_biguint(a, b);
+        self.api.rem_biguint(&sum, modulus)
+    }
+
+    /// Computes `a - b`, assuming that `b` is reduced.
+    fn bls12_381_fp_sub(
+        &mut self,
+        a: &BigUintTarget,
+        b: &BigUintTarget,
+        modulus: &BigUintTarget,
+    ) -> BigUintTarget {
+        let a_plus_modulus = self.api.add_biguint(a, modulus);
+        let difference = self.api.sub_biguint(&a_plus_modulus, b);
+        self.api.rem_biguint(&difference, modulus)
+    }
+
+    fn bls12_381_fp_mul(
+        &mut self,
+        a: &BigUintTarget,
+        b: &BigUintTarget,
+        modulus: &BigUintTarget,
+    ) -> BigUintTarget {
+        let product = self.api.mul_biguint(a, b);
+        self.api.rem_biguint(&product, modulus)
+    }
+
+    /// Adds two points of the BLS12-381 G1 group, handling the point at infinity, doubling, and
+    /// the sum of a point and its negation.
+    pub fn g1_add(&mut self, a: G1PointVariable, b: G1PointVariable) -> G1PointVariable {
+        let modulus = self.api.constant_biguint(&bls12_381_base_modulus());
+        let x1 = fp_to_biguint(&a.x);
+        let y1 = fp_to_biguint(&a.y);
+        let x2 = fp_to_biguint(&b.x);
+        let y2 = fp_to_biguint(&b.y);
+
+        let same_x = self.is_equal(a.x.clone(), b.x.clone());
+        let same_y = self.is_equal(a.y.clone(), b.y.clone());
+        let is_double = self.and(same_x, same_y);
+        let not_same_y = self.not(same_y);
+        let is_opposite = self.and(same_x, not_same_y);
+
+        // The slope is (y2 - y1) / (x2 - x1) for distinct points, and 3 * x1^2 / (2 * y1) when
+        // doubling. G1 has no points of order two, so `y1` is non-zero when doubling.
+        let add_numerator = self.bls12_381_fp_sub(&y2, &y1, &modulus);
+        let add_denominator = self.bls12_381_fp_sub(&x2, &x1, &modulus);
+        let three = self.api.constant_biguint(&BigUint::from(3u32));
+        let x1_squared = self.bls12_381_fp_mul(&x1, &x1, &modulus);
+        let double_numerator = self.bls12_381_fp_mul(&three, &x1_squared, &modulus);
+        let double_denominator = self.bls12_381_fp_add(&y1, &y1, &modulus);
+
+        let add_numerator = self.bls12_381_fp_from_biguint(&add_numerator);
+        let add_denominator = self.bls12_381_fp_from_biguint(&add_denominator);
+        let double_numerator = self.bls12_381_fp_from_biguint(&double_numerator);
+        let double_denominator = self.bls12_381_fp_from_biguint(&double_denominator);
+        let numerator = self.select(is_double, double_numerator, add_numerator);
+        let denominator = self.select(is_double, double_denominator, add_denominator);
+
+        let mut input_stream = VariableStream::new();
+        input_stream.write(&numerator);
+        input_stream.write(&denominator);
+        let output_stream = self.hint(input_stream, Bls12381FpDivHint);
+        let slope = output_stream.read::<Bls12381FpVariable>(self);
+
+        // Check that `slope * denominator == numerator`, unless the points are opposite, in which
+        // case the denominator is zero and the sum is the point at infinity. The slope is unused
+        // if either point is the point at infinity.
+        let slope = fp_to_biguint(&slope);
+        let slope_times_denominator =
+            self.bls12_381_fp_mul(&slope, &fp_to_biguint(&denominator), &modulus);
+        let slope_times_denominator = self.bls12_381_fp_from_biguint(&slope_times_denominator);
+        let slope_is_valid = self.is_equal(slope_times_denominator, numerator);
+        let slope_is_valid = self.or(slope_is_valid, is_opposite);
+        let slope_is_valid = self.or(slope_is_valid, a.is_infinity);
+        let slope_is_valid = self.or(slope_is_valid, b.is_infinity);
+        let one = self._true();
+        self.assert_is_equal(slope_is_valid, one);
+
+        // x3 = slope^2 - x1 - x2 and y3 = slope * (x1 - x3) - y1.
+        let slope_squared = self.bls12_381_fp_mul(&slope, &slope, &modulus);
+        let x3 = self.bls12_381_fp_sub(&slope_squared, &x1, &modulus);
+        let x3 = self.bls12_381_fp_sub(&x3, &x2, &modulus);
+        let x1_minus_x3 = self.bls12_381_fp_sub(&x1, &x3, &modulus);
+        let y3 = self.bls12_381_fp_mul(&slope, &x1_minus_x3, &modulus);
+        let y3 = self.bls12_381_fp_sub(&y3, &y1, &modulus);
+
+        let sum = G1PointVariable {
+            x: self.bls12_381_fp_from_biguint(&x3),
+            y: self.bls12_381_fp_from_biguint(&y3),
+            is_infinity: is_opposite,
+        };
+        let sum = self.select(b.is_infinity, a.clone(), sum);
+        self.select(a.is_infinity, b, sum)
+    }
+
+    /// Decompresses a public key in the compressed zcash serialization of BLS12-381, i.e. the
+    /// big-endian x coordinate whose three top bits flag the compression, the point at infinity
+    /// and whether y is the larger of its two candidates.
+    ///
+    /// The point at infinity is rejected since it is not a valid public key. The point is checked
+    /// to be on the curve but not to be in the G1 subgroup, which the consensus layer checks
+    /// before a key is added to the state.
+    pub fn g1_decompress(&mut self, pubkey: BLSPubkeyVariable) -> G1PointVariable {
+        let true_v = self._true();
+        let false_v = self._false();
+        let bytes = pubkey.0 .0;
+        let flags = bytes[0].as_be_bits();
+        self.assert_is_equal(flags[0], true_v);
+        self.assert_is_equal(flags[1], false_v);
+        let y_is_larger = flags[2];
+
+        let mut x_bytes = bytes.to_vec();
+        x_bytes[0] = ByteVariable([
+            false_v, false_v, false_v, flags[3], flags[4], flags[5], flags[6], flags[7],
+        ]);
+        let x_limbs = x_bytes
+            .chunks(4)
+            .rev()
+            .map(|chunk| U32Variable::decode(self, chunk))
+            .collect::<Vec<_>>();
+        let x = Bls12381FpVariable::new(x_limbs);
+
+        let mut input_stream = VariableStream::new();
+        input_stream.write(&x);
+        input_stream.write(&y_is_larger);
+        let output_stream = self.hint(input_stream, Bls12381G1DecompressHint);
+        let y = output_stream.read::<Bls12381FpVariable>(self);
+        y.assert_is_valid(self);
+
+        // Check that both coordinates are reduced and that the flag selects y.
+        let modulus = bls12_381_base_modulus();
+        let max = self.api.constant_biguint(&(&modulus - 1u32));
+        let half = self.api.constant_biguint(&((&modulus - 1u32) / 2u32));
+        let x_target = fp_to_biguint(&x);
+        let y_target = fp_to_biguint(&y);
+        let x_is_reduced = BoolVariable::from(self.api.cmp_biguint(&x_target, &max));
+        self.assert_is_equal(x_is_reduced, true_v);
+        let y_is_reduced = BoolVariable::from(self.api.cmp_biguint(&y_target, &max));
+        self.assert_is_equal(y_is_reduced, true_v);
+        let y_is_smaller = BoolVariable::from(self.api.cmp_biguint(&y_target, &half));
+        let y_is_not_smaller = self.not(y_is_smaller);
+        self.assert_is_equal(y_is_not_smaller, y_is_larger);
+
+        // Check that the point is on the curve y^2 = x^3 + 4.
+        let modulus = self.api.constant_biguint(&modulus);
+        let four = self.api.constant_biguint(&BigUint::from(4u32));
+        let y_squared = self.bls12_381_fp_mul(&y_target, &y_target, &modulus);
+        let x_squared = self.bls12_381_fp_mul(&x_target, &x_target, &modulus);
+        let x_cubed = self.bls12_381_fp_mul(&x_squared, &x_target, &modulus);
+        let x_cubed_plus_four = self.bls12_381_fp_add(&x_cubed, &four, &modulus);
+        let y_squared = self.bls12_381_fp_from_biguint(&y_squared);
+        let x_cubed_plus_four = self.bls12_381_fp_from_biguint(&x_cubed_plus_four);
+        self.assert_is_equal(y_squared, x_cubed_plus_four);
+
+        G1PointVariable {
+            x,
+            y,
+            is_infinity: false_v,
+        }
+    }
+
+    /// Computes the aggregate public key of the participating members of a committee, i.e. the
+    /// sum of the public keys whose participation bit is set. Non-participating keys contribute
+    /// the point at infinity.
+    ///
+    /// The public keys are expected to be decompressed and valid points of G1.
+    pub fn aggregate_pubkeys(
+        &mut self,
+        pubkeys: &[G1PointVariable],
+        participation: &[BoolVariable],
+    ) -> G1PointVariable {
+        assert_eq!(
+            pubkeys.len(),
+            participation.len(),
+            "there must be one participation bit per pubkey"
+        );
+        let zero = self.constant::<Bls12381FpVariable>(vec![0; BLS12_381_FP_LIMBS]);
+        let mut aggregate = G1PointVariable {
+            x: zero.clone(),
+            y: zero,
+            is_infinity: self._true(),
+        };
+        for (pubkey, participated) in pubkeys.iter().zip(participation.iter()) {
+            let infinity = G1PointVariable {
+                x: pubkey.x.clone(),
+                y: pubkey.y.clone(),
+                is_infinity: self._true(),
+            };
+            let summand = self.select(*participated, pubkey.clone(), infinity);
+            aggregate = self.g1_add(aggregate, summand);
+        }
+        aggregate
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    type L = DefaultParameters;
+    const D: usize = 2;
+
+    pub(crate) type Point = Option<(BigUint, BigUint)>;
+
+    fn generator() -> Point {
+        let x = "17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb";
+        let y = "08b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1";
+        Some((
+            BigUint::from_str_radix(x, 16).unwrap(),
+            BigUint::from_str_radix(y, 16).unwrap(),
+        ))
+    }
+
+    /// Affine point addition outside of the circuit.
+    pub(crate) fn add(a: &Point, b: &Point) -> Point {
+        let p = bls12_381_base_modulus();
+        let ((x1, y1), (x2, y2)) = match (a, b) {
+            (None, _) => return b.clone(),
+            (_, None) => return a.clone(),
+            (Some(a), Some(b)) => (a, b),
+        };
+        let inverse = |x: BigUint| x.modpow(&(&p - 2u32), &p);
+        let slope = if x1 == x2 {
+            if y1 != y2 {
+                return None;
+            }
+            BigUint::from(3u32) * x1 * x1 * inverse(BigUint::from(2u32) * y1) % &p
+        } else {
+            (y2 + &p - y1) * inverse((x2 + &p - x1) % &p) % &p
+        };
+        let x3 = (&slope * &slope + &p * 2u32 - x1 - x2) % &p;
+        let y3 = (slope * ((x1 + &p - &x3) % &p) + &p - y1) % &p;
+        Some((x3, y3))
+    }
+
+    fn multiple(n: usize) -> Point {
+        (0..n).fold(None, |acc, _| add(&acc, &generator()))
+    }
+
+    pub(crate) fn value(point: &Point) -> G1PointValue<<L as PlonkParameters<D>>::Field> {
+        let limbs = |x: &BigUint| {
+            let mut limbs = x.to_u32_digits();
+            limbs.resize(BLS12_381_FP_LIMBS, 0);
+            limbs
+        };
+        let (x, y) = point.clone().unwrap();
+        G1PointValue {
+            x: limbs(&x),
+            y: limbs(&y),
+            is_infinity: false,
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_aggregate_pubkeys() {
+        env_logger::try_init().unwrap_or_default();
+
+        // The first two keys are equal so that the aggregation exercises doubling, and the third
+        // one does not participate.
+        let pubkeys = [multiple(1), multiple(1), multiple(2), multiple(5)];
+        let participation = [true, true, false, true];
+        let expected = multiple(7);
+        let p = bls12_381_base_modulus();
+        let (x, y) = expected.clone().unwrap();
+        assert_eq!(&y * &y % &p, (&x * &x * &x + 4u32) % &p);
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let pubkey_variables = (0..pubkeys.len())
+            .map(|_| builder.read::<G1PointVariable>())
+            .collect::<Vec<_>>();
+        let participation_variables = (0..pubkeys.len())
+            .map(|_| builder.read::<BoolVariable>())
+            .collect::<Vec<_>>();
+        let aggregate = builder.aggregate_pubkeys(&pubkey_variables, &participation_variables);
+        builder.write(aggregate);
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        for pubkey in pubkeys.iter() {
+            input.write::<G1PointVariable>(value(pubkey));
+        }
+        for participated in participation {
+            input.write::<BoolVariable>(participated);
+        }
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        let aggregate = output.read::<G1PointVariable>();
+        let expected = value(&expected);
+        assert!(!aggregate.is_infinity);
+        assert_eq!(aggregate.x, expected.x);
+        assert_eq!(aggregate.y, expected.y);
+    }
+
+    /// Decompresses a point outside of the circuit.
+    pub(crate) fn decompress(bytes: &[u8; 48]) -> Point {
+        let p = bls12_381_base_modulus();
+        if bytes[0] & 0x40 != 0 {
+            return None;
+        }
+        let mut x_bytes = *bytes;
+        x_bytes[0] &= 0x1f;
+        let x = BigUint::from_bytes_be(&x_bytes);
+        let y_squared = (x.modpow(&BigUint::from(3u32), &p) + 4u32) % &p;
+        let y = y_squared.modpow(&((&p + 1u32) / 4u32), &p);
+        assert_eq!(&y * &y % &p, y_squared, "x is not on the curve");
+        let y_is_larger = bytes[0] & 0x20 != 0;
+        let y = if (y > (&p - 1u32) / 2u32) == y_is_larger {
+            y
+        } else {
+            &p - y
+        };
+        Some((x, y))
+    }
+
+    /// Compresses a point outside of the circuit.
+    fn compress(point: &Point) -> [u8; 48] {
+        let p = bls12_381_base_modulus();
+        let (x, y) = point.clone().unwrap();
+        let mut bytes = x.to_bytes_be();
+        bytes.splice(0..0, vec![0u8; 48 - bytes.len()]);
+        bytes[0] |= 0x80;
+        if y > (&p - 1u32) / 2u32 {
+            bytes[0] |= 0x20;
+        }
+        bytes.try_into().unwrap()
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_g1_decompress() {
+        env_logger::try_init().unwrap_or_default();
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let pubkey = builder.read::<BLSPubkeyVariable>();
+        let point = builder.g1_decompress(pubkey);
+        builder.write(point);
+        let circuit = builder.build();
+
+        // Each point and its negation, which have the same x and opposite flags.
+        let p = bls12_381_base_modulus();
+        for n in [1, 2, 5] {
+            let point = multiple(n);
+            let (x, y) = point.clone().unwrap();
+            for point in [point, Some((x, &p - y))] {
+                let mut input = circuit.input();
+                input.write::<BLSPubkeyVariable>(compress(&point));
+                let (proof, mut output) = circuit.prove(&input);
+                circuit.verify(&proof, &input, &output);
+
+                assert_eq!(decompress(&compress(&point)), point);
+                let decompressed = output.read::<G1PointVariable>();
+                let expected = value(&point);
+                assert!(!decompressed.is_infinity);
+                assert_eq!(decompressed.x, expected.x);
+                assert_eq!(decompressed.y, expected.y);
+            }
+        }
+
+        // The point at infinity, a wrong sign flag and an x off the curve are rejected.
+        let mut infinity = [0u8; 48];
+        infinity[0] = 0xc0;
+        let mut wrong_sign = compress(&multiple(1));
+        wrong_sign[0] ^= 0x20;
+        let is_square = |a: BigUint| a.modpow(&((&p - 1u32) / 2u32), &p) <= BigUint::from(1u32);
+        let off_curve_x = (1u8..)
+            .find(|x| !is_square(BigUint::from(*x).pow(3) + 4u32))
+            .unwrap();
+        let mut off_curve = [0u8; 48];
+        off_curve[0] = 0x80;
+        off_curve[47] = off_curve_x;
+        for pubkey in [infinity, wrong_sign, off_curve] {
+            let mut input = circuit.input();
+            input.write::<BLSPubkeyVariable>(pubkey);
+            assert!(circuit.mock_prove(&input).is_err());
+        }
+    }
+}
@@ -0,0 +1,268 @@
+//! ABI encoding of function calls with static arguments.
+//!
+//! Reference: https://docs.soliditylang.org/en/latest/abi-spec.html
+
+use plonky2::field::types::Field;
+
+use super::storage::generators::TRANSACTION_ENCODING_LEN;
+use super::storage::vars::EthTransactionVariable;
+use super::vars::AddressVariable;
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::uint::uint256::U256Variable;
+use crate::frontend::vars::{BoolVariable, ByteVariable, Bytes32Variable, EvmVariable, Variable};
+
+/// The maximum number of static arguments of a call checked by `assert_calldata_is`.
+pub const MAX_STATIC_ARGS: usize = 8;
+
+/// The length in bytes of the calldata read by `assert_calldata_is`, enough for a call with
+/// `MAX_STATIC_ARGS` static arguments.
+const MAX_STATIC_CALLDATA_LEN: usize = 4 + 32 * MAX_STATIC_ARGS;
+
+/// A static argument of a function call, which is encoded as a single 32-byte word.
+#[derive(Debug, Clone, Copy)]
+pub enum AbiValue {
+    Address(AddressVariable),
+    Uint(U256Variable),
+    Bool(BoolVariable),
+    Bytes32(Bytes32Variable),
+}
+
+/// Returns the selector of the function with the given signature, e.g.
+/// `transfer(address,uint256)`.
+pub fn function_selector(signature: &str) -> [u8; 4] {
+    ethers::utils::id(signature)
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// ABI encodes static arguments, left padding each one to 32 bytes.
+    pub fn abi_encode(&mut self, args: &[AbiValue]) -> Vec<ByteVariable> {
+        let false_v = self._false();
+        let zero = self.constant::<ByteVariable>(0);
+        let mut encoded = Vec::with_capacity(32 * args.len());
+        for arg in args.iter() {
+            let bytes = match arg {
+                AbiValue::Address(address) => address.encode(self),
+                AbiValue::Uint(value) => value.encode(self),
+                AbiValue::Bool(value) => {
+                    let mut bits = [false_v; 8];
+                    bits[7] = *value;
+                    vec![ByteVariable(bits)]
+                }
+                AbiValue::Bytes32(value) => value.as_bytes().to_vec(),
+            };
+            encoded.extend(vec![zero; 32 - bytes.len()]);
+            encoded.extend(bytes);
+        }
+        encoded
+    }
+
+    /// Asserts that the calldata of `transaction` is a call to the function with `selector` with
+    /// the static arguments `args`, i.e. that it equals `selector || abi_encode(args)`.
+    ///
+    /// The calldata is the `data` item of the transaction, which is the sixth item of legacy
+    /// transactions, the seventh of access list transactions and the eighth of later types. It is
+    /// read in a window of `4 + 32 * MAX_STATIC_ARGS` bytes, which must fit in the padded encoding.
+    pub fn assert_calldata_is(
+        &mut self,
+        transaction: &EthTransactionVariable,
+        selector: [u8; 4],
+        args: &[AbiValue],
+    ) {
+        assert!(
+            args.len() <= MAX_STATIC_ARGS,
+            "calls with more than {} static arguments are not supported",
+            MAX_STATIC_ARGS
+        );
+        let mut expected = selector
+            .iter()
+            .map(|byte| self.constant::<ByteVariable>(*byte))
+            .collect::<Vec<_>>();
+        expected.extend(self.abi_encode(args));
+
+        let encoding = &transaction.encoding;
+        let seed = transaction.hash.as_bytes();
+        let zero = self.zero::<Variable>();
+        let one = self.one::<Variable>();
+        let true_v = self._true();
+        let false_v = self._false();
+
+        // Typed transactions are prefixed by their type, which is below 0x80.
+        let is_typed = self.not(encoding[0].as_be_bits()[0]);
+        let access_list_type = self.constant::<ByteVariable>(1);
+        let is_access_list = self.is_equal(encoding[0], access_list_type);
+        let is_legacy = self.not(is_typed);
+        let has_no_fee_cap = self.or(is_legacy, is_access_list);
+        let has_fee_cap = self.not(has_no_fee_cap);
+
+        let mut position = self.select(is_typed, one, zero);
+        let (is_list, header_len, _) = self.decode_rlp_header_at(encoding, position, &seed);
+        self.assert_is_equal(is_list, true_v);
+        position = self.add(position, header_len);
+
+        // Skip the items before `data`: the chain id of typed transactions, then the nonce, the
+        // gas price or the priority fee and fee cap, the gas limit, the recipient and the value.
+        let skipped = [
+            Some(is_typed),
+            None,
+            None,
+            Some(has_fee_cap),
+            None,
+            None,
+            None,
+        ];
+        for condition in skipped {
+            let (_, header_len, payload_len) = self.decode_rlp_header_at(encoding, position, &seed);
+            let next = self.add(position, header_len);
+            let next = self.add(next, payload_len);
+            position = match condition {
+                Some(condition) => self.select(condition, next, position),
+                None => next,
+            };
+        }
+
+        let (is_list, header_len, payload_len) =
+            self.decode_rlp_header_at(encoding, position, &seed);
+        self.assert_is_equal(is_list, false_v);
+        let expected_len =
+            self.constant::<Variable>(L::Field::from_canonical_usize(expected.len()));
+        self.assert_is_equal(payload_len, expected_len);
+        position = self.add(position, header_len);
+        let calldata = self
+            .get_fixed_subarray::<TRANSACTION_ENCODING_LEN, MAX_STATIC_CALLDATA_LEN>(
+                encoding, position, &seed,
+            );
+        for (byte, expected) in calldata.as_slice().iter().zip(expected) {
+            self.assert_is_equal(*byte, expected);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::signers::{LocalWallet, Signer};
+    use ethers::types::transaction::eip2718::TypedTransaction;
+    use ethers::types::transaction::eip2930::AccessList;
+    use ethers::types::{
+        Eip1559TransactionRequest, Eip2930TransactionRequest, TransactionRequest, H256, U256,
+    };
+    use ethers::utils::{keccak256, rlp};
+
+    use super::*;
+    use crate::frontend::eth::mpt::builder::transform_proof_to_padded;
+    use crate::frontend::eth::mpt::trie::trie_root_and_proof;
+    use crate::frontend::eth::storage::generators::{BLOCK_TRIE_NODE_LEN, BLOCK_TRIE_PROOF_LEN};
+    use crate::prelude::{ArrayVariable, DefaultBuilder, U32Variable};
+    use crate::utils::{address, bytes};
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_assert_calldata_is() {
+        let selector = function_selector("transfer(address,uint256)");
+        assert_eq!(selector, [0xa9, 0x05, 0x9c, 0xbb]);
+
+        let mut builder = DefaultBuilder::new();
+        let transactions_root = builder.read::<Bytes32Variable>();
+        let transaction_hash = builder.read::<Bytes32Variable>();
+        let index = builder.read::<U32Variable>();
+        let proof =
+            builder.read::<ArrayVariable<
+                ArrayVariable<ByteVariable, BLOCK_TRIE_NODE_LEN>,
+                BLOCK_TRIE_PROOF_LEN,
+            >>();
+        let lengths = builder.read::<ArrayVariable<U32Variable, BLOCK_TRIE_PROOF_LEN>>();
+        let to = builder.read::<AddressVariable>();
+        let amount = builder.read::<U256Variable>();
+        let transaction = builder.verify_transaction_proof(
+            transaction_hash,
+            transactions_root,
+            index,
+            &proof,
+            &lengths,
+        );
+        builder.assert_calldata_is(
+            &transaction,
+            selector,
+            &[AbiValue::Address(to), AbiValue::Uint(amount)],
+        );
+        let circuit = builder.build();
+
+        // The calldata of an ERC-20 transfer of 100 base units of USDC to 0x28C6…1d60, sent in a
+        // legacy, an access list and a fee market transaction signed with a test key. The block
+        // also has a plain ether transfer.
+        let calldata: Vec<u8> = bytes!("0xa9059cbb00000000000000000000000028c6c06298d514db089934071355e5743bf21d600000000000000000000000000000000000000000000000000000000000000064");
+        let usdc = address!("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+        let to = address!("0x28C6c06298d514Db089934071355E5743bf21d60");
+        let wallet = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+            .parse::<LocalWallet>()
+            .unwrap()
+            .with_chain_id(1u64);
+        let legacy = TransactionRequest::new()
+            .to(usdc)
+            .nonce(0)
+            .gas(60_000)
+            .gas_price(30_000_000_000u64)
+            .data(calldata.clone())
+            .chain_id(1u64);
+        let access_list =
+            Eip2930TransactionRequest::new(legacy.clone().nonce(1), AccessList::default());
+        let fee_market = Eip1559TransactionRequest::new()
+            .to(usdc)
+            .nonce(2)
+            .gas(60_000)
+            .max_fee_per_gas(30_000_000_000u64)
+            .max_priority_fee_per_gas(1_000_000_000u64)
+            .data(calldata)
+            .chain_id(1u64);
+        let ether_transfer = TransactionRequest::new()
+            .to(to)
+            .nonce(3)
+            .gas(21_000)
+            .gas_price(30_000_000_000u64)
+            .value(1)
+            .chain_id(1u64);
+        let transactions = [
+            TypedTransaction::Legacy(legacy),
+            TypedTransaction::Eip2930(access_list),
+            TypedTransaction::Eip1559(fee_market),
+            TypedTransaction::Legacy(ether_transfer),
+        ]
+        .iter()
+        .map(|transaction| {
+            let signature = wallet.sign_transaction_sync(transaction).unwrap();
+            transaction.rlp_signed(&signature).to_vec()
+        })
+        .collect::<Vec<_>>();
+        let entries = transactions
+            .iter()
+            .enumerate()
+            .map(|(index, transaction)| (rlp::encode(&index).to_vec(), transaction.clone()))
+            .collect::<Vec<_>>();
+
+        let write_input = |index: usize, amount: u64| {
+            let (root, proof) = trie_root_and_proof(&entries, &entries[index].0);
+            let (proof, lengths) =
+                transform_proof_to_padded::<BLOCK_TRIE_NODE_LEN, BLOCK_TRIE_PROOF_LEN>(proof);
+            let mut input = circuit.input();
+            input.write::<Bytes32Variable>(root);
+            input.write::<Bytes32Variable>(H256::from(keccak256(&transactions[index])));
+            input.write::<U32Variable>(index as u32);
+            input.write::<ArrayVariable<
+                ArrayVariable<ByteVariable, BLOCK_TRIE_NODE_LEN>,
+                BLOCK_TRIE_PROOF_LEN,
+            >>(proof);
+            input.write::<ArrayVariable<U32Variable, BLOCK_TRIE_PROOF_LEN>>(
+                lengths.into_iter().map(|length| length as u32).collect(),
+            );
+            input.write::<AddressVariable>(to);
+            input.write::<U256Variable>(U256::from(amount));
+            input
+        };
+
+        for index in 0..3 {
+            assert!(circuit.mock_prove(&write_input(index, 100)).is_ok());
+            assert!(circuit.mock_prove(&write_input(index, 101)).is_err());
+        }
+        assert!(circuit.mock_prove(&write_input(3, 100)).is_err());
+    }
+}
@@ -5,25 +5,33 @@ use super::generators::{
     BeaconAllWithdrawalsHint, BeaconBalanceBatchWitnessHint, BeaconBalanceGenerator,
     BeaconBalanceWitnessHint, BeaconBalancesGenerator, BeaconBlockRootsHint, BeaconGraffitiHint,
     BeaconHeaderHint, BeaconHeadersFromOffsetRangeHint, BeaconHistoricalBlockHint,
-    BeaconPartialBalancesHint, BeaconPartialValidatorsHint, BeaconValidatorBatchHint,
-    BeaconValidatorGenerator, BeaconValidatorSubtreeHint, BeaconValidatorSubtreePoseidonHint,
-    BeaconValidatorSubtreesHint, BeaconValidatorsHint, BeaconWithdrawalGenerator,
-    BeaconWithdrawalsGenerator, CompressedBeaconValidatorBatchHint, CLOSE_SLOT_BLOCK_ROOT_DEPTH,
-    FAR_SLOT_BLOCK_ROOT_DEPTH, FAR_SLOT_HISTORICAL_SUMMARY_DEPTH,
+    BeaconPartialBalancesHint, BeaconPartialValidatorsHint, BeaconStateFieldHint,
+    BeaconTotalBalanceGenerator, BeaconValidatorBatchHint, BeaconValidatorGenerator,
+    BeaconValidatorSubtreeHint, BeaconValidatorSubtreePoseidonHint, BeaconValidatorSubtreesHint,
+    BeaconValidatorsHint, BeaconWithdrawalGenerator, BeaconWithdrawalsGenerator,
+    CompressedBeaconValidatorBatchHint, CLOSE_SLOT_BLOCK_ROOT_DEPTH, FAR_SLOT_BLOCK_ROOT_DEPTH,
+    FAR_SLOT_HISTORICAL_SUMMARY_DEPTH,
 };
 use super::vars::{
-    BeaconBalancesVariable, BeaconHeaderVariable, BeaconValidatorVariable,
+    BeaconBalancesVariable, BeaconDepositVariable, BeaconHeaderVariable,
+    BeaconLightClientUpdateVariable, BeaconSyncCommitteeVariable, BeaconValidatorVariable,
     BeaconValidatorsVariable, BeaconWithdrawalVariable, BeaconWithdrawalsVariable,
-    CompressedBeaconValidatorVariable,
+    CompressedBeaconValidatorVariable, MAX_NEXT_SYNC_COMMITTEE_BRANCH_DEPTH, SYNC_COMMITTEE_SIZE,
 };
 use crate::backend::circuit::PlonkParameters;
 use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::ecc::bls12_381::g1::G1PointVariable;
 use crate::frontend::eth::vars::BLSPubkeyVariable;
 use crate::frontend::uint::uint64::U64Variable;
 use crate::frontend::vars::{
     Bytes32Variable, CircuitVariable, EvmVariable, SSZVariable, VariableStream,
 };
-use crate::prelude::{ArrayVariable, BoolVariable, ByteVariable, BytesVariable, U256Variable};
+use crate::prelude::{
+    ArrayVariable, BoolVariable, ByteVariable, BytesVariable, U128Variable, U256Variable,
+    U32Variable, Variable,
+};
+use crate::utils::eth::beacon::ssz::{beacon_state_schema, zero_hashes, SSZType};
+use crate::utils::eth::beacon::BeaconFork;
 use crate::utils::eth::concat_g_indices;
 
 /// The gindex for blockRoot -> validatorsRoot.
@@ -56,6 +64,11 @@ const HISTORICAL_SUMMARY_BLOCK_ROOT_GINDEX: u64 = 16384;
 /// The gindex for blockRoot -> state -> state.block_roots[0].
 const CLOSE_SLOT_BLOCK_ROOT_GINDEX: u64 = 2924544;
 
+/// The path of the current fork version in the beacon state.
+fn fork_version_path() -> Vec<String> {
+    vec!["fork".to_string(), "current_version".to_string()]
+}
+
 /// The log2 of the validator registry limit.
 const VALIDATOR_REGISTRY_LIMIT_LOG2: usize = 40;
 
@@ -92,6 +105,15 @@ const CAPELLA_FORK_EPOCH: u64 = 194048;
 /// Beacon chain constant MAX_WITHDRAWALS_PER_PAYLOAD.
 const MAX_WITHDRAWALS_PER_PAYLOAD: usize = 16;
 
+/// The depth of a deposit proof, which is DEPOSIT_CONTRACT_TREE_DEPTH plus the length mix-in.
+const DEPOSIT_PROOF_DEPTH: usize = 33;
+
+/// Beacon chain constant EPOCHS_PER_SYNC_COMMITTEE_PERIOD times SLOTS_PER_EPOCH.
+const SLOTS_PER_SYNC_COMMITTEE_PERIOD: u64 = 256 * SLOTS_PER_EPOCH;
+
+/// Beacon chain constant DOMAIN_SYNC_COMMITTEE.
+const DOMAIN_SYNC_COMMITTEE: [u8; 4] = [7, 0, 0, 0];
+
 impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
     /// Get the first B validators for a given block root.
     pub fn beacon_get_partial_validators<const B: usize>(
@@ -275,6 +297,105 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         }
     }
 
+    /// Get the sum of all validator balances at a given state root of the given fork, where `B` is
+    /// an upper bound on the number of validators. The balances root is proven against the state
+    /// root and recomputed from every balance leaf, so the sum covers the entire balances list.
+    /// The fork version of the state is proven as well, so the state must be at `fork`.
+    ///
+    /// The fork is a parameter rather than detected with `BeaconClient::get_fork`, because the
+    /// state root is only known when proving, while the gindex of the balances and the depth of
+    /// their proof, which depend on the layout of the fork, are fixed when building the circuit.
+    ///
+    /// The hint downloads the whole beacon state and witnesses all `B` balances at once, so it
+    /// needs memory for the full state and the circuit grows linearly with `B`.
+    pub fn beacon_get_total_balance<const B: usize>(
+        &mut self,
+        state_root: Bytes32Variable,
+        fork: BeaconFork,
+    ) -> U128Variable {
+        let gindex = beacon_state_schema(fork).gindex(&["balances".to_string()]);
+        let depth = 63 - gindex.leading_zeros() as usize;
+
+        let mut input_stream = VariableStream::new();
+        input_stream.write(&state_root);
+        let hint = BeaconTotalBalanceGenerator::<B> { fork };
+        let output_stream = self.async_hint(input_stream, hint);
+
+        let balances_root = output_stream.read::<Bytes32Variable>(self);
+        let proof = (0..depth)
+            .map(|_| output_stream.read::<Bytes32Variable>(self))
+            .collect::<Vec<_>>();
+        self.ssz_verify_proof_const(state_root, balances_root, &proof, gindex);
+
+        let length = output_stream.read::<U64Variable>(self);
+        let balances = (0..B)
+            .map(|_| output_stream.read::<U64Variable>(self))
+            .collect::<Vec<_>>();
+
+        // Check that the state has the layout of `fork`.
+        let fork_version_gindex = beacon_state_schema(fork).gindex(&fork_version_path());
+        let fork_version_leaf = output_stream.read::<Bytes32Variable>(self);
+        let fork_version_proof = (0..63 - fork_version_gindex.leading_zeros() as usize)
+            .map(|_| output_stream.read::<Bytes32Variable>(self))
+            .collect::<Vec<_>>();
+        self.beacon_verify_fork_version(state_root, fork, fork_version_leaf, &fork_version_proof);
+
+        self.beacon_sum_balances(balances_root, &balances, length)
+    }
+
+    /// Verifies that `balances` (padded with zeros) and `length` hash to the balances root and
+    /// returns the sum of the balances. The number of balances must be a multiple of four such
+    /// that the number of leaves is a power of two.
+    pub fn beacon_sum_balances(
+        &mut self,
+        balances_root: Bytes32Variable,
+        balances: &[U64Variable],
+        length: U64Variable,
+    ) -> U128Variable {
+        assert!(balances.len() % 4 == 0 && (balances.len() / 4).is_power_of_two());
+
+        // Each leaf packs four little-endian balances.
+        let leafs = balances
+            .chunks_exact(4)
+            .map(|chunk| {
+                let mut leaf = Vec::new();
+                for balance in chunk.iter() {
+                    let mut bytes = balance.encode(self);
+                    bytes.reverse();
+                    leaf.extend(bytes);
+                }
+                Bytes32Variable(BytesVariable(leaf.try_into().unwrap()))
+            })
+            .collect::<Vec<_>>();
+        let mut root = self.ssz_hash_leafs(&leafs);
+
+        // Extend the subtree to the full list of `VALIDATOR_REGISTRY_LIMIT / 4` leaves.
+        let zero_hashes = zero_hashes(VALIDATOR_REGISTRY_LIMIT_LOG2 - 2);
+        let subtree_depth = leafs.len().trailing_zeros() as usize;
+        for zero_hash in zero_hashes[subtree_depth..VALIDATOR_REGISTRY_LIMIT_LOG2 - 2].iter() {
+            let zero_hash = self.constant::<Bytes32Variable>(*zero_hash);
+            root = self.curta_sha256_pair(root, zero_hash);
+        }
+
+        let zero = self.constant::<ByteVariable>(0);
+        let mut length_bytes = length.encode(self);
+        length_bytes.reverse();
+        length_bytes.extend([zero; 24]);
+        let length_leaf = Bytes32Variable(BytesVariable(length_bytes.try_into().unwrap()));
+        let root = self.curta_sha256_pair(root, length_leaf);
+        self.assert_is_equal(root, balances_root);
+
+        let zero = self.zero::<U32Variable>();
+        let mut sum = self.zero::<U128Variable>();
+        for balance in balances.iter() {
+            let balance = U128Variable {
+                limbs: [balance.limbs[0], balance.limbs[1], zero, zero],
+            };
+            sum = self.add(sum, balance);
+        }
+        sum
+    }
+
     /// Given a batch size, limit, and block root, return the hash of all subtrees of the validator
     /// tree. The subtrees will all contain `B` validators, and `N`/`B` subtrees will be returned.
     pub fn beacon_witness_validator_subtrees<const B: usize, const N: usize>(
@@ -510,6 +631,46 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         header
     }
 
+    /// Verifies that `headers` form a contiguous segment of the beacon chain, where `roots[i]` is
+    /// the root of `headers[i]` and each header's parent is the previous header.
+    pub fn verify_block_chain(
+        &mut self,
+        roots: &[Bytes32Variable],
+        headers: &[BeaconHeaderVariable],
+    ) {
+        assert_eq!(
+            roots.len(),
+            headers.len(),
+            "expected one root per header, got {} roots and {} headers",
+            roots.len(),
+            headers.len()
+        );
+        for (i, (root, header)) in roots.iter().zip(headers.iter()).enumerate() {
+            let restored_root = self.ssz_hash_tree_root(*header);
+            self.assert_is_equal(*root, restored_root);
+            if i > 0 {
+                self.assert_is_equal(header.parent_root, roots[i - 1]);
+            }
+        }
+    }
+
+    /// Asserts that `slot` is in `epoch` and is not after `current_slot`, which should be proven
+    /// against a trusted block root, e.g. the slot of its header.
+    pub fn assert_within_epoch_bounds(
+        &mut self,
+        slot: U64Variable,
+        epoch: U64Variable,
+        current_slot: U64Variable,
+    ) {
+        let slots_per_epoch = self.constant::<U64Variable>(SLOTS_PER_EPOCH);
+        let slot_epoch = self.div(slot, slots_per_epoch);
+        self.assert_is_equal(slot_epoch, epoch);
+
+        let true_v = self._true();
+        let is_not_future = self.lte(slot, current_slot);
+        self.assert_is_equal(is_not_future, true_v);
+    }
+
     /// Get a historical block root using state.block_roots for close slots and historical_summaries for slots > 8192 slots away.
     pub fn beacon_get_historical_block(
         &mut self,
@@ -611,6 +772,58 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         block_roots
     }
 
+    /// Returns the current fork version of the beacon state with the given root. The version is
+    /// proven against the state root with the layout of `fork` and must be a version of `fork` on
+    /// a known network, so this also checks that the state has the layout of `fork`.
+    pub fn beacon_get_fork_version(
+        &mut self,
+        state_root: Bytes32Variable,
+        fork: BeaconFork,
+    ) -> BytesVariable<4> {
+        let schema = beacon_state_schema(fork);
+        let leaf = self.beacon_get_state_field(state_root, &schema, &["fork", "current_version"]);
+        self.beacon_decode_fork_version(leaf, fork)
+    }
+
+    /// Verifies the `fork.current_version` leaf of a beacon state with the layout of `fork` and
+    /// returns the version, asserting that it is a version of `fork` on a known network.
+    fn beacon_verify_fork_version(
+        &mut self,
+        state_root: Bytes32Variable,
+        fork: BeaconFork,
+        leaf: Bytes32Variable,
+        branch: &[Bytes32Variable],
+    ) -> BytesVariable<4> {
+        let gindex = beacon_state_schema(fork).gindex(&fork_version_path());
+        self.ssz_verify_proof_const(state_root, leaf, branch, gindex);
+        self.beacon_decode_fork_version(leaf, fork)
+    }
+
+    /// Decodes the `fork.current_version` leaf of a beacon state, which is the version padded
+    /// with zeros, and asserts that it is a version of `fork` on a known network.
+    fn beacon_decode_fork_version(
+        &mut self,
+        leaf: Bytes32Variable,
+        fork: BeaconFork,
+    ) -> BytesVariable<4> {
+        let bytes = leaf.as_bytes();
+        let zero = self.constant::<ByteVariable>(0);
+        for byte in bytes[4..].iter() {
+            self.assert_is_equal(*byte, zero);
+        }
+        let version = BytesVariable::<4>(bytes[..4].try_into().unwrap());
+
+        let mut is_fork_version = self._false();
+        for fork_version in fork.fork_versions() {
+            let fork_version = self.constant::<BytesVariable<4>>(fork_version);
+            let is_equal = self.is_equal(version, fork_version);
+            is_fork_version = self.or(is_fork_version, is_equal);
+        }
+        let true_v = self._true();
+        self.assert_is_equal(is_fork_version, true_v);
+        version
+    }
+
     pub fn beacon_get_graffiti(&mut self, block_root: Bytes32Variable) -> Bytes32Variable {
         let mut input = VariableStream::new();
         input.write(&block_root);
@@ -640,7 +853,275 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         output.read::<ArrayVariable<Bytes32Variable, B>>(self)
     }
 
+    /// Verify a deposit against the deposit contract root using its deposit tree inclusion proof.
+    /// The last element of the branch is the deposit count mixed into the root.
+    ///
+    /// The index of the deposit must be below 2^32, the capacity of the deposit tree. The proof
+    /// only reads the low `DEPOSIT_PROOF_DEPTH` bits of the index, and its bit 32 would hash the
+    /// deposit count on the wrong side, so larger indices are rejected rather than aliased.
+    pub fn beacon_verify_deposit(
+        &mut self,
+        deposit: BeaconDepositVariable,
+        index: U64Variable,
+        branch: ArrayVariable<Bytes32Variable, DEPOSIT_PROOF_DEPTH>,
+        deposit_root: Bytes32Variable,
+    ) {
+        let index_bits = self.to_le_bits(index);
+        let _false = self._false();
+        for bit in index_bits[DEPOSIT_PROOF_DEPTH - 1..].iter() {
+            self.assert_is_equal(*bit, _false);
+        }
+
+        let leaf = self.ssz_hash_tree_root(deposit);
+        self.ssz_verify_proof(deposit_root, leaf, branch.as_slice(), index);
+    }
+
+    /// Get the hash tree root of the field at `path` in the beacon state with the given root,
+    /// where `schema` describes the beacon state. For basic types, this is the serialized field
+    /// padded to 32 bytes.
+    pub fn beacon_get_state_field(
+        &mut self,
+        state_root: Bytes32Variable,
+        schema: &SSZType,
+        path: &[&str],
+    ) -> Bytes32Variable {
+        let path = path.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+        let gindex = schema.gindex(&path);
+        let depth = 63 - gindex.leading_zeros() as usize;
+
+        let mut input_stream = VariableStream::new();
+        input_stream.write(&state_root);
+        let hint = BeaconStateFieldHint {
+            schema: schema.clone(),
+            path,
+        };
+        let output_stream = self.async_hint(input_stream, hint);
+
+        let leaf = output_stream.read::<Bytes32Variable>(self);
+        let proof = (0..depth)
+            .map(|_| output_stream.read::<Bytes32Variable>(self))
+            .collect::<Vec<_>>();
+        self.ssz_verify_proof_const(state_root, leaf, &proof, gindex);
+        leaf
+    }
+
+    /// Verifies that the validator at `validator_index` is the member at `committee_position` of
+    /// the current sync committee, by proving that both pubkeys have the same hash tree root
+    /// against the beacon state with the given root. The state is downloaded once, and its fork
+    /// version is proven to be a version of `fork`, which determines the layout of the state.
+    pub fn verify_sync_committee_membership(
+        &mut self,
+        validator_index: U64Variable,
+        committee_position: usize,
+        state_root: Bytes32Variable,
+        fork: BeaconFork,
+    ) {
+        assert!(
+            committee_position < SYNC_COMMITTEE_SIZE,
+            "committee position {} is out of bounds",
+            committee_position
+        );
+        let schema = beacon_state_schema(fork);
+        let path = |path: &[&str]| path.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+        let committee_position_str = committee_position.to_string();
+        let committee_gindex = schema.gindex(&path(&[
+            "current_sync_committee",
+            "pubkeys",
+            &committee_position_str,
+        ]));
+        let fork_version_gindex = schema.gindex(&fork_version_path());
+
+        // The gindex of `validators[i].pubkey` is `first_gindex + i * stride`.
+        let first_gindex = schema.gindex(&path(&["validators", "0", "pubkey"]));
+        let stride = schema.gindex(&path(&["validators", "1", "pubkey"])) - first_gindex;
+        let validator_depth = 63 - first_gindex.leading_zeros() as usize;
+
+        // Larger indices would overflow into the bits of the gindex of the validators list.
+        let registry_limit = self.constant::<U64Variable>(1 << VALIDATOR_REGISTRY_LIMIT_LOG2);
+        let is_valid_index = self.lt(validator_index, registry_limit);
+        let true_v = self._true();
+        self.assert_is_equal(is_valid_index, true_v);
+        let first_gindex = self.constant::<U64Variable>(first_gindex);
+        let stride = self.constant::<U64Variable>(stride);
+        let offset = self.mul(validator_index, stride);
+        let validator_gindex = self.add(first_gindex, offset);
+
+        let mut input_stream = VariableStream::new();
+        input_stream.write(&state_root);
+        input_stream.write(&validator_index);
+        let hint = BeaconSyncCommitteeMembershipHint {
+            fork,
+            committee_position,
+        };
+        let output_stream = self.async_hint(input_stream, hint);
+
+        let read_branch = |builder: &mut Self, gindex: u64| {
+            (0..63 - gindex.leading_zeros() as usize)
+                .map(|_| output_stream.read::<Bytes32Variable>(builder))
+                .collect::<Vec<_>>()
+        };
+        let pubkey_leaf = output_stream.read::<Bytes32Variable>(self);
+        let validator_branch = (0..validator_depth)
+            .map(|_| output_stream.read::<Bytes32Variable>(self))
+            .collect::<Vec<_>>();
+        let committee_branch = read_branch(self, committee_gindex);
+        let fork_version_leaf = output_stream.read::<Bytes32Variable>(self);
+        let fork_version_branch = read_branch(self, fork_version_gindex);
+
+        // Both pubkeys are checked against the same leaf.
+        self.ssz_verify_proof(state_root, pubkey_leaf, &validator_branch, validator_gindex);
+        self.ssz_verify_proof_const(state_root, pubkey_leaf, &committee_branch, committee_gindex);
+        self.beacon_verify_fork_version(state_root, fork, fork_version_leaf, &fork_version_branch);
+    }
+
+    /// Checks the merkle branches of an altair light client update that advances to the next
+    /// sync committee and returns the signing root that the participating sync committee members
+    /// signed over.
+    ///
+    /// This only checks that the finalized header and the next sync committee are committed to by
+    /// the attested state, that the slots are ordered, and that a supermajority of the bits in
+    /// `sync_committee_bits` are set. Use `verify_sync_committee_update` to also aggregate the
+    /// public keys of the participants.
+    ///
+    /// The branches are checked with the generalized indices of `attested_fork`, the fork of the
+    /// attested state at the slot of the attested header. `fork_version` must be the fork version
+    /// at the epoch of `signature_slot - 1`, as in `compute_fork_version` of the spec.
+    /// Reference: https://github.com/ethereum/consensus-specs/blob/dev/specs/altair/light-client/sync-protocol.md#validate_light_client_update
+    pub fn verify_sync_committee_update_branches(
+        &mut self,
+        update: &BeaconLightClientUpdateVariable,
+        genesis_validators_root: Bytes32Variable,
+        fork_version: BytesVariable<4>,
+        attested_fork: BeaconFork,
+    ) -> Bytes32Variable {
+        let schema = beacon_state_schema(attested_fork);
+
+        // Check that a supermajority of the sync committee participated.
+        let mut participants = self.zero::<Variable>();
+        for bit in update.sync_committee_bits.as_vec() {
+            participants = self.add(participants, bit.variable);
+        }
+        // The number of participants is at most SYNC_COMMITTEE_SIZE, so it fits in a u32.
+        let participants = U32Variable::from_variables_unsafe(&[participants]);
+        let threshold = self.constant::<U32Variable>((2 * SYNC_COMMITTEE_SIZE).div_ceil(3) as u32);
+        let has_supermajority = self.gte(participants, threshold);
+        let true_v = self._true();
+        self.assert_is_equal(has_supermajority, true_v);
+
+        // Check that signature_slot > attested_header.slot >= finalized_header.slot.
+        let attested_slot = update.attested_header.slot;
+        let is_signature_slot_valid = self.gt(update.signature_slot, attested_slot);
+        self.assert_is_equal(is_signature_slot_valid, true_v);
+        let is_finalized_slot_valid = self.gte(attested_slot, update.finalized_header.slot);
+        self.assert_is_equal(is_finalized_slot_valid, true_v);
+
+        // Verify the finalized header against the attested state.
+        let attested_state_root = update.attested_header.state_root;
+        let finalized_root = self.ssz_hash_tree_root(update.finalized_header);
+        let finality_gindex =
+            schema.gindex(&["finalized_checkpoint".to_string(), "root".to_string()]);
+        let finality_depth = 63 - finality_gindex.leading_zeros() as usize;
+        self.ssz_verify_proof_const(
+            attested_state_root,
+            finalized_root,
+            &update.finality_branch.as_slice()[..finality_depth],
+            finality_gindex,
+        );
+
+        // Verify the next sync committee against the attested state.
+        let next_sync_committee_root = self.ssz_hash_tree_root(update.next_sync_committee.clone());
+        let next_sync_committee_gindex = schema.gindex(&["next_sync_committee".to_string()]);
+        let next_sync_committee_depth = 63 - next_sync_committee_gindex.leading_zeros() as usize;
+        self.ssz_verify_proof_const(
+            attested_state_root,
+            next_sync_committee_root,
+            &update.next_sync_committee_branch.as_slice()[..next_sync_committee_depth],
+            next_sync_committee_gindex,
+        );
+
+        // The domain is DOMAIN_SYNC_COMMITTEE followed by the first 28 bytes of the fork data root.
+        let zero = self.constant::<ByteVariable>(0);
+        let mut fork_data = fork_version.0.to_vec();
+        fork_data.extend([zero; 28]);
+        fork_data.extend(genesis_validators_root.as_bytes());
+        let fork_data_root = self.curta_sha256(&fork_data);
+        let mut domain = self
+            .constant::<BytesVariable<4>>(DOMAIN_SYNC_COMMITTEE)
+            .0
+            .to_vec();
+        domain.extend(&fork_data_root.as_bytes()[..28]);
+        let domain = Bytes32Variable(BytesVariable(domain.try_into().unwrap()));
+
+        let attested_root = self.ssz_hash_tree_root(update.attested_header);
+        self.curta_sha256_pair(attested_root, domain)
+    }
+
+    /// Verifies an altair light client update that advances to the next sync committee, and
+    /// returns the signing root together with the aggregate public key of the participants.
+    ///
+    /// On top of `verify_sync_committee_update_branches`, this checks `current_sync_committee`
+    /// against the attested state with `current_sync_committee_branch` (padded with zeros like the
+    /// branches of the update), checks that the update is signed in the period of the attested
+    /// header, so that the current sync committee of the attested state is the signing committee,
+    /// and aggregates the public keys of the members selected by `sync_committee_bits`.
+    ///
+    /// The BLS pairing check is NOT done: there is no hash to G2 or pairing gadget in this crate,
+    /// so the update must not be trusted until the sync aggregate signature is verified against
+    /// the returned aggregate public key and signing root, i.e. until
+    /// `e(aggregate_pubkey, hash_to_g2(signing_root)) == e(g1, signature)` is checked.
+    pub fn verify_sync_committee_update(
+        &mut self,
+        update: &BeaconLightClientUpdateVariable,
+        current_sync_committee: &BeaconSyncCommitteeVariable,
+        current_sync_committee_branch: &ArrayVariable<
+            Bytes32Variable,
+            MAX_NEXT_SYNC_COMMITTEE_BRANCH_DEPTH,
+        >,
+        genesis_validators_root: Bytes32Variable,
+        fork_version: BytesVariable<4>,
+        attested_fork: BeaconFork,
+    ) -> (Bytes32Variable, G1PointVariable) {
+        let signing_root = self.verify_sync_committee_update_branches(
+            update,
+            genesis_validators_root,
+            fork_version,
+            attested_fork,
+        );
+
+        // The signing committee is the current committee of the attested state as long as the
+        // update is signed in the same period.
+        let slots_per_period = self.constant::<U64Variable>(SLOTS_PER_SYNC_COMMITTEE_PERIOD);
+        let attested_period = self.div(update.attested_header.slot, slots_per_period);
+        let signature_period = self.div(update.signature_slot, slots_per_period);
+        self.assert_is_equal(attested_period, signature_period);
+
+        let schema = beacon_state_schema(attested_fork);
+        let committee_root = self.ssz_hash_tree_root(current_sync_committee.clone());
+        let committee_gindex = schema.gindex(&["current_sync_committee".to_string()]);
+        let committee_depth = 63 - committee_gindex.leading_zeros() as usize;
+        self.ssz_verify_proof_const(
+            update.attested_header.state_root,
+            committee_root,
+            &current_sync_committee_branch.as_slice()[..committee_depth],
+            committee_gindex,
+        );
+
+        let pubkeys = current_sync_committee
+            .pubkeys
+            .as_vec()
+            .into_iter()
+            .map(|pubkey| self.g1_decompress(pubkey))
+            .collect::<Vec<_>>();
+        let aggregate_pubkey =
+            self.aggregate_pubkeys(&pubkeys, update.sync_committee_bits.as_slice());
+
+        (signing_root, aggregate_pubkey)
+    }
+
     /// Verify a simple serialize (ssz) merkle proof with a dynamic index.
+    ///
+    /// `gindex` is a generalized index, of which only the bits below the depth of the branch are
+    /// used. Use `verify_merkle_proof` to also check the depth of the index.
     #[allow(unused_variables)]
     pub fn ssz_verify_proof(
         &mut self,
@@ -653,7 +1134,7 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         self.assert_is_equal(root, expected_root);
     }
 
-    /// Verify a simple serialize (ssz) merkle proof with a constant index.
+    /// Verify a simple serialize (ssz) merkle proof with a constant generalized index.
     #[allow(unused_variables)]
     pub fn ssz_verify_proof_const(
         &mut self,
@@ -736,14 +1217,27 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
 pub(crate) mod tests {
     use std::env;
 
+    use ethers::types::{H256, U128};
     use log::debug;
+    use tokio::runtime::Runtime;
 
-    use crate::backend::circuit::DefaultParameters;
+    use crate::backend::circuit::{DefaultParameters, PlonkParameters};
     use crate::frontend::builder::CircuitBuilder;
+    use crate::frontend::ecc::bls12_381::g1::tests::{add, decompress, value};
+    use crate::frontend::ecc::bls12_381::g1::G1PointVariable;
+    use crate::frontend::eth::beacon::vars::{
+        BeaconDepositValue, BeaconDepositVariable, BeaconHeaderValue, BeaconHeaderVariable,
+        BeaconLightClientUpdateValue, BeaconLightClientUpdateVariable, BeaconSyncCommitteeValue,
+        BeaconSyncCommitteeVariable, MAX_FINALITY_BRANCH_DEPTH,
+        MAX_NEXT_SYNC_COMMITTEE_BRANCH_DEPTH,
+    };
     use crate::frontend::eth::vars::BLSPubkeyVariable;
     use crate::frontend::uint::uint64::U64Variable;
-    use crate::frontend::vars::Bytes32Variable;
-    use crate::utils::eth::beacon::BeaconClient;
+    use crate::frontend::vars::{Bytes32Variable, BytesVariable};
+    use crate::prelude::{ArrayVariable, U128Variable};
+    use crate::utils::eth::beacon::ssz::{beacon_state_schema, SSZType};
+    use crate::utils::eth::beacon::{BeaconClient, BeaconHeader, LightClientUpdate};
+    use crate::utils::hash::sha256;
     use crate::utils::{bytes, bytes32};
 
     type L = DefaultParameters;
@@ -1109,6 +1603,100 @@ pub(crate) mod tests {
         circuit.test_default_serializers();
     }
 
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_beacon_get_state_field() {
+        env_logger::try_init().unwrap_or_default();
+        dotenv::dotenv().ok();
+
+        let consensus_rpc = env::var("CONSENSUS_RPC_URL").unwrap();
+        let client = BeaconClient::new(consensus_rpc);
+        let latest_block_root = client.get_finalized_block_root().unwrap();
+        let fork = client.get_fork(latest_block_root.clone()).unwrap();
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        builder.set_beacon_client(client);
+
+        let block_root = builder.constant::<Bytes32Variable>(bytes32!(latest_block_root));
+        let header = builder.beacon_get_block_header(block_root);
+        let schema = beacon_state_schema(fork);
+        let slot_leaf = builder.beacon_get_state_field(header.state_root, &schema, &["slot"]);
+        let expected_slot_leaf = builder.ssz_hash_tree_root(header.slot);
+        builder.assert_is_equal(slot_leaf, expected_slot_leaf);
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        circuit.test_default_serializers();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_beacon_get_fork_version() {
+        env_logger::try_init().unwrap_or_default();
+        dotenv::dotenv().ok();
+
+        let consensus_rpc = env::var("CONSENSUS_RPC_URL").unwrap();
+        let client = BeaconClient::new(consensus_rpc);
+        let latest_block_root = client.get_finalized_block_root().unwrap();
+        let rt = Runtime::new().unwrap();
+        let header = rt
+            .block_on(client.get_header(latest_block_root.clone()))
+            .unwrap();
+        let fork = client.get_fork(header.state_root.clone()).unwrap();
+        let fork_version: [u8; 4] =
+            bytes!(client.get_fork_version(header.state_root.clone()).unwrap());
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let state_root = builder.constant::<Bytes32Variable>(bytes32!(header.state_root));
+        let version = builder.beacon_get_fork_version(state_root, fork);
+        builder.write(version);
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        assert_eq!(output.read::<BytesVariable<4>>(), fork_version);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_verify_sync_committee_membership() {
+        env_logger::try_init().unwrap_or_default();
+        dotenv::dotenv().ok();
+
+        let consensus_rpc = env::var("CONSENSUS_RPC_URL").unwrap();
+        let client = BeaconClient::new(consensus_rpc);
+        let latest_block_root = client.get_finalized_block_root().unwrap();
+        let rt = Runtime::new().unwrap();
+        let header = rt
+            .block_on(client.get_header(latest_block_root.clone()))
+            .unwrap();
+        let committee = client
+            .get_sync_committee_indices(header.state_root.clone())
+            .unwrap();
+        let committee_position = 7;
+        let validator_index = committee[committee_position];
+        let fork = client.get_fork(header.state_root.clone()).unwrap();
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let state_root = builder.constant::<Bytes32Variable>(bytes32!(header.state_root));
+        let validator_index_var = builder.read::<U64Variable>();
+        builder.verify_sync_committee_membership(
+            validator_index_var,
+            committee_position,
+            state_root,
+            fork,
+        );
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.write::<U64Variable>(validator_index);
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
     #[test]
     #[cfg_attr(feature = "ci", ignore)]
     fn test_beacon_witness_headers_from_offset_range() {
@@ -1275,4 +1863,393 @@ pub(crate) mod tests {
         circuit.verify(&proof, &input, &output);
         circuit.test_default_serializers();
     }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_beacon_verify_deposit() {
+        env_logger::try_init().unwrap_or_default();
+        dotenv::dotenv().ok();
+
+        let consensus_rpc = env::var("CONSENSUS_RPC_URL").unwrap();
+        let client = BeaconClient::new(consensus_rpc);
+
+        // The eth1 data of the mainnet voting period starting at slot 7340032 can only be updated
+        // once half of its 2048 slots have voted, so the first block with deposits is searched
+        // from the middle of the period.
+        let block = (7341056..7342080u64)
+            .find_map(|slot| {
+                client
+                    .get_block_deposits(slot.to_string())
+                    .ok()
+                    .filter(|block| !block.deposits.is_empty())
+            })
+            .expect("no block with deposits in the voting period");
+
+        // The deposits of a block are proven against the eth1 data of its state, which holds
+        // the index of the next deposit after the deposits of the block are processed.
+        let state = Runtime::new()
+            .unwrap()
+            .block_on(client.get_state_ssz(block.state_root.clone()))
+            .unwrap();
+        let schema = beacon_state_schema(client.get_fork(block.state_root.clone()).unwrap());
+        assert_eq!(
+            schema.hash_tree_root(&state),
+            bytes32!(block.state_root.as_str())
+        );
+        let deposit_root = schema
+            .prove(
+                &state,
+                &["eth1_data".to_string(), "deposit_root".to_string()],
+            )
+            .leaf;
+        let next_index = schema
+            .prove(&state, &["eth1_deposit_index".to_string()])
+            .value;
+        let next_index = u64::from_le_bytes(next_index.try_into().unwrap());
+
+        let deposit = block.deposits.last().unwrap();
+        let index = next_index - 1;
+        let deposit_value = BeaconDepositValue::<<L as PlonkParameters<D>>::Field> {
+            pubkey: bytes!(deposit.data.pubkey.as_str()),
+            withdrawal_credentials: bytes32!(deposit.data.withdrawal_credentials.as_str()),
+            amount: deposit.data.amount.parse().unwrap(),
+            signature: bytes!(deposit.data.signature.as_str()),
+        };
+        let branch = deposit
+            .proof
+            .iter()
+            .map(|node| bytes32!(node.as_str()))
+            .collect::<Vec<_>>();
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let deposit = builder.constant::<BeaconDepositVariable>(deposit_value);
+        let index_variable = builder.read::<U64Variable>();
+        let branch = builder.constant::<ArrayVariable<Bytes32Variable, 33>>(branch);
+        let deposit_root = builder.constant::<Bytes32Variable>(deposit_root);
+        builder.beacon_verify_deposit(deposit, index_variable, branch, deposit_root);
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.write::<U64Variable>(index);
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        // A different index, or one that only agrees with the index on the bits read by the
+        // proof, is rejected.
+        for wrong_index in [index ^ 1, index + (1 << 33)] {
+            let mut input = circuit.input();
+            input.write::<U64Variable>(wrong_index);
+            assert!(circuit.mock_prove(&input).is_err());
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_beacon_sum_balances() {
+        env_logger::try_init().unwrap_or_default();
+
+        let balances = (0..13u64)
+            .map(|i| 32_000_000_000 + i * 1_234_567)
+            .collect::<Vec<_>>();
+        let serialized = balances
+            .iter()
+            .flat_map(|b| b.to_le_bytes())
+            .collect::<Vec<_>>();
+        let balances_root = SSZType::list(SSZType::Uint(8), 1 << 40).hash_tree_root(&serialized);
+        let total = balances.iter().map(|b| *b as u128).sum::<u128>();
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let root = builder.read::<Bytes32Variable>();
+        let padded = builder.read::<ArrayVariable<U64Variable, 16>>();
+        let length = builder.read::<U64Variable>();
+        let claimed_total = builder.read::<U128Variable>();
+        let sum = builder.beacon_sum_balances(root, padded.as_slice(), length);
+        builder.assert_is_equal(sum, claimed_total);
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.write::<Bytes32Variable>(balances_root);
+        let mut padded = balances.clone();
+        padded.resize(16, 0);
+        input.write::<ArrayVariable<U64Variable, 16>>(padded);
+        input.write::<U64Variable>(balances.len() as u64);
+        input.write::<U128Variable>(U128::from(total));
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
+    #[test]
+    fn test_assert_within_epoch_bounds() {
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let slot = builder.read::<U64Variable>();
+        let epoch = builder.read::<U64Variable>();
+        let current_slot = builder.read::<U64Variable>();
+        builder.assert_within_epoch_bounds(slot, epoch, current_slot);
+        let circuit = builder.build();
+
+        let cases = [
+            ((7_000_031, 218_750, 7_000_100), true),
+            ((7_000_032, 218_751, 7_000_032), true),
+            // The slot is not in the epoch.
+            ((7_000_032, 218_750, 7_000_100), false),
+            ((7_000_031, 218_751, 7_000_100), false),
+            // The slot is in the future.
+            ((7_000_101, 218_753, 7_000_100), false),
+        ];
+        for ((slot, epoch, current_slot), valid) in cases {
+            let mut input = circuit.input();
+            input.write::<U64Variable>(slot);
+            input.write::<U64Variable>(epoch);
+            input.write::<U64Variable>(current_slot);
+            assert_eq!(circuit.mock_prove(&input).is_ok(), valid);
+        }
+    }
+
+    fn header_value(header: &BeaconHeader) -> BeaconHeaderValue<<L as PlonkParameters<D>>::Field> {
+        BeaconHeaderValue {
+            slot: header.slot.parse().unwrap(),
+            proposer_index: header.proposer_index.parse().unwrap(),
+            parent_root: bytes32!(header.parent_root),
+            state_root: bytes32!(header.state_root),
+            body_root: bytes32!(header.body_root),
+        }
+    }
+
+    /// Parses a merkle branch and pads it with zeros to `depth` nodes.
+    fn pad_branch(branch: &[String], depth: usize) -> Vec<H256> {
+        let mut branch = branch.iter().map(|node| bytes32!(node)).collect::<Vec<_>>();
+        branch.resize(depth, H256::zero());
+        branch
+    }
+
+    /// Converts a light client update of the beacon API to its circuit value.
+    fn light_client_update_value(
+        update: &LightClientUpdate,
+    ) -> BeaconLightClientUpdateValue<<L as PlonkParameters<D>>::Field> {
+        let bits: [u8; 64] = bytes!(update.sync_aggregate.sync_committee_bits);
+        BeaconLightClientUpdateValue {
+            attested_header: header_value(&update.attested_header.beacon),
+            next_sync_committee: BeaconSyncCommitteeValue {
+                pubkeys: update
+                    .next_sync_committee
+                    .pubkeys
+                    .iter()
+                    .map(|pubkey| bytes!(pubkey))
+                    .collect(),
+                aggregate_pubkey: bytes!(update.next_sync_committee.aggregate_pubkey),
+            },
+            next_sync_committee_branch: pad_branch(
+                &update.next_sync_committee_branch,
+                MAX_NEXT_SYNC_COMMITTEE_BRANCH_DEPTH,
+            ),
+            finalized_header: header_value(&update.finalized_header.beacon),
+            finality_branch: pad_branch(&update.finality_branch, MAX_FINALITY_BRANCH_DEPTH),
+            sync_committee_bits: (0..512)
+                .map(|i| (bits[i / 8] >> (i % 8)) & 1 == 1)
+                .collect(),
+            signature_slot: update.signature_slot.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_verify_sync_committee_update_branches() {
+        env_logger::try_init().unwrap_or_default();
+        dotenv::dotenv().ok();
+
+        let consensus_rpc = env::var("CONSENSUS_RPC_URL").unwrap();
+        let client = BeaconClient::new(consensus_rpc);
+        let finalized_slot = client.get_finalized_slot().unwrap().parse::<u64>().unwrap();
+        let period = finalized_slot / 8192 - 1;
+        let update = client.get_light_client_update(period).unwrap();
+        let genesis_validators_root = bytes32!(client.get_genesis_validators_root().unwrap());
+
+        // The signature is over the fork version of the epoch before the signature slot.
+        let signature_slot = update.signature_slot.parse::<u64>().unwrap();
+        let fork_version: [u8; 4] = bytes!(client
+            .get_fork_version((signature_slot.max(1) - 1).to_string())
+            .unwrap());
+        let attested_fork = client
+            .get_fork(update.attested_header.beacon.slot.clone())
+            .unwrap();
+
+        let update_value = light_client_update_value(&update);
+
+        // Compute the expected signing root outside of the circuit.
+        let header = &update.attested_header.beacon;
+        let header_schema = SSZType::container(&[
+            ("slot", SSZType::Uint(8)),
+            ("proposer_index", SSZType::Uint(8)),
+            ("parent_root", SSZType::ByteVector(32)),
+            ("state_root", SSZType::ByteVector(32)),
+            ("body_root", SSZType::ByteVector(32)),
+        ]);
+        let mut serialized_header = Vec::new();
+        serialized_header.extend(header.slot.parse::<u64>().unwrap().to_le_bytes());
+        serialized_header.extend(header.proposer_index.parse::<u64>().unwrap().to_le_bytes());
+        serialized_header.extend(bytes32!(header.parent_root).as_bytes());
+        serialized_header.extend(bytes32!(header.state_root).as_bytes());
+        serialized_header.extend(bytes32!(header.body_root).as_bytes());
+        let attested_root = header_schema.hash_tree_root(&serialized_header);
+        let fork_data_root = sha256(
+            &[
+                [fork_version.as_slice(), &[0u8; 28][..]].concat(),
+                genesis_validators_root.as_bytes().to_vec(),
+            ]
+            .concat(),
+        );
+        let domain = [[7u8, 0, 0, 0].as_slice(), &fork_data_root[..28]].concat();
+        let signing_root = H256::from(sha256(
+            &[attested_root.as_bytes(), domain.as_slice()].concat(),
+        ));
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let update = builder.read::<BeaconLightClientUpdateVariable>();
+        let genesis_validators_root_var = builder.read::<Bytes32Variable>();
+        let fork_version_var = builder.read::<BytesVariable<4>>();
+        let computed_signing_root = builder.verify_sync_committee_update_branches(
+            &update,
+            genesis_validators_root_var,
+            fork_version_var,
+            attested_fork,
+        );
+        builder.write(computed_signing_root);
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.write::<BeaconLightClientUpdateVariable>(update_value);
+        input.write::<Bytes32Variable>(genesis_validators_root);
+        input.write::<BytesVariable<4>>(fork_version);
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        assert_eq!(output.read::<Bytes32Variable>(), signing_root);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_verify_sync_committee_update() {
+        env_logger::try_init().unwrap_or_default();
+        dotenv::dotenv().ok();
+
+        let consensus_rpc = env::var("CONSENSUS_RPC_URL").unwrap();
+        let client = BeaconClient::new(consensus_rpc);
+        let finalized_slot = client.get_finalized_slot().unwrap().parse::<u64>().unwrap();
+        let period = finalized_slot / 8192 - 1;
+        let update = client.get_light_client_update(period).unwrap();
+        let genesis_validators_root = bytes32!(client.get_genesis_validators_root().unwrap());
+        let update_value = light_client_update_value(&update);
+        let signature_slot = update_value.signature_slot;
+        let fork_version: [u8; 4] = bytes!(client
+            .get_fork_version((signature_slot.max(1) - 1).to_string())
+            .unwrap());
+        let attested_fork = client
+            .get_fork(update.attested_header.beacon.slot.clone())
+            .unwrap();
+
+        // The current sync committee of the attested state signed the update.
+        let attested_state_root = update.attested_header.beacon.state_root.clone();
+        let state = Runtime::new()
+            .unwrap()
+            .block_on(client.get_state_ssz(attested_state_root))
+            .unwrap();
+        let proof =
+            beacon_state_schema(attested_fork).prove(&state, &["current_sync_committee".into()]);
+        let committee_pubkeys = proof
+            .value
+            .chunks_exact(48)
+            .map(|pubkey| <[u8; 48]>::try_from(pubkey).unwrap())
+            .collect::<Vec<_>>();
+        let (aggregate_pubkey, pubkeys) = committee_pubkeys.split_last().unwrap();
+        let committee_value = BeaconSyncCommitteeValue {
+            pubkeys: pubkeys.to_vec(),
+            aggregate_pubkey: *aggregate_pubkey,
+        };
+        let mut committee_branch = proof.branch.clone();
+        committee_branch.resize(MAX_NEXT_SYNC_COMMITTEE_BRANCH_DEPTH, H256::zero());
+
+        // Aggregate the public keys of the participants outside of the circuit, and check that
+        // all of the keys sum to the aggregate public key of the committee.
+        let points = pubkeys.iter().map(decompress).collect::<Vec<_>>();
+        let all = points.iter().fold(None, |acc, point| add(&acc, point));
+        assert_eq!(all, decompress(aggregate_pubkey));
+        let participants = points
+            .iter()
+            .zip(update_value.sync_committee_bits.iter())
+            .filter(|(_, participated)| **participated)
+            .fold(None, |acc, (point, _)| add(&acc, point));
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let update = builder.read::<BeaconLightClientUpdateVariable>();
+        let committee = builder.read::<BeaconSyncCommitteeVariable>();
+        let committee_branch_var =
+            builder.read::<ArrayVariable<Bytes32Variable, MAX_NEXT_SYNC_COMMITTEE_BRANCH_DEPTH>>();
+        let genesis_validators_root_var = builder.read::<Bytes32Variable>();
+        let fork_version_var = builder.read::<BytesVariable<4>>();
+        let (_, aggregate) = builder.verify_sync_committee_update(
+            &update,
+            &committee,
+            &committee_branch_var,
+            genesis_validators_root_var,
+            fork_version_var,
+            attested_fork,
+        );
+        builder.write(aggregate);
+
+        // The circuit aggregates 512 keys, so only its witness is generated.
+        let circuit = builder.mock_build();
+        let mut input = circuit.input();
+        input.write::<BeaconLightClientUpdateVariable>(update_value);
+        input.write::<BeaconSyncCommitteeVariable>(committee_value);
+        input.write::<ArrayVariable<Bytes32Variable, MAX_NEXT_SYNC_COMMITTEE_BRANCH_DEPTH>>(
+            committee_branch,
+        );
+        input.write::<Bytes32Variable>(genesis_validators_root);
+        input.write::<BytesVariable<4>>(fork_version);
+        let (_, mut output) = circuit.mock_prove(&input);
+
+        let aggregate = output.read::<G1PointVariable>();
+        let expected = value(&participants);
+        assert!(!aggregate.is_infinity);
+        assert_eq!(aggregate.x, expected.x);
+        assert_eq!(aggregate.y, expected.y);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_verify_block_chain() {
+        env_logger::try_init().unwrap_or_default();
+        dotenv::dotenv().ok();
+
+        // Walk back three blocks from the latest finalized block.
+        let consensus_rpc = env::var("CONSENSUS_RPC_URL").unwrap();
+        let client = BeaconClient::new(consensus_rpc);
+        let rt = Runtime::new().unwrap();
+        let mut roots = vec![client.get_finalized_block_root().unwrap()];
+        let mut headers = Vec::new();
+        for _ in 0..3 {
+            let header = rt
+                .block_on(client.get_header(roots.last().unwrap().clone()))
+                .unwrap();
+            roots.push(header.parent_root.clone());
+            headers.push(header_value(&header));
+        }
+        roots.pop();
+        roots.reverse();
+        headers.reverse();
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let roots_var = builder.read::<ArrayVariable<Bytes32Variable, 3>>();
+        let headers_var = builder.read::<ArrayVariable<BeaconHeaderVariable, 3>>();
+        builder.verify_block_chain(roots_var.as_slice(), headers_var.as_slice());
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<Bytes32Variable, 3>>(
+            roots.iter().map(|root| bytes32!(root)).collect(),
+        );
+        input.write::<ArrayVariable<BeaconHeaderVariable, 3>>(headers);
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
 }
@@ -9,6 +9,9 @@ mod headers;
 mod historical;
 mod partial_balances;
 mod partial_validators;
+mod state_field;
+mod sync_committee;
+mod total_balance;
 mod validator;
 mod validator_subtree;
 mod validator_witness;
@@ -29,6 +32,9 @@ pub use historical::{
 };
 pub use partial_balances::BeaconPartialBalancesHint;
 pub use partial_validators::BeaconPartialValidatorsHint;
+pub use state_field::BeaconStateFieldHint;
+pub use sync_committee::BeaconSyncCommitteeMembershipHint;
+pub use total_balance::BeaconTotalBalanceGenerator;
 pub use validator::BeaconValidatorGenerator;
 pub use validator_subtree::{
     BeaconValidatorSubtreeHint, BeaconValidatorSubtreePoseidonHint, BeaconValidatorSubtreesHint,
@@ -0,0 +1,46 @@
+use std::env;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::frontend::hint::asynchronous::hint::AsyncHint;
+use crate::frontend::vars::ValueStream;
+use crate::prelude::{Bytes32Variable, PlonkParameters};
+use crate::utils::eth::beacon::ssz::SSZType;
+use crate::utils::eth::beacon::BeaconClient;
+use crate::utils::hex;
+
+/// Input: (state_root: bytes32)
+/// Output: (leaf: bytes32, branch: bytes32[depth])
+///
+/// Deserializes the beacon state with the given schema and extracts the field at `path` along
+/// with its merkle proof to the state root.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BeaconStateFieldHint {
+    pub schema: SSZType,
+    pub path: Vec<String>,
+}
+
+#[async_trait]
+impl<L: PlonkParameters<D>, const D: usize> AsyncHint<L, D> for BeaconStateFieldHint {
+    async fn hint(
+        &self,
+        input_stream: &mut ValueStream<L, D>,
+        output_stream: &mut ValueStream<L, D>,
+    ) {
+        let client = BeaconClient::new(env::var("CONSENSUS_RPC_URL").unwrap());
+        let state_root = input_stream.read_value::<Bytes32Variable>();
+
+        let state = client
+            .get_state_ssz(hex!(state_root.as_bytes()))
+            .await
+            .unwrap();
+        assert_eq!(self.schema.hash_tree_root(&state), state_root);
+
+        let proof = self.schema.prove(&state, &self.path);
+        output_stream.write_value::<Bytes32Variable>(proof.leaf);
+        for node in proof.branch {
+            output_stream.write_value::<Bytes32Variable>(node);
+        }
+    }
+}
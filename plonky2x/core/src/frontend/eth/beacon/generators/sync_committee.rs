@@ -0,0 +1,75 @@
+use std::env;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::frontend::hint::asynchronous::hint::AsyncHint;
+use crate::frontend::uint::uint64::U64Variable;
+use crate::frontend::vars::ValueStream;
+use crate::prelude::{Bytes32Variable, PlonkParameters};
+use crate::utils::eth::beacon::ssz::beacon_state_schema;
+use crate::utils::eth::beacon::{BeaconClient, BeaconFork};
+use crate::utils::hex;
+
+/// Input: (state_root: bytes32, validator_index: u64)
+/// Output: (pubkey_leaf: bytes32, validator_branch: bytes32[validator_depth],
+///          committee_branch: bytes32[committee_depth], fork_version_leaf: bytes32,
+///          fork_version_branch: bytes32[fork_version_depth])
+///
+/// Downloads the beacon state once and extracts the proofs of the pubkey of the validator at
+/// `validator_index`, of the pubkey at `committee_position` of the current sync committee, and of
+/// the current fork version of the state. The pubkey leaf is the hash tree root of the validator's
+/// pubkey, which the committee proof is checked against.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BeaconSyncCommitteeMembershipHint {
+    /// The fork of the beacon state, which determines its layout.
+    pub fork: BeaconFork,
+    pub committee_position: usize,
+}
+
+#[async_trait]
+impl<L: PlonkParameters<D>, const D: usize> AsyncHint<L, D> for BeaconSyncCommitteeMembershipHint {
+    async fn hint(
+        &self,
+        input_stream: &mut ValueStream<L, D>,
+        output_stream: &mut ValueStream<L, D>,
+    ) {
+        let client = BeaconClient::new(env::var("CONSENSUS_RPC_URL").unwrap());
+        let state_root = input_stream.read_value::<Bytes32Variable>();
+        let validator_index = input_stream.read_value::<U64Variable>();
+
+        let state = client
+            .get_state_ssz(hex!(state_root.as_bytes()))
+            .await
+            .unwrap();
+        let schema = beacon_state_schema(self.fork);
+        assert_eq!(schema.hash_tree_root(&state), state_root);
+
+        let validator_path = [
+            "validators".to_string(),
+            validator_index.to_string(),
+            "pubkey".to_string(),
+        ];
+        let committee_path = [
+            "current_sync_committee".to_string(),
+            "pubkeys".to_string(),
+            self.committee_position.to_string(),
+        ];
+        let fork_version_path = ["fork".to_string(), "current_version".to_string()];
+        let validator_proof = schema.prove(&state, &validator_path);
+        let committee_proof = schema.prove(&state, &committee_path);
+        let fork_version_proof = schema.prove(&state, &fork_version_path);
+
+        output_stream.write_value::<Bytes32Variable>(validator_proof.leaf);
+        for node in validator_proof.branch {
+            output_stream.write_value::<Bytes32Variable>(node);
+        }
+        for node in committee_proof.branch {
+            output_stream.write_value::<Bytes32Variable>(node);
+        }
+        output_stream.write_value::<Bytes32Variable>(fork_version_proof.leaf);
+        for node in fork_version_proof.branch {
+            output_stream.write_value::<Bytes32Variable>(node);
+        }
+    }
+}
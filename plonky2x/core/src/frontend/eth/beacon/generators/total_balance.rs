@@ -0,0 +1,77 @@
+use std::env;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::frontend::hint::asynchronous::hint::AsyncHint;
+use crate::frontend::uint::uint64::U64Variable;
+use crate::frontend::vars::ValueStream;
+use crate::prelude::{Bytes32Variable, PlonkParameters};
+use crate::utils::eth::beacon::ssz::beacon_state_schema;
+use crate::utils::eth::beacon::{BeaconClient, BeaconFork};
+use crate::utils::hex;
+
+/// Input: (state_root: bytes32)
+/// Output: (balances_root: bytes32, branch: bytes32[depth], length: u64, balances: u64[B],
+///          fork_version_leaf: bytes32, fork_version_branch: bytes32[fork_version_depth])
+///
+/// Extracts all validator balances from the beacon state along with the merkle proof of the
+/// balances root to the state root, and the proof of the current fork version of the state. The
+/// balances are padded with zeros up to `B`.
+///
+/// The whole ssz serialized beacon state is downloaded and held in memory, along with the `B`
+/// balances, so `B` and the maximum response size of the beacon client bound the memory used by
+/// the hint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BeaconTotalBalanceGenerator<const B: usize> {
+    /// The fork of the beacon state, which determines its layout.
+    pub fork: BeaconFork,
+}
+
+#[async_trait]
+impl<L: PlonkParameters<D>, const D: usize, const B: usize> AsyncHint<L, D>
+    for BeaconTotalBalanceGenerator<B>
+{
+    async fn hint(
+        &self,
+        input_stream: &mut ValueStream<L, D>,
+        output_stream: &mut ValueStream<L, D>,
+    ) {
+        let client = BeaconClient::new(env::var("CONSENSUS_RPC_URL").unwrap());
+        let state_root = input_stream.read_value::<Bytes32Variable>();
+
+        let state = client
+            .get_state_ssz(hex!(state_root.as_bytes()))
+            .await
+            .unwrap();
+        let schema = beacon_state_schema(self.fork);
+        let proof = schema.prove(&state, &["balances".to_string()]);
+        output_stream.write_value::<Bytes32Variable>(proof.leaf);
+        for node in proof.branch {
+            output_stream.write_value::<Bytes32Variable>(node);
+        }
+
+        let nb_balances = proof.value.len() / 8;
+        assert!(
+            nb_balances <= B,
+            "state has {} balances but at most {} are supported",
+            nb_balances,
+            B
+        );
+        output_stream.write_value::<U64Variable>(nb_balances as u64);
+        let balances = proof
+            .value
+            .chunks_exact(8)
+            .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()));
+        for balance in balances.chain(std::iter::repeat(0)).take(B) {
+            output_stream.write_value::<U64Variable>(balance);
+        }
+
+        let fork_version_path = ["fork".to_string(), "current_version".to_string()];
+        let fork_version_proof = schema.prove(&state, &fork_version_path);
+        output_stream.write_value::<Bytes32Variable>(fork_version_proof.leaf);
+        for node in fork_version_proof.branch {
+            output_stream.write_value::<Bytes32Variable>(node);
+        }
+    }
+}
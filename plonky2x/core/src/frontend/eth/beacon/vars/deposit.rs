@@ -0,0 +1,50 @@
+use std::fmt::Debug;
+
+use plonky2::hash::hash_types::RichField;
+use plonky2x_derive::CircuitVariable;
+
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::eth::vars::BLSPubkeyVariable;
+use crate::frontend::uint::uint64::U64Variable;
+use crate::frontend::vars::{Bytes32Variable, BytesVariable, CircuitVariable, SSZVariable};
+use crate::prelude::{ByteVariable, Variable};
+
+/// The `DepositData` container submitted to the deposit contract.
+#[derive(Debug, Copy, Clone, CircuitVariable)]
+#[value_name(BeaconDepositValue)]
+pub struct BeaconDepositVariable {
+    pub pubkey: BLSPubkeyVariable,
+    pub withdrawal_credentials: Bytes32Variable,
+    pub amount: U64Variable,
+    pub signature: BytesVariable<96>,
+}
+
+impl SSZVariable for BeaconDepositVariable {
+    fn hash_tree_root<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Bytes32Variable {
+        let zero = builder.constant::<ByteVariable>(0);
+
+        let mut pubkey_serialized = self.pubkey.0 .0.to_vec();
+        pubkey_serialized.extend([zero; 16]);
+        let pubkey_leaf = builder.curta_sha256(&pubkey_serialized);
+
+        let amount_leaf = self.amount.hash_tree_root(builder);
+
+        // The signature is 96 bytes, so it is merkleized as 3 chunks padded with a zero chunk.
+        let sig_a = builder.curta_sha256(&self.signature.0[..64]);
+
+        let mut sig_b_input = Vec::new();
+        sig_b_input.extend(&self.signature.0[64..]);
+        sig_b_input.extend([zero; 32]);
+        let sig_b = builder.curta_sha256(&sig_b_input);
+
+        let signature_leaf = builder.curta_sha256_pair(sig_a, sig_b);
+
+        let ab = builder.curta_sha256_pair(pubkey_leaf, self.withdrawal_credentials);
+        let cd = builder.curta_sha256_pair(amount_leaf, signature_leaf);
+        builder.curta_sha256_pair(ab, cd)
+    }
+}
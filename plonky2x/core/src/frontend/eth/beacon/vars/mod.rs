@@ -1,6 +1,8 @@
 mod balances;
 mod compressed_validator;
+mod deposit;
 mod header;
+mod sync_committee;
 mod validator;
 mod validators;
 mod withdrawal;
@@ -8,7 +10,9 @@ mod withdrawals;
 
 pub use balances::*;
 pub use compressed_validator::*;
+pub use deposit::*;
 pub use header::*;
+pub use sync_committee::*;
 pub use validator::*;
 pub use validators::*;
 pub use withdrawal::*;
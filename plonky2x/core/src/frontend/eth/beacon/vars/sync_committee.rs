@@ -0,0 +1,72 @@
+use std::fmt::Debug;
+
+use plonky2::hash::hash_types::RichField;
+use plonky2x_derive::CircuitVariable;
+
+use super::BeaconHeaderVariable;
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::eth::vars::BLSPubkeyVariable;
+use crate::frontend::uint::uint64::U64Variable;
+use crate::frontend::vars::{Bytes32Variable, CircuitVariable, SSZVariable};
+use crate::prelude::{ArrayVariable, BoolVariable, ByteVariable, Variable};
+
+/// Beacon chain constant SYNC_COMMITTEE_SIZE.
+pub const SYNC_COMMITTEE_SIZE: usize = 512;
+
+/// The maximum depth of the proof from stateRoot -> finalizedCheckpoint.root over all forks,
+/// which is 6 up to deneb and 7 from electra onwards.
+pub const MAX_FINALITY_BRANCH_DEPTH: usize = 7;
+
+/// The maximum depth of the proof from stateRoot -> nextSyncCommittee over all forks, which is 5
+/// up to deneb and 6 from electra onwards.
+pub const MAX_NEXT_SYNC_COMMITTEE_BRANCH_DEPTH: usize = 6;
+
+/// The `SyncCommittee` container.
+#[derive(Debug, Clone, CircuitVariable)]
+#[value_name(BeaconSyncCommitteeValue)]
+pub struct BeaconSyncCommitteeVariable {
+    pub pubkeys: ArrayVariable<BLSPubkeyVariable, SYNC_COMMITTEE_SIZE>,
+    pub aggregate_pubkey: BLSPubkeyVariable,
+}
+
+impl SSZVariable for BeaconSyncCommitteeVariable {
+    fn hash_tree_root<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Bytes32Variable {
+        let zero = builder.constant::<ByteVariable>(0);
+        let mut pubkey_leaf = |pubkey: &BLSPubkeyVariable| {
+            let mut serialized = pubkey.0 .0.to_vec();
+            serialized.extend([zero; 16]);
+            builder.curta_sha256(&serialized)
+        };
+
+        let leafs = self
+            .pubkeys
+            .as_vec()
+            .iter()
+            .map(&mut pubkey_leaf)
+            .collect::<Vec<_>>();
+        let aggregate_pubkey_leaf = pubkey_leaf(&self.aggregate_pubkey);
+        let pubkeys_root = builder.ssz_hash_leafs(&leafs);
+        builder.curta_sha256_pair(pubkeys_root, aggregate_pubkey_leaf)
+    }
+}
+
+/// The fields of an altair `LightClientUpdate` needed to advance to the next sync committee.
+/// The sync committee signature is not included since it is verified outside of the circuit.
+///
+/// The branches are padded with zeros up to their maximum depth over all forks.
+#[derive(Debug, Clone, CircuitVariable)]
+#[value_name(BeaconLightClientUpdateValue)]
+pub struct BeaconLightClientUpdateVariable {
+    pub attested_header: BeaconHeaderVariable,
+    pub next_sync_committee: BeaconSyncCommitteeVariable,
+    pub next_sync_committee_branch:
+        ArrayVariable<Bytes32Variable, MAX_NEXT_SYNC_COMMITTEE_BRANCH_DEPTH>,
+    pub finalized_header: BeaconHeaderVariable,
+    pub finality_branch: ArrayVariable<Bytes32Variable, MAX_FINALITY_BRANCH_DEPTH>,
+    pub sync_committee_bits: ArrayVariable<BoolVariable, SYNC_COMMITTEE_SIZE>,
+    pub signature_slot: U64Variable,
+}
@@ -1,3 +1,4 @@
+pub mod abi;
 pub mod beacon;
 pub mod mpt;
 pub mod rlp;
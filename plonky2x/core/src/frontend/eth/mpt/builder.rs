@@ -1,10 +1,44 @@
+use ethers::types::H256;
 use starkyx::math::field::Field;
 
-use crate::frontend::vars::Nibbles;
+use crate::frontend::eth::vars::AddressVariable;
 use crate::prelude::{
-    ArrayVariable, ByteVariable, Bytes32Variable, CircuitBuilder, CircuitVariable, PlonkParameters,
-    U32Variable, Variable,
+    ArrayVariable, BoolVariable, ByteVariable, Bytes32Variable, CircuitBuilder, CircuitVariable,
+    PlonkParameters, U32Variable, Variable,
 };
+use crate::utils::bytes32;
+
+/// The maximum length in bytes of a branch node whose children are referenced by hash.
+pub const MAX_BRANCH_NODE_LEN: usize = 3 + 16 * 33 + 1;
+
+/// An upper bound on the position of the value in a leaf node: a list header of at most 3 bytes,
+/// a path of at most 33 bytes with its header and a value header of at most 3 bytes.
+const MAX_LEAF_VALUE_POSITION: usize = 3 + 34 + 3;
+
+/// The maximum number of nibbles of a path, i.e. of a 32-byte key.
+const MAX_PATH_NIBBLES: usize = 64;
+
+/// The number of bits of a position in the nibbles of a key.
+const KEY_POSITION_BITS: usize = 7;
+
+/// The maximum length in bytes of an encoded account `[nonce, balance, storageRoot, codeHash]`: a
+/// list header of 2 bytes, a nonce of at most 9 bytes, a balance of at most 33 bytes and two
+/// hashes of 33 bytes.
+pub const ACCOUNT_VALUE_LEN: usize = 2 + 9 + 33 + 2 * 33;
+
+/// The value of a key in a merkle patricia trie, as returned by `get_mpt_value`.
+#[derive(Debug, Clone)]
+pub struct MptValue<const VALUE_LEN: usize> {
+    /// Whether the key is in the trie.
+    pub exists: BoolVariable,
+    /// The value, padded with zeros. It is all zeros if the key is not in the trie.
+    pub value: ArrayVariable<ByteVariable, VALUE_LEN>,
+    /// The length of the value in bytes.
+    pub len: Variable,
+    /// The hash of the leaf node holding the value. It commits to `value`, so it can seed
+    /// `get_fixed_subarray` on the value.
+    pub leaf_hash: Bytes32Variable,
+}
 
 pub fn transform_proof_to_padded<const ENCODING_LEN: usize, const PROOF_LEN: usize>(
     storage_proof: Vec<Vec<u8>>,
@@ -40,173 +74,489 @@ pub fn transform_proof_to_padded<const ENCODING_LEN: usize, const PROOF_LEN: usi
 }
 
 impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
-    const PREFIX_EXTENSION_EVEN: u8 = 0;
-    const PREFIX_EXTENSION_ODD: u8 = 1;
-    const PREFIX_LEAF_EVEN: u8 = 2;
-    const PREFIX_LEAF_ODD: u8 = 3;
-    /// Get the validators for a given block root.
-    /// P is the number of proof elements to be considered
-    pub fn verify_mpt_proof<const ENCODING_LEN: usize, const PROOF_LEN: usize>(
+    /// Verifies the merkle patricia proof of `key` against `root`, and returns the value of the
+    /// key or that it is not in the trie.
+    ///
+    /// `proof` holds the nodes from the root along the path of the key, as returned by
+    /// `eth_getProof`, each padded with zeros to `NODE_LEN` bytes and with its length in
+    /// `len_nodes`. Only the first `key_len` bytes of `key` are used. A proof that the key is not
+    /// in the trie ends with the node where the path of the key leaves the trie.
+    ///
+    /// Each node is checked against the hash referencing it with `keccak256_var`, so nodes shorter
+    /// than 32 bytes, which are embedded in their parent rather than hashed, are not supported.
+    /// Neither are values in branch nodes, which only exist when a key is a prefix of another. The
+    /// state, storage, receipts and transactions tries have neither.
+    pub fn get_mpt_value<const NODE_LEN: usize, const PROOF_LEN: usize, const VALUE_LEN: usize>(
         &mut self,
-        key: Bytes32Variable,
-        proof: ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>,
-        len_nodes: ArrayVariable<U32Variable, PROOF_LEN>,
         root: Bytes32Variable,
-        value: Bytes32Variable,
-    ) {
-        const ELEMENT_LEN: usize = 32; // Maximum size of list element
-        const LIST_LEN: usize = 17; // Maximum length of the list for each proof element
-
-        let tree_radix = self.constant::<Variable>(L::Field::from_canonical_u8(16u8));
-        let branch_node_length = self.constant::<Variable>(L::Field::from_canonical_u8(17u8));
-        let leaf_or_extension_node_length =
-            self.constant::<Variable>(L::Field::from_canonical_u8(2u8));
-        let prefix_leaf_even = self.constant::<ByteVariable>(Self::PREFIX_LEAF_EVEN);
-        let prefix_leaf_odd = self.constant::<ByteVariable>(Self::PREFIX_LEAF_ODD);
-        let prefix_extension_even = self.constant::<ByteVariable>(Self::PREFIX_EXTENSION_EVEN);
-        let prefix_extension_odd = self.constant::<ByteVariable>(Self::PREFIX_EXTENSION_ODD);
-        let one: Variable = self.one::<Variable>();
-        let two = self.constant::<Variable>(L::Field::from_canonical_u8(2));
-        let const_64 = self.constant::<Variable>(L::Field::from_canonical_u8(64));
-        let const_32 = self.constant::<U32Variable>(32u32);
-        let const_128 = self.constant::<U32Variable>(128u32);
-
-        let mut current_key_idx = self.zero::<Variable>();
-        let mut finished = self._false();
+        key: &[ByteVariable],
+        key_len: Variable,
+        proof: &ArrayVariable<ArrayVariable<ByteVariable, NODE_LEN>, PROOF_LEN>,
+        len_nodes: &ArrayVariable<U32Variable, PROOF_LEN>,
+    ) -> MptValue<VALUE_LEN> {
+        assert!(
+            NODE_LEN >= MAX_BRANCH_NODE_LEN,
+            "NODE_LEN must be at least {} bytes to hold a branch node",
+            MAX_BRANCH_NODE_LEN
+        );
+        assert!(
+            NODE_LEN >= MAX_LEAF_VALUE_POSITION + VALUE_LEN,
+            "NODE_LEN is too small to hold a leaf node with a value of VALUE_LEN bytes"
+        );
+        assert!(
+            2 * key.len() <= MAX_PATH_NIBBLES,
+            "keys must be at most 32 bytes long"
+        );
 
-        let mut padded_root = root.as_bytes().to_vec();
-        while padded_root.len() < ELEMENT_LEN {
-            padded_root.push(self.constant::<ByteVariable>(0));
+        let true_v = self._true();
+        let false_v = self._false();
+        let zero = self.zero::<Variable>();
+        let one = self.one::<Variable>();
+        let two = self.constant::<Variable>(L::Field::from_canonical_u8(2));
+        let thirty_three = self.constant::<Variable>(L::Field::from_canonical_u8(33));
+        let not_a_nibble = self.constant::<Variable>(L::Field::from_canonical_u8(16));
+        let hash_prefix = self.constant::<ByteVariable>(0x80 + 32);
+        let empty_prefix = self.constant::<ByteVariable>(0x80);
+
+        // The nibbles of the key, followed by a value that is not a nibble, so that a path running
+        // past the end of the key does not match it.
+        let key_nibbles_len = self.mul(key_len, two);
+        let mut key_nibbles = Vec::new();
+        let mut within_key = true_v;
+        for (i, byte) in key.iter().enumerate() {
+            for (j, nibble) in byte.to_nibbles(self).into_iter().enumerate() {
+                let idx = self.constant::<Variable>(L::Field::from_canonical_usize(2 * i + j));
+                let at_end = self.is_equal(idx, key_nibbles_len);
+                let not_at_end = self.not(at_end);
+                within_key = self.and(within_key, not_at_end);
+                let nibble = nibble.to_variable(self);
+                key_nibbles.push(self.select(within_key, nibble, not_a_nibble));
+            }
         }
-        let mut current_node_id = ArrayVariable::<ByteVariable, ELEMENT_LEN>::new(padded_root);
-        let hash_key = self.keccak256_witness(&key.as_bytes());
-        let key_path: ArrayVariable<ByteVariable, 64> =
-            hash_key.as_bytes().to_vec().to_nibbles(self).into();
+        let max_key_nibbles_len =
+            self.constant::<Variable>(L::Field::from_canonical_usize(2 * key.len()));
+        let is_whole_key = self.is_equal(key_nibbles_len, max_key_nibbles_len);
+        self.assert_implies(within_key, is_whole_key);
+        key_nibbles.resize(2 * key.len() + MAX_PATH_NIBBLES, not_a_nibble);
+
+        // The empty trie has no nodes.
+        let empty_root = self.empty_mpt_root();
+        let mut done = self.is_equal(root, empty_root);
+        let mut expected_hash = root;
+        let mut key_pos = zero;
+        let mut exists = false_v;
+        let mut value =
+            self.constant::<ArrayVariable<ByteVariable, VALUE_LEN>>(vec![0u8; VALUE_LEN]);
+        let mut value_len = zero;
+        let mut leaf_hash = self.constant::<Bytes32Variable>(H256::zero());
 
         for i in 0..PROOF_LEN {
-            let current_node = proof[i].clone();
-            let current_node_hash =
-                self.keccak256_variable_witness(current_node.as_slice(), len_nodes[i].variable);
-
-            if i == 0 {
-                self.assert_is_equal(current_node_hash, root);
-            } else {
-                let first_32_bytes_eq = self.is_equal::<Bytes32Variable>(
-                    current_node[0..32].into(),
-                    current_node_id[0..32].into(),
-                );
-                let hash_eq = self.is_equal::<Bytes32Variable>(
-                    current_node_hash,
-                    current_node_id.as_slice()[0..32].into(),
-                );
-                let node_len_le_32 = self.lt(len_nodes[i], const_32);
-                let case_len_le_32 = self.and(node_len_le_32, first_32_bytes_eq);
-                let inter = self.not(node_len_le_32);
-                let case_len_gt_32 = self.and(inter, hash_eq);
-                let equality_fulfilled = self.or(case_len_le_32, case_len_gt_32);
-                let checked_equality = self.or(equality_fulfilled, finished);
-                let t = self._true();
-                self.assert_is_equal(checked_equality, t);
+            let node = &proof[i];
+            let node_len = len_nodes[i].variable;
+            let active = self.not(done);
+
+            // The bytes after the node must be zero, so that the hash of the node commits to the
+            // whole array and can seed `get_fixed_subarray`.
+            let mut within_node = true_v;
+            for j in 0..NODE_LEN {
+                let idx = self.constant::<Variable>(L::Field::from_canonical_usize(j));
+                let at_end = self.is_equal(idx, node_len);
+                let not_at_end = self.not(at_end);
+                within_node = self.and(within_node, not_at_end);
+                let byte = node[j].to_variable(self);
+                let is_zero = self.is_zero(byte);
+                let is_valid_byte = self.or(within_node, is_zero);
+                self.assert_is_equal(is_valid_byte, true_v);
+            }
+            let node_hash = self.keccak256_var(node.as_slice(), len_nodes[i]);
+            let is_expected_hash = self.is_equal(node_hash, expected_hash);
+            self.assert_implies(active, is_expected_hash);
+            let seed = node_hash.as_bytes();
+
+            // Nodes are lists of 17 items for branch nodes and of 2 items for leaf and extension
+            // nodes, which end with their second item.
+            let (is_list, list_header_len, list_payload_len) =
+                self.decode_rlp_header([node[0], node[1], node[2]]);
+            let list_len = self.add(list_header_len, list_payload_len);
+            let has_list_len = self.is_equal(list_len, node_len);
+            let is_valid_list = self.and(is_list, has_list_len);
+            self.assert_implies(active, is_valid_list);
+
+            let path_pos = list_header_len;
+            let (_, path_header_len, path_bytes_len) =
+                self.decode_rlp_header_at(node, path_pos, &seed);
+            let second_pos = self.add(path_pos, path_header_len);
+            let second_pos = self.add(second_pos, path_bytes_len);
+            let (_, second_header_len, second_len) =
+                self.decode_rlp_header_at(node, second_pos, &seed);
+            let second_end = self.add(second_pos, second_header_len);
+            let second_end = self.add(second_end, second_len);
+            let is_short = self.is_equal(second_end, node_len);
+            let is_branch = self.not(is_short);
+
+            // The path of leaf and extension nodes is hex prefix encoded: its first nibble is 0 or
+            // 1 for extension nodes and 2 or 3 for leaf nodes, and is odd if the path has an odd
+            // number of nibbles, which then start at the second nibble rather than the third.
+            let path_start = self.add(path_pos, path_header_len);
+            let path_bytes = self.get_fixed_subarray::<NODE_LEN, 33>(node, path_start, &seed);
+            let flag_bits = path_bytes[0].as_be_bits();
+            let is_large_flag = self.or(flag_bits[0], flag_bits[1]);
+            let is_valid_flag = self.not(is_large_flag);
+            let is_active_short = self.and(active, is_short);
+            self.assert_implies(is_active_short, is_valid_flag);
+            let is_leaf = flag_bits[2];
+            let is_odd = flag_bits[3];
+
+            let mut path_nibbles = Vec::new();
+            for byte in path_bytes.as_slice() {
+                for nibble in byte.to_nibbles(self) {
+                    path_nibbles.push(nibble.to_variable(self));
+                }
+            }
+            let mut path = Vec::new();
+            for t in 0..MAX_PATH_NIBBLES {
+                path.push(self.select(is_odd, path_nibbles[t + 1], path_nibbles[t + 2]));
+            }
+            let path_len = self.mul(path_bytes_len, two);
+            let path_len = self.sub(path_len, two);
+            let path_len = self.add(path_len, is_odd.variable);
+
+            // The rest of the key from `key_pos`, shifted by a barrel shifter on its bits.
+            let mut key_rest = key_nibbles.clone();
+            let key_pos_bits = self.api.split_le(key_pos.0, KEY_POSITION_BITS);
+            for (b, bit) in key_pos_bits.into_iter().enumerate() {
+                let shift = 1 << b;
+                let mut shifted = Vec::with_capacity(key_rest.len());
+                for t in 0..key_rest.len() {
+                    let next = key_rest.get(t + shift).copied().unwrap_or(not_a_nibble);
+                    shifted.push(self.select(bit.into(), next, key_rest[t]));
+                }
+                key_rest = shifted;
             }
 
-            let (decoded_list, decoded_element_lens, len_decoded_list) = self
-                .decode_element_as_list::<ENCODING_LEN, LIST_LEN, ELEMENT_LEN>(
-                    current_node,
-                    len_nodes[i].variable,
-                    finished,
-                );
-
-            let is_branch = self.is_equal(len_decoded_list, branch_node_length);
-            let is_leaf = self.is_equal(len_decoded_list, leaf_or_extension_node_length);
-            let key_terminated = self.is_equal(current_key_idx, const_64);
-            let path = decoded_list[0].as_slice().to_vec().to_nibbles(self);
-            let prefix = path[0];
-            let prefix_leaf_even = self.is_equal(prefix, prefix_leaf_even);
-            let prefix_leaf_odd = self.is_equal(prefix, prefix_leaf_odd);
-            let prefix_extension_even = self.is_equal(prefix, prefix_extension_even);
-            let prefix_extension_odd = self.is_equal(prefix, prefix_extension_odd);
-
-            let offset_even = self.mul(prefix_extension_even.variable, two);
-            let offset_odd = self.mul(prefix_extension_odd.variable, one);
-            let offset = self.add(offset_even, offset_odd);
-            let branch_key = self.select_array(key_path.clone().as_slice(), current_key_idx);
-            let branch_key_variable = branch_key.to_variable(self);
-
-            // Case 1
-            let is_branch_and_key_terminated = self.and(is_branch, key_terminated);
-            let case_1_value = self.mul(is_branch_and_key_terminated.variable, tree_radix);
-            let b = self.not(key_terminated);
-            let is_branch_and_key_not_terminated = self.and(is_branch, b);
-            let case_2_value = self.mul(
-                is_branch_and_key_not_terminated.variable,
-                branch_key_variable,
-            );
-            let case_3_value = self.mul(is_leaf.variable, one);
+            let mut within_path = true_v;
+            let mut path_matches = true_v;
+            for t in 0..MAX_PATH_NIBBLES {
+                let idx = self.constant::<Variable>(L::Field::from_canonical_usize(t));
+                let at_end = self.is_equal(idx, path_len);
+                let not_at_end = self.not(at_end);
+                within_path = self.and(within_path, not_at_end);
+                let is_same_nibble = self.is_equal(path[t], key_rest[t]);
+                let outside_path = self.not(within_path);
+                let nibble_matches = self.or(outside_path, is_same_nibble);
+                path_matches = self.and(path_matches, nibble_matches);
+            }
+            let path_end = self.add(key_pos, path_len);
+            let is_key_end = self.is_equal(path_end, key_nibbles_len);
+            let leaf_matches = self.and(path_matches, is_key_end);
+
+            // The second item of an extension node is the hash of its child, and that of a leaf
+            // node is the value.
+            let second = self.get_fixed_subarray::<NODE_LEN, 33>(node, second_pos, &seed);
+            let is_second_hash = self.is_equal(second[0], hash_prefix);
+            let extension_child = Bytes32Variable::from(&second.as_slice()[1..]);
+
+            let value_pos = self.add(second_pos, second_header_len);
+            let leaf_value = self.get_fixed_subarray::<NODE_LEN, VALUE_LEN>(node, value_pos, &seed);
+            let second_len_u32 = U32Variable::from_variables(self, &[second_len]);
+            let max_value_len = self.constant::<U32Variable>(VALUE_LEN as u32);
+            let fits_value = self.lte(second_len_u32, max_value_len);
+
+            // Branch nodes must reference each child by hash or be empty at its nibble, and have
+            // no value.
+            let mut child_pos = list_header_len;
+            let mut children = Vec::new();
+            let mut has_children = Vec::new();
+            let mut is_valid_branch = true_v;
+            for _ in 0..16 {
+                let child = self.get_fixed_subarray::<NODE_LEN, 33>(node, child_pos, &seed);
+                let is_hash = self.is_equal(child[0], hash_prefix);
+                let is_empty = self.is_equal(child[0], empty_prefix);
+                let is_valid_child = self.or(is_hash, is_empty);
+                is_valid_branch = self.and(is_valid_branch, is_valid_child);
+                children.push(Bytes32Variable::from(&child.as_slice()[1..]));
+                has_children.push(is_hash);
+                let child_len = self.select(is_hash, thirty_three, one);
+                child_pos = self.add(child_pos, child_len);
+            }
+            let branch_value = self.get_fixed_subarray::<NODE_LEN, 1>(node, child_pos, &seed);
+            let is_empty_value = self.is_equal(branch_value[0], empty_prefix);
+            is_valid_branch = self.and(is_valid_branch, is_empty_value);
+            let branch_end = self.add(child_pos, one);
+            let is_branch_end = self.is_equal(branch_end, node_len);
+            is_valid_branch = self.and(is_valid_branch, is_branch_end);
+            let is_active_branch = self.and(active, is_branch);
+            self.assert_implies(is_active_branch, is_valid_branch);
+
+            let key_nibble = key_rest[0];
+            let is_key_consumed = self.is_equal(key_nibble, not_a_nibble);
+            let is_key_left = self.not(is_key_consumed);
+            self.assert_implies(is_active_branch, is_key_left);
+            let nibble = self.select(is_key_consumed, zero, key_nibble);
+            let children: [Bytes32Variable; 16] = children.try_into().unwrap();
+            let has_children: [BoolVariable; 16] = has_children.try_into().unwrap();
+            let branch_child = self.select_by_nibble(nibble, &children);
+            let has_branch_child = self.select_by_nibble(nibble, &has_children);
+
+            // The key is in the trie if it ends at a leaf, and is not if its path leaves the trie,
+            // at an empty child of a branch node or at a leaf or extension node whose path differs.
+            let not_leaf = self.not(is_leaf);
+            let is_extension = self.and(is_short, not_leaf);
+            let is_leaf_node = self.and(is_short, is_leaf);
+            let found = self.and(is_leaf_node, leaf_matches);
+            let found = self.and(active, found);
+            self.assert_implies(found, fits_value);
+            exists = self.or(exists, found);
+            value = self.select(found, leaf_value, value);
+            value_len = self.select(found, second_len, value_len);
+            leaf_hash = self.select(found, node_hash, leaf_hash);
+
+            let follows_branch = self.and(is_branch, has_branch_child);
+            let follows_extension = self.and(is_extension, path_matches);
+            let is_active_extension = self.and(active, follows_extension);
+            self.assert_implies(is_active_extension, is_second_hash);
+            let follows = self.or(follows_branch, follows_extension);
+            let continues = self.and(active, follows);
+            let child = self.select(is_branch, branch_child, extension_child);
+            expected_hash = self.select(continues, child, expected_hash);
+            let branch_key_pos = self.add(key_pos, one);
+            let next_key_pos = self.select(is_branch, branch_key_pos, path_end);
+            key_pos = self.select(continues, next_key_pos, key_pos);
+            done = self.not(continues);
+        }
+        // The proof must reach the leaf or the node where the key leaves the trie.
+        self.assert_is_equal(done, true_v);
+
+        MptValue {
+            exists,
+            value,
+            len: value_len,
+            leaf_hash,
+        }
+    }
 
-            let c = self.add(case_1_value, case_2_value);
-            let updated_current_node_id_idx = self.add(c, case_3_value); // TODO: make this more concise
+    /// Returns the value of the storage slot `key` from its merkle patricia proof against the
+    /// storage root `root`, which is zero if the slot is empty. See `get_mpt_value`.
+    pub fn get_storage_proof_value<const ENCODING_LEN: usize, const PROOF_LEN: usize>(
+        &mut self,
+        key: Bytes32Variable,
+        proof: &ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>,
+        len_nodes: &ArrayVariable<U32Variable, PROOF_LEN>,
+        root: Bytes32Variable,
+    ) -> Bytes32Variable {
+        // Storage tries are keyed by the hash of the slot.
+        let key_bytes_len = self.constant::<U32Variable>(32);
+        let hashed_key = self.keccak256_var(&key.as_bytes(), key_bytes_len);
+        let key_len = self.constant::<Variable>(L::Field::from_canonical_usize(32));
+        let result = self.get_mpt_value::<ENCODING_LEN, PROOF_LEN, 33>(
+            root,
+            &hashed_key.as_bytes(),
+            key_len,
+            proof,
+            len_nodes,
+        );
 
-            let updated_current_node_id =
-                self.select_array(decoded_list.as_slice(), updated_current_node_id_idx);
-            // If finished == 1, then we should not update the current_node_id
-            current_node_id = self.select_array(
-                &[updated_current_node_id, current_node_id],
-                finished.variable,
-            );
+        // Values are RLP encoded big-endian integers without leading zeros, and an empty slot is
+        // read as the empty string.
+        let mut empty = vec![0u8; 33];
+        empty[0] = 0x80;
+        let empty = self.constant::<ArrayVariable<ByteVariable, 33>>(empty);
+        let encoded = self.select(result.exists, result.value, empty);
+        let (is_list, header_len, payload_len) =
+            self.decode_rlp_header([encoded[0], encoded[1], encoded[2]]);
+        let is_string = self.not(is_list);
+        let encoded_len = self.add(header_len, payload_len);
+        let has_encoded_len = self.is_equal(encoded_len, result.len);
+        let is_valid_value = self.and(is_string, has_encoded_len);
+        self.assert_implies(result.exists, is_valid_value);
+
+        // Skip the header, which is a single byte unless the value is a single byte below 0x80,
+        // and right align the payload. The shift fails if the payload is longer than 32 bytes.
+        let one = self.one::<Variable>();
+        let has_header = self.is_equal(header_len, one);
+        let mut bytes = Vec::new();
+        for i in 0..32 {
+            bytes.push(self.select(has_header, encoded[i + 1], encoded[i]));
+        }
+        let thirty_two = self.constant::<Variable>(L::Field::from_canonical_u8(32));
+        let shift = self.sub(thirty_two, payload_len);
+        let shift_bits = self.api.split_le(shift.0, 6);
+        let zero_byte = self.constant::<ByteVariable>(0);
+        for (b, bit) in shift_bits.into_iter().enumerate() {
+            let offset = 1 << b;
+            let mut shifted = Vec::with_capacity(32);
+            for j in 0..32 {
+                let previous = if j >= offset {
+                    bytes[j - offset]
+                } else {
+                    zero_byte
+                };
+                shifted.push(self.select(bit.into(), previous, bytes[j]));
+            }
+            bytes = shifted;
+        }
+        Bytes32Variable::from(bytes.as_slice())
+    }
 
-            let mut do_path_remainder_check = self.not(finished);
-            do_path_remainder_check = self.and(do_path_remainder_check, is_leaf);
-            let d = self.or(prefix_extension_even, prefix_extension_odd);
-            do_path_remainder_check = self.and(do_path_remainder_check, d);
-
-            let e = self.mul(decoded_element_lens[0], two);
-            let f = self.mul(offset, do_path_remainder_check.variable);
-            let mut check_length = self.sub(e, f);
-            check_length = self.mul(check_length, do_path_remainder_check.variable);
-
-            self.assert_subarray_equal(
-                &path,
-                offset,
-                key_path.as_slice(),
-                current_key_idx,
-                check_length,
-            );
+    /// Returns the storage root of `address` from the merkle patricia proof of its account against
+    /// the state root `root`, which is the root of the empty trie if the account does not exist.
+    /// See `get_mpt_value`.
+    pub fn get_account_storage_root<const ENCODING_LEN: usize, const PROOF_LEN: usize>(
+        &mut self,
+        address: AddressVariable,
+        proof: &ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>,
+        len_nodes: &ArrayVariable<U32Variable, PROOF_LEN>,
+        root: Bytes32Variable,
+    ) -> Bytes32Variable {
+        // The state trie is keyed by the hash of the address.
+        let address_len = self.constant::<U32Variable>(20);
+        let hashed_address = self.keccak256_var(&address.0 .0, address_len);
+        let key_len = self.constant::<Variable>(L::Field::from_canonical_usize(32));
+        let account = self.get_mpt_value::<ENCODING_LEN, PROOF_LEN, ACCOUNT_VALUE_LEN>(
+            root,
+            &hashed_address.as_bytes(),
+            key_len,
+            proof,
+            len_nodes,
+        );
 
-            current_key_idx = self.add(current_key_idx, is_branch_and_key_not_terminated.variable);
-            let j = self.mul(is_leaf.variable, check_length);
-            current_key_idx = self.add(current_key_idx, j);
+        // The encoded account ends with the storage root and the code hash, each with its 1-byte
+        // string header.
+        let zero = self.zero::<Variable>();
+        let hashes_len = self.constant::<Variable>(L::Field::from_canonical_usize(2 * 33));
+        let hashes_start = self.sub(account.len, hashes_len);
+        let hashes_start = self.select(account.exists, hashes_start, zero);
+        let hashes = self.get_fixed_subarray::<ACCOUNT_VALUE_LEN, 66>(
+            &account.value,
+            hashes_start,
+            &account.leaf_hash.as_bytes(),
+        );
+        let hash_prefix = self.constant::<ByteVariable>(0x80 + 32);
+        let has_storage_root = self.is_equal(hashes[0], hash_prefix);
+        let has_code_hash = self.is_equal(hashes[33], hash_prefix);
+        let has_hashes = self.and(has_storage_root, has_code_hash);
+        self.assert_implies(account.exists, has_hashes);
+
+        let storage_root = Bytes32Variable::from(&hashes.as_slice()[1..33]);
+        let empty_root = self.empty_mpt_root();
+        self.select(account.exists, storage_root, empty_root)
+    }
 
-            let prefix_leaf_even_and_leaf = self.and(prefix_leaf_even, is_leaf);
-            let prefix_leaf_odd_and_leaf = self.and(prefix_leaf_odd, is_leaf);
-            let l = self.or(is_branch_and_key_terminated, prefix_leaf_even_and_leaf);
-            let m = self.or(l, prefix_leaf_odd_and_leaf);
-            finished = self.or(finished, m);
+    /// Returns the item at `index` of the transactions or receipts trie with root `root` from its
+    /// merkle patricia proof, failing if there is no such item. See `get_mpt_value`.
+    ///
+    /// These tries are keyed by `rlp(index)`, and `index` must be below 65536.
+    pub fn get_block_trie_item<
+        const NODE_LEN: usize,
+        const PROOF_LEN: usize,
+        const VALUE_LEN: usize,
+    >(
+        &mut self,
+        root: Bytes32Variable,
+        index: U32Variable,
+        proof: &ArrayVariable<ArrayVariable<ByteVariable, NODE_LEN>, PROOF_LEN>,
+        len_nodes: &ArrayVariable<U32Variable, PROOF_LEN>,
+    ) -> MptValue<VALUE_LEN> {
+        let bits = self
+            .api
+            .split_le(index.variable.0, 16)
+            .into_iter()
+            .map(BoolVariable::from)
+            .collect::<Vec<_>>();
+        let low = ByteVariable(core::array::from_fn(|i| bits[7 - i]));
+        let high = ByteVariable(core::array::from_fn(|i| bits[15 - i]));
+
+        // Zero is encoded as the empty string, indices below 128 as a single byte and the others
+        // as a string of one or two bytes.
+        let zero_byte = self.constant::<ByteVariable>(0);
+        let is_high_zero = self.is_equal(high, zero_byte);
+        let is_zero = self.is_zero(index.variable);
+        let is_low_small = self.not(bits[7]);
+        let is_small = self.and(is_high_zero, is_low_small);
+        let empty_string = self.constant::<ByteVariable>(0x80);
+        let one_byte_prefix = self.constant::<ByteVariable>(0x81);
+        let two_bytes_prefix = self.constant::<ByteVariable>(0x82);
+        let prefix = self.select(is_high_zero, one_byte_prefix, two_bytes_prefix);
+        let small_key = self.select(is_zero, empty_string, low);
+        let first = self.select(is_small, small_key, prefix);
+        let second = self.select(is_high_zero, low, high);
+        let key = [first, second, low];
+
+        let one = self.one::<Variable>();
+        let two = self.constant::<Variable>(L::Field::from_canonical_u8(2));
+        let three = self.constant::<Variable>(L::Field::from_canonical_u8(3));
+        let long_key_len = self.select(is_high_zero, two, three);
+        let key_len = self.select(is_small, one, long_key_len);
+
+        let item = self
+            .get_mpt_value::<NODE_LEN, PROOF_LEN, VALUE_LEN>(root, &key, key_len, proof, len_nodes);
+        let true_v = self._true();
+        self.assert_is_equal(item.exists, true_v);
+        item
+    }
+
+    /// Verifies the merkle patricia proof that the storage slot `key` has value `value` in the
+    /// storage trie with root `root`. See `get_storage_proof_value`.
+    pub fn verify_mpt_proof<const ENCODING_LEN: usize, const PROOF_LEN: usize>(
+        &mut self,
+        key: Bytes32Variable,
+        proof: ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>,
+        len_nodes: ArrayVariable<U32Variable, PROOF_LEN>,
+        root: Bytes32Variable,
+        value: Bytes32Variable,
+    ) {
+        let proven_value = self.get_storage_proof_value(key, &proof, &len_nodes, root);
+        self.assert_is_equal(proven_value, value);
+    }
+
+    /// Selects the child of a branch node at `nibble`, which must be less than 16.
+    ///
+    /// The nibble is split into 4 bits, which range checks it, and the child is selected by a
+    /// binary tree of multiplexers on the bits, from the least significant one.
+    pub fn select_by_nibble<V: CircuitVariable>(
+        &mut self,
+        nibble: Variable,
+        children: &[V; 16],
+    ) -> V {
+        let bits = self.api.split_le(nibble.0, 4);
+        let mut layer = children.to_vec();
+        for bit in bits {
+            let mut next_layer = Vec::with_capacity(layer.len() / 2);
+            for pair in layer.chunks_exact(2) {
+                next_layer.push(self.select(bit.into(), pair[1].clone(), pair[0].clone()));
+            }
+            layer = next_layer;
         }
+        layer.pop().unwrap()
+    }
 
-        // Can be unsafe because `current_node_id` comes from a ByteVariable.
-        let current_node_id_u32 =
-            U32Variable::from_variables_unsafe(&[current_node_id[0].to_variable(self)]);
-        let current_node_len = self.sub(current_node_id_u32, const_128);
-        let lhs_offset = self.sub(const_32, current_node_len);
-
-        self.assert_subarray_equal(
-            &value.as_bytes(),
-            lhs_offset.variable,
-            current_node_id.as_slice(),
-            one,
-            current_node_len.variable,
-        );
+    /// The root of the empty trie, `keccak256(rlp(""))`.
+    fn empty_mpt_root(&mut self) -> Bytes32Variable {
+        self.constant::<Bytes32Variable>(bytes32!(
+            "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"
+        ))
+    }
+
+    /// Fails if `condition` is true and `check` is false.
+    pub(crate) fn assert_implies(&mut self, condition: BoolVariable, check: BoolVariable) {
+        let not_condition = self.not(condition);
+        let holds = self.or(not_condition, check);
+        let true_v = self._true();
+        self.assert_is_equal(holds, true_v);
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use ethers::types::{Address, H256, U256};
+    use ethers::utils::{keccak256, rlp};
     use log::debug;
 
+    use super::super::trie::{trie_root, trie_root_and_proof};
     use super::super::utils::{read_fixture, EIP1186ProofResponse};
     use super::*;
     use crate::frontend::eth::utils::u256_to_h256_be;
-    use crate::prelude::DefaultBuilder;
+    use crate::prelude::{DefaultBuilder, GoldilocksField};
     use crate::utils;
 
     #[test]
@@ -272,4 +622,244 @@ mod tests {
 
         let (_witness, mut _output) = circuit.mock_prove(&input);
     }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_get_storage_proof_value() {
+        utils::setup_logger();
+        const ENCODING_LEN: usize = 600;
+        const PROOF_LEN: usize = 4;
+
+        // A storage trie whose slots 0 to 39 hold their index plus one.
+        let slot_key = |slot: u64| H256::from_low_u64_be(slot);
+        let entries = (0..40u64)
+            .map(|slot| {
+                (
+                    keccak256(slot_key(slot)).to_vec(),
+                    rlp::encode(&U256::from(slot + 1)).to_vec(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut builder = DefaultBuilder::new();
+        let key = builder.read::<Bytes32Variable>();
+        let proof =
+            builder.read::<ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>>();
+        let len_nodes = builder.read::<ArrayVariable<U32Variable, PROOF_LEN>>();
+        let root = builder.read::<Bytes32Variable>();
+        let value = builder.get_storage_proof_value(key, &proof, &len_nodes, root);
+        builder.write(value);
+        let circuit = builder.build();
+
+        let write_input = |key: H256, proof: Vec<Vec<u8>>, root: H256| {
+            let (proof, lengths) = transform_proof_to_padded::<ENCODING_LEN, PROOF_LEN>(proof);
+            let mut input = circuit.input();
+            input.write::<Bytes32Variable>(key);
+            input.write::<ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>>(
+                proof,
+            );
+            input.write::<ArrayVariable<U32Variable, PROOF_LEN>>(
+                lengths.into_iter().map(|length| length as u32).collect(),
+            );
+            input.write::<Bytes32Variable>(root);
+            input
+        };
+
+        let (root, proof) = trie_root_and_proof(&entries, &keccak256(slot_key(7)));
+        let mut output = circuit
+            .mock_prove(&write_input(slot_key(7), proof.clone(), root))
+            .unwrap();
+        assert_eq!(output.read::<Bytes32Variable>(), H256::from_low_u64_be(8));
+
+        // An empty slot is proven by the nodes up to where its path leaves the trie.
+        let (_, absent_proof) = trie_root_and_proof(&entries, &keccak256(slot_key(100)));
+        let mut output = circuit
+            .mock_prove(&write_input(slot_key(100), absent_proof, root))
+            .unwrap();
+        assert_eq!(output.read::<Bytes32Variable>(), H256::zero());
+
+        // Every slot of the empty trie is empty.
+        let mut output = circuit
+            .mock_prove(&write_input(slot_key(7), Vec::new(), trie_root(&[])))
+            .unwrap();
+        assert_eq!(output.read::<Bytes32Variable>(), H256::zero());
+
+        // The proof of a slot does not prove another slot of the trie, and must reach the leaf.
+        assert!(circuit
+            .mock_prove(&write_input(slot_key(8), proof.clone(), root))
+            .is_err());
+        let truncated_proof = proof[..proof.len() - 1].to_vec();
+        assert!(circuit
+            .mock_prove(&write_input(slot_key(7), truncated_proof, root))
+            .is_err());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_get_account_storage_root() {
+        utils::setup_logger();
+        const ENCODING_LEN: usize = 600;
+        const PROOF_LEN: usize = 10;
+
+        let mut builder = DefaultBuilder::new();
+        let address = builder.read::<AddressVariable>();
+        let proof =
+            builder.read::<ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>>();
+        let len_nodes = builder.read::<ArrayVariable<U32Variable, PROOF_LEN>>();
+        let root = builder.read::<Bytes32Variable>();
+        let storage_root = builder.get_account_storage_root(address, &proof, &len_nodes, root);
+        builder.write(storage_root);
+        let circuit = builder.build();
+
+        let write_input = |address: Address, proof: Vec<Vec<u8>>, root: H256| {
+            let (proof, lengths) = transform_proof_to_padded::<ENCODING_LEN, PROOF_LEN>(proof);
+            let mut input = circuit.input();
+            input.write::<AddressVariable>(address);
+            input.write::<ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>>(
+                proof,
+            );
+            input.write::<ArrayVariable<U32Variable, PROOF_LEN>>(
+                lengths.into_iter().map(|length| length as u32).collect(),
+            );
+            input.write::<Bytes32Variable>(root);
+            input
+        };
+
+        // The account of the fixture at Ethereum block https://etherscan.io/block/17880427.
+        let result: EIP1186ProofResponse =
+            read_fixture("./src/frontend/eth/mpt/fixtures/example.json");
+        let state_root =
+            bytes32!("0xff90251f501c864f21d696c811af4c3aa987006916bd0e31a6c06cc612e7632e");
+        let account_proof = result
+            .account_proof
+            .iter()
+            .map(|node| node.to_vec())
+            .collect::<Vec<_>>();
+        let mut output = circuit
+            .mock_prove(&write_input(
+                result.address,
+                account_proof.clone(),
+                state_root,
+            ))
+            .unwrap();
+        assert_eq!(output.read::<Bytes32Variable>(), result.storage_hash);
+
+        // The proof of an account does not prove another account.
+        assert!(circuit
+            .mock_prove(&write_input(
+                Address::from_low_u64_be(1),
+                account_proof,
+                state_root
+            ))
+            .is_err());
+
+        // An account that does not exist has an empty storage.
+        let entries = (1..40u64)
+            .map(|i| {
+                let mut account = rlp::RlpStream::new_list(4);
+                account.append(&U256::from(i));
+                account.append(&U256::from(i * 1000));
+                account.append(&H256::from_low_u64_be(i).as_bytes().to_vec());
+                account.append(&H256::from(keccak256(b"")).as_bytes().to_vec());
+                (
+                    keccak256(Address::from_low_u64_be(i)).to_vec(),
+                    account.out().to_vec(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let absent = Address::from_low_u64_be(100);
+        let (root, absent_proof) = trie_root_and_proof(&entries, &keccak256(absent));
+        let mut output = circuit
+            .mock_prove(&write_input(absent, absent_proof, root))
+            .unwrap();
+        assert_eq!(output.read::<Bytes32Variable>(), trie_root(&[]));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_get_block_trie_item() {
+        utils::setup_logger();
+        const ENCODING_LEN: usize = 600;
+        const PROOF_LEN: usize = 7;
+        const VALUE_LEN: usize = 40;
+
+        // Enough items for keys `rlp(index)` of one, two and three bytes.
+        let entries = (0..300usize)
+            .map(|index| (rlp::encode(&index).to_vec(), vec![index as u8; VALUE_LEN]))
+            .collect::<Vec<_>>();
+
+        let mut builder = DefaultBuilder::new();
+        let index = builder.read::<U32Variable>();
+        let proof =
+            builder.read::<ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>>();
+        let len_nodes = builder.read::<ArrayVariable<U32Variable, PROOF_LEN>>();
+        let root = builder.read::<Bytes32Variable>();
+        let item = builder.get_block_trie_item::<ENCODING_LEN, PROOF_LEN, VALUE_LEN>(
+            root, index, &proof, &len_nodes,
+        );
+        builder.write(item.value);
+        let circuit = builder.build();
+
+        let root = trie_root(&entries);
+        let write_input = |index: usize, proof: Vec<Vec<u8>>| {
+            let (proof, lengths) = transform_proof_to_padded::<ENCODING_LEN, PROOF_LEN>(proof);
+            let mut input = circuit.input();
+            input.write::<U32Variable>(index as u32);
+            input.write::<ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>>(
+                proof,
+            );
+            input.write::<ArrayVariable<U32Variable, PROOF_LEN>>(
+                lengths.into_iter().map(|length| length as u32).collect(),
+            );
+            input.write::<Bytes32Variable>(root);
+            input
+        };
+
+        for index in [0, 1, 127, 128, 255, 256, 299] {
+            let (_, proof) = trie_root_and_proof(&entries, &entries[index].0);
+            let mut output = circuit.mock_prove(&write_input(index, proof)).unwrap();
+            assert_eq!(
+                output.read::<ArrayVariable<ByteVariable, VALUE_LEN>>(),
+                entries[index].1
+            );
+        }
+
+        // The proof of an item does not prove another index, nor an index past the end.
+        let (_, proof) = trie_root_and_proof(&entries, &entries[5].0);
+        assert!(circuit.mock_prove(&write_input(6, proof)).is_err());
+        let (_, absent_proof) = trie_root_and_proof(&entries, &rlp::encode(&300usize).to_vec());
+        assert!(circuit.mock_prove(&write_input(300, absent_proof)).is_err());
+    }
+
+    #[test]
+    fn test_select_by_nibble() {
+        utils::setup_logger();
+        let mut builder = DefaultBuilder::new();
+        let nibble = builder.read::<Variable>();
+        let children = builder.read::<ArrayVariable<Bytes32Variable, 16>>();
+        let children: [Bytes32Variable; 16] = children.as_vec().try_into().unwrap();
+        let child = builder.select_by_nibble(nibble, &children);
+        builder.write(child);
+        let circuit = builder.build();
+
+        let children_values = (0..16u64)
+            .map(|i| H256::from_low_u64_be(0x1000 + i))
+            .collect::<Vec<_>>();
+        for i in 0..16u64 {
+            let mut input = circuit.input();
+            input.write::<Variable>(GoldilocksField::from_canonical_u64(i));
+            input.write::<ArrayVariable<Bytes32Variable, 16>>(children_values.clone());
+            let mut output = circuit.mock_prove(&input).unwrap();
+            assert_eq!(
+                output.read::<Bytes32Variable>(),
+                children_values[i as usize]
+            );
+        }
+
+        // The nibble must fit in 4 bits.
+        let mut input = circuit.input();
+        input.write::<Variable>(GoldilocksField::from_canonical_u64(16));
+        input.write::<ArrayVariable<Bytes32Variable, 16>>(children_values);
+        assert!(circuit.mock_prove(&input).is_err());
+    }
 }
@@ -2,4 +2,5 @@ pub mod builder;
 pub mod generators;
 pub mod reference;
 pub mod rlc;
+pub mod trie;
 pub mod utils;
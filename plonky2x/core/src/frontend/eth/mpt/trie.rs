@@ -0,0 +1,199 @@
+//! An in-memory merkle patricia trie, used to compute the roots and proofs of tries whose proofs
+//! are not served by the RPC, such as the transactions and receipts tries of a block.
+//!
+//! Reference: https://ethereum.org/en/developers/docs/data-structures-and-encoding/patricia-merkle-trie
+
+use ethers::types::H256;
+use ethers::utils::keccak256;
+use ethers::utils::rlp::RlpStream;
+
+use super::reference::to_nibbles;
+
+/// Returns the root of the trie with the given `(key, value)` entries.
+pub fn trie_root(entries: &[(Vec<u8>, Vec<u8>)]) -> H256 {
+    trie_root_and_proof(entries, &[]).0
+}
+
+/// Returns the root of the trie with the given `(key, value)` entries and the proof of `key`, i.e.
+/// the nodes from the root along the path of `key`. If `key` is not in the trie, the proof ends at
+/// the node where its path diverges from the trie.
+pub fn trie_root_and_proof(entries: &[(Vec<u8>, Vec<u8>)], key: &[u8]) -> (H256, Vec<Vec<u8>>) {
+    if entries.is_empty() {
+        return (H256::from(keccak256([0x80])), Vec::new());
+    }
+    let entries = entries
+        .iter()
+        .map(|(key, value)| (to_nibbles(key), value.as_slice()))
+        .collect::<Vec<_>>();
+    let key = to_nibbles(key);
+
+    let mut proof = Vec::new();
+    let root = encode_node(&entries, Some(&key), &mut proof);
+    // The nodes are pushed from the leaf up to the root.
+    proof.reverse();
+    (H256::from(keccak256(root)), proof)
+}
+
+/// Encodes the node holding `entries`, whose keys are the paths that remain below the node. If
+/// `key` is given, the node is on the path of `key` and is appended to `proof`.
+fn encode_node(
+    entries: &[(Vec<u8>, &[u8])],
+    key: Option<&[u8]>,
+    proof: &mut Vec<Vec<u8>>,
+) -> Vec<u8> {
+    let prefix_len = (0..)
+        .take_while(|i| {
+            entries
+                .iter()
+                .all(|(path, _)| path.len() > *i && path[*i] == entries[0].0[*i])
+        })
+        .count();
+
+    let mut stream;
+    if entries.len() == 1 {
+        let (path, value) = &entries[0];
+        stream = RlpStream::new_list(2);
+        stream.append(&encode_path(path, true));
+        stream.append(&value.to_vec());
+    } else if prefix_len > 0 {
+        let prefix = &entries[0].0[..prefix_len];
+        let children = entries
+            .iter()
+            .map(|(path, value)| (path[prefix_len..].to_vec(), *value))
+            .collect::<Vec<_>>();
+        let child_key = key
+            .filter(|key| key.starts_with(prefix))
+            .map(|key| &key[prefix_len..]);
+        let child = encode_node(&children, child_key, proof);
+        stream = RlpStream::new_list(2);
+        stream.append(&encode_path(prefix, false));
+        append_reference(&mut stream, child);
+    } else {
+        stream = RlpStream::new_list(17);
+        for nibble in 0..16u8 {
+            let children = entries
+                .iter()
+                .filter(|(path, _)| path.first() == Some(&nibble))
+                .map(|(path, value)| (path[1..].to_vec(), *value))
+                .collect::<Vec<_>>();
+            if children.is_empty() {
+                stream.append_empty_data();
+                continue;
+            }
+            let child_key = key
+                .filter(|key| key.first() == Some(&nibble))
+                .map(|key| &key[1..]);
+            let child = encode_node(&children, child_key, proof);
+            append_reference(&mut stream, child);
+        }
+        match entries.iter().find(|(path, _)| path.is_empty()) {
+            Some((_, value)) => stream.append(&value.to_vec()),
+            None => stream.append_empty_data(),
+        };
+    }
+
+    let node = stream.out().to_vec();
+    if key.is_some() {
+        proof.push(node.clone());
+    }
+    node
+}
+
+/// Appends a reference to `child`, which is the node itself if it is shorter than 32 bytes and
+/// its hash otherwise.
+fn append_reference(stream: &mut RlpStream, child: Vec<u8>) {
+    if child.len() < 32 {
+        stream.append_raw(&child, 1);
+    } else {
+        stream.append(&keccak256(child).to_vec());
+    }
+}
+
+/// Encodes a path with the hex prefix encoding, whose first nibble flags whether the node is a
+/// leaf and whether the path has an odd number of nibbles.
+fn encode_path(path: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = 2 * is_leaf as u8 + (path.len() % 2) as u8;
+    let mut nibbles = vec![flag];
+    if path.len() % 2 == 0 {
+        nibbles.push(0);
+    }
+    nibbles.extend_from_slice(path);
+    nibbles
+        .chunks(2)
+        .map(|pair| (pair[0] << 4) | pair[1])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::bytes32;
+
+    fn entries(pairs: &[(&str, &str)]) -> Vec<(Vec<u8>, Vec<u8>)> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.as_bytes().to_vec(), value.as_bytes().to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn test_trie_root() {
+        assert_eq!(
+            trie_root(&[]),
+            bytes32!("0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421")
+        );
+
+        // The vectors of go-ethereum's trie tests, which have extension nodes, embedded nodes and
+        // a value in a branch node.
+        let dogs = entries(&[
+            ("doe", "reindeer"),
+            ("dog", "puppy"),
+            ("dogglesworth", "cat"),
+        ]);
+        assert_eq!(
+            trie_root(&dogs),
+            bytes32!("0x8aad789dff2f538bca5d8ea56e8abe10f4c7ba3a5dea95fea4cd6e7c3a1168d3")
+        );
+        let puppy = entries(&[
+            ("do", "verb"),
+            ("horse", "stallion"),
+            ("doge", "coin"),
+            ("dog", "puppy"),
+        ]);
+        assert_eq!(
+            trie_root(&puppy),
+            bytes32!("0x5991bb8c6514148a29db676a14ac506cd2cd5775ace63c30a4fe457715e9ac84")
+        );
+        let value = "a".repeat(75);
+        let single = entries(&[("A", value.as_str())]);
+        assert_eq!(
+            trie_root(&single),
+            bytes32!("0xd23786fb4a010da3ce639d66d5e904a11dbc02746d1ce25029e53290cabf28ab")
+        );
+    }
+
+    #[test]
+    fn test_trie_proof() {
+        // Hashed keys, so that every node is at least 32 bytes long and referenced by its hash.
+        let entries = (0..50u64)
+            .map(|i| (keccak256(i.to_be_bytes()).to_vec(), vec![i as u8 + 1; 40]))
+            .collect::<Vec<_>>();
+
+        let (root, proof) = trie_root_and_proof(&entries, &entries[7].0);
+        assert_eq!(root, trie_root(&entries));
+        assert_eq!(H256::from(keccak256(&proof[0])), root);
+        for pair in proof.windows(2) {
+            let child_hash = keccak256(&pair[1]);
+            assert!(pair[0]
+                .windows(32)
+                .any(|window| window == child_hash.as_slice()));
+        }
+        let leaf = proof.last().unwrap();
+        assert!(leaf.ends_with(&entries[7].1));
+
+        // The proof of an absent key stops where its path leaves the trie.
+        let (_, absent_proof) = trie_root_and_proof(&entries, &keccak256(b"absent"));
+        assert!(!absent_proof.is_empty());
+        assert_eq!(H256::from(keccak256(&absent_proof[0])), root);
+    }
+}
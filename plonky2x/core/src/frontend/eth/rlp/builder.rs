@@ -52,6 +52,68 @@ impl<L: PlonkParameters<D>, const D: usize, const ENCODING_LEN: usize, const LIS
 }
 
 impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Decodes the header of an RLP item given its first three bytes, returning whether the item
+    /// is a list, the length of the header and the length of the payload. Payloads of up to
+    /// 65535 bytes are supported.
+    pub fn decode_rlp_header(
+        &mut self,
+        prefix: [ByteVariable; 3],
+    ) -> (BoolVariable, Variable, Variable) {
+        let bits = prefix[0].as_be_bits();
+        let first = prefix[0].to_variable(self);
+        let second = prefix[1].to_variable(self);
+        let third = prefix[2].to_variable(self);
+
+        // Bytes below 0x80 are their own encoding, 0x80..0xbf prefix strings and 0xc0..0xff
+        // prefix lists. Payloads longer than 55 bytes have a prefix of 0xb8..0xbf or 0xf8..0xff,
+        // followed by the big-endian payload length in `(prefix & 7) + 1` bytes.
+        let is_single_byte = self.not(bits[0]);
+        let is_list = self.and(bits[0], bits[1]);
+        let is_long = self.and(bits[0], bits[2]);
+        let is_long = self.and(is_long, bits[3]);
+        let is_long = self.and(is_long, bits[4]);
+
+        let false_v = self._false();
+        let is_too_long = self.or(bits[5], bits[6]);
+        let is_too_long = self.and(is_long, is_too_long);
+        self.assert_is_equal(is_too_long, false_v);
+
+        let string_offset = self.constant::<Variable>(L::Field::from_canonical_u8(0x80));
+        let list_offset = self.constant::<Variable>(L::Field::from_canonical_u8(0xc0));
+        let offset = self.select(is_list, list_offset, string_offset);
+        let short_payload_len = self.sub(first, offset);
+
+        let byte_size = self.constant::<Variable>(L::Field::from_canonical_u16(256));
+        let two_byte_len = self.mul(second, byte_size);
+        let two_byte_len = self.add(two_byte_len, third);
+        let long_payload_len = self.select(bits[7], two_byte_len, second);
+
+        let one = self.one::<Variable>();
+        let two = self.constant::<Variable>(L::Field::from_canonical_u8(2));
+        let three = self.constant::<Variable>(L::Field::from_canonical_u8(3));
+        let long_header_len = self.select(bits[7], three, two);
+
+        let zero = self.zero::<Variable>();
+        let header_len = self.select(is_long, long_header_len, one);
+        let header_len = self.select(is_single_byte, zero, header_len);
+        let payload_len = self.select(is_long, long_payload_len, short_payload_len);
+        let payload_len = self.select(is_single_byte, one, payload_len);
+
+        (is_list, header_len, payload_len)
+    }
+
+    /// Decodes the header of the RLP item starting at `position` in `encoded`, where `seed` is a
+    /// commitment to `encoded` as in `get_fixed_subarray`. See `decode_rlp_header`.
+    pub fn decode_rlp_header_at<const ENCODING_LEN: usize>(
+        &mut self,
+        encoded: &ArrayVariable<ByteVariable, ENCODING_LEN>,
+        position: Variable,
+        seed: &[ByteVariable],
+    ) -> (BoolVariable, Variable, Variable) {
+        let prefix = self.get_fixed_subarray::<ENCODING_LEN, 3>(encoded, position, seed);
+        self.decode_rlp_header(prefix.as_slice().try_into().unwrap())
+    }
+
     pub fn decode_element_as_list<
         const ENCODING_LEN: usize,
         const LIST_LEN: usize,
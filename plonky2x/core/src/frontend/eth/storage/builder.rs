@@ -1,14 +1,25 @@
 use ethers::types::Address;
+use plonky2::field::types::Field;
 
 use super::generators::{
-    EthBlockGenerator, EthLogGenerator, EthStorageKeyGenerator, EthStorageProofHint,
+    EthBlockGenerator, EthHeaderHint, EthLogGenerator, EthReceiptHint, EthStorageKeyGenerator,
+    EthStorageMptProofHint, EthStorageProofHint, EthTransactionHint, ACCOUNT_PROOF_LEN,
+    BLOCK_TRIE_NODE_LEN, BLOCK_TRIE_PROOF_LEN, HEADER_ENCODING_LEN, LOG_DATA_LEN,
+    RECEIPT_ENCODING_LEN, STORAGE_PROOF_ENCODING_LEN, STORAGE_PROOF_LEN, TRANSACTION_ENCODING_LEN,
+};
+use super::vars::{
+    EthAccountVariable, EthHeaderRootsVariable, EthHeaderVariable, EthLogVariable,
+    EthTransactionVariable,
 };
-use super::vars::{EthAccountVariable, EthHeaderVariable, EthLogVariable};
 use crate::backend::circuit::PlonkParameters;
 use crate::frontend::builder::CircuitBuilder;
 use crate::frontend::eth::vars::AddressVariable;
+use crate::frontend::uint::int256::I256Variable;
 use crate::frontend::uint::uint256::U256Variable;
-use crate::frontend::vars::{Bytes32Variable, VariableStream};
+use crate::frontend::vars::{
+    ArrayVariable, ByteVariable, Bytes32Variable, BytesVariable, CircuitVariable, EvmVariable,
+    U32Variable, Variable, VariableStream,
+};
 
 impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
     pub fn get_storage_key_at_witness(
@@ -39,6 +50,184 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         output_stream.read::<Bytes32Variable>(self)
     }
 
+    /// Computes the storage location `keccak256(map_key || mapping_location)` of the value at
+    /// `map_key` in the mapping stored at `mapping_location`.
+    pub fn get_storage_key_at(
+        &mut self,
+        mapping_location: U256Variable,
+        map_key: Bytes32Variable,
+    ) -> Bytes32Variable {
+        let mut input = map_key.as_bytes().to_vec();
+        input.extend(mapping_location.encode(self));
+        let length = self.constant::<U32Variable>(input.len() as u32);
+        self.keccak256_var(&input, length)
+    }
+
+    /// Fetches the header of the block with hash `block_hash` and returns the roots of the tries it
+    /// commits to.
+    ///
+    /// The encoded header is checked against `block_hash` with `keccak256_var`. It is a list of
+    /// more than 255 bytes starting with the parent hash, the uncles hash and the coinbase, so the
+    /// roots are at fixed positions.
+    pub fn eth_get_header_roots(&mut self, block_hash: Bytes32Variable) -> EthHeaderRootsVariable {
+        let mut input_stream = VariableStream::new();
+        input_stream.write(&block_hash);
+        let hint = EthHeaderHint::new(self);
+        let output_stream = self.async_hint(input_stream, hint);
+        let header = output_stream.read::<ArrayVariable<ByteVariable, HEADER_ENCODING_LEN>>(self);
+
+        // The list header is `0xf9` followed by the 2-byte length of the payload, without leading
+        // zeros.
+        let list_prefix = self.constant::<ByteVariable>(0xf9);
+        self.assert_is_equal(header[0], list_prefix);
+        let zero_byte = self.constant::<ByteVariable>(0);
+        let has_leading_zero = self.is_equal(header[1], zero_byte);
+        let false_v = self._false();
+        self.assert_is_equal(has_leading_zero, false_v);
+        let len_high = header[1].to_variable(self);
+        let len_low = header[2].to_variable(self);
+        let byte_base = self.constant::<Variable>(L::Field::from_canonical_u16(256));
+        let list_header_len = self.constant::<Variable>(L::Field::from_canonical_u8(3));
+        let header_len = self.mul(len_high, byte_base);
+        let header_len = self.add(header_len, len_low);
+        let header_len = self.add(header_len, list_header_len);
+        let header_len = U32Variable::from_variables(self, &[header_len]);
+        let header_hash = self.keccak256_var(header.as_slice(), header_len);
+        self.assert_is_equal(header_hash, block_hash);
+
+        // The parent hash, uncles hash, coinbase, state root, transactions root and receipts root,
+        // each with its 1-byte string header.
+        let mut items = Vec::new();
+        let mut position = 3;
+        for item_len in [32, 32, 20, 32, 32, 32] {
+            let prefix = self.constant::<ByteVariable>(0x80 + item_len as u8);
+            self.assert_is_equal(header[position], prefix);
+            items.push(&header.as_slice()[position + 1..position + 1 + item_len]);
+            position += 1 + item_len;
+        }
+        EthHeaderRootsVariable {
+            state_root: Bytes32Variable::from(items[3]),
+            transactions_root: Bytes32Variable::from(items[4]),
+            receipts_root: Bytes32Variable::from(items[5]),
+        }
+    }
+
+    /// Returns the value at `storage_key` in the storage of `address` at the block with hash
+    /// `block_hash`, which is zero if the slot is empty.
+    ///
+    /// The value is proven by the merkle patricia proof of the slot against the storage root of the
+    /// account, itself proven by the proof of the account against the state root of the block
+    /// header.
+    pub fn eth_get_storage_at(
+        &mut self,
+        block_hash: Bytes32Variable,
+        address: AddressVariable,
+        storage_key: Bytes32Variable,
+    ) -> Bytes32Variable {
+        let roots = self.eth_get_header_roots(block_hash);
+
+        let mut input_stream = VariableStream::new();
+        input_stream.write(&block_hash);
+        input_stream.write(&address);
+        input_stream.write(&storage_key);
+
+        let hint = EthStorageMptProofHint::new(self);
+        let output_stream = self.async_hint(input_stream, hint);
+        let account_proof = output_stream.read::<ArrayVariable<
+            ArrayVariable<ByteVariable, STORAGE_PROOF_ENCODING_LEN>,
+            ACCOUNT_PROOF_LEN,
+        >>(self);
+        let account_lengths =
+            output_stream.read::<ArrayVariable<U32Variable, ACCOUNT_PROOF_LEN>>(self);
+        let storage_proof = output_stream.read::<ArrayVariable<
+            ArrayVariable<ByteVariable, STORAGE_PROOF_ENCODING_LEN>,
+            STORAGE_PROOF_LEN,
+        >>(self);
+        let storage_lengths =
+            output_stream.read::<ArrayVariable<U32Variable, STORAGE_PROOF_LEN>>(self);
+
+        let storage_root = self.get_account_storage_root(
+            address,
+            &account_proof,
+            &account_lengths,
+            roots.state_root,
+        );
+        self.get_storage_proof_value(storage_key, &storage_proof, &storage_lengths, storage_root)
+    }
+
+    /// Returns the balance of `holder` in the ERC-20 `token`, whose `balances` mapping is stored
+    /// at `balances_slot` (e.g. 9 for USDC), at the block with hash `block_hash`.
+    pub fn erc20_balance_of(
+        &mut self,
+        token: AddressVariable,
+        holder: AddressVariable,
+        balances_slot: U256Variable,
+        block_hash: Bytes32Variable,
+    ) -> U256Variable {
+        // Mapping keys are abi encoded, so the address is left padded to 32 bytes.
+        let zero = self.constant::<ByteVariable>(0);
+        let mut map_key = vec![zero; 12];
+        map_key.extend(holder.0 .0);
+        let map_key = Bytes32Variable(BytesVariable(map_key.try_into().unwrap()));
+
+        let storage_key = self.get_storage_key_at(balances_slot, map_key);
+        let balance = self.eth_get_storage_at(block_hash, token, storage_key);
+        U256Variable::decode(self, &balance.as_bytes())
+    }
+
+    /// Returns the change of the value at `storage_key` in the storage of `address` from the block
+    /// with hash `block_hash_a` to the block with hash `block_hash_b`, e.g. the change of a
+    /// contract's reserve. Both values are verified against the storage proofs of their block.
+    ///
+    /// The values are read as unsigned integers, and the circuit fails if their difference does
+    /// not fit in an `int256`.
+    pub fn storage_slot_delta(
+        &mut self,
+        address: AddressVariable,
+        storage_key: Bytes32Variable,
+        block_hash_a: Bytes32Variable,
+        block_hash_b: Bytes32Variable,
+    ) -> I256Variable {
+        let value_a = self.eth_get_storage_at(block_hash_a, address, storage_key);
+        let value_b = self.eth_get_storage_at(block_hash_b, address, storage_key);
+        let value_a = U256Variable::decode(self, &value_a.as_bytes());
+        let value_b = U256Variable::decode(self, &value_b.as_bytes());
+        self.i256_sub_u256(value_b, value_a)
+    }
+
+    /// Returns the owner of `token_id` in the ERC-721 `token`, whose `_owners` mapping is stored
+    /// at `owners_slot` (e.g. 2 for OpenZeppelin's ERC721), at the block with hash `block_hash`.
+    ///
+    /// Like `ownerOf`, the proof fails if the token is not minted, i.e. its owner is the zero
+    /// address. The slot of an unminted token is empty, so `eth_get_storage_at` proves it is zero
+    /// and the circuit rejects it, rather than witness generation failing.
+    pub fn erc721_owner_of(
+        &mut self,
+        token: AddressVariable,
+        token_id: U256Variable,
+        owners_slot: U256Variable,
+        block_hash: Bytes32Variable,
+    ) -> AddressVariable {
+        let map_key = token_id.encode(self);
+        let map_key = Bytes32Variable(BytesVariable(map_key.try_into().unwrap()));
+        let storage_key = self.get_storage_key_at(owners_slot, map_key);
+        let value = self.eth_get_storage_at(block_hash, token, storage_key);
+
+        // The address is right aligned in the slot, so the upper 12 bytes must be zero.
+        let bytes = value.as_bytes();
+        let zero = self.constant::<ByteVariable>(0);
+        for byte in bytes[..12].iter() {
+            self.assert_is_equal(*byte, zero);
+        }
+        let owner = AddressVariable(BytesVariable(bytes[12..].try_into().unwrap()));
+
+        let zero_address = self.zero::<AddressVariable>();
+        let is_unminted = self.is_equal(owner, zero_address);
+        let false_v = self._false();
+        self.assert_is_equal(is_unminted, false_v);
+        owner
+    }
+
     pub fn eth_get_block_by_hash_witness(
         &mut self,
         block_hash: Bytes32Variable,
@@ -68,20 +257,213 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         self.add_simple_generator(generator);
         value
     }
+
+    /// Returns the transaction with hash `transaction_hash` in the block with hash `block_hash`,
+    /// proven against the transactions root of the block header.
+    pub fn eth_get_transaction(
+        &mut self,
+        transaction_hash: Bytes32Variable,
+        block_hash: Bytes32Variable,
+    ) -> EthTransactionVariable {
+        let roots = self.eth_get_header_roots(block_hash);
+        self.eth_prove_transaction(transaction_hash, block_hash, roots.transactions_root)
+    }
+
+    /// Fetches the proof of the transaction with hash `transaction_hash` in the block with hash
+    /// `block_hash`, and verifies it against `transactions_root`.
+    fn eth_prove_transaction(
+        &mut self,
+        transaction_hash: Bytes32Variable,
+        block_hash: Bytes32Variable,
+        transactions_root: Bytes32Variable,
+    ) -> EthTransactionVariable {
+        let mut input_stream = VariableStream::new();
+        input_stream.write(&block_hash);
+        input_stream.write(&transaction_hash);
+        let hint = EthTransactionHint::new(self);
+        let output_stream = self.async_hint(input_stream, hint);
+        let index = output_stream.read::<U32Variable>(self);
+        let proof =
+            output_stream.read::<ArrayVariable<
+                ArrayVariable<ByteVariable, BLOCK_TRIE_NODE_LEN>,
+                BLOCK_TRIE_PROOF_LEN,
+            >>(self);
+        let lengths = output_stream.read::<ArrayVariable<U32Variable, BLOCK_TRIE_PROOF_LEN>>(self);
+        self.verify_transaction_proof(transaction_hash, transactions_root, index, &proof, &lengths)
+    }
+
+    /// Verifies the merkle patricia proof of the transaction at `index` in the transactions trie
+    /// with root `transactions_root`, and that its hash is `transaction_hash`.
+    pub fn verify_transaction_proof(
+        &mut self,
+        transaction_hash: Bytes32Variable,
+        transactions_root: Bytes32Variable,
+        index: U32Variable,
+        proof: &ArrayVariable<
+            ArrayVariable<ByteVariable, BLOCK_TRIE_NODE_LEN>,
+            BLOCK_TRIE_PROOF_LEN,
+        >,
+        len_nodes: &ArrayVariable<U32Variable, BLOCK_TRIE_PROOF_LEN>,
+    ) -> EthTransactionVariable {
+        let transaction = self.get_block_trie_item::<
+            BLOCK_TRIE_NODE_LEN,
+            BLOCK_TRIE_PROOF_LEN,
+            TRANSACTION_ENCODING_LEN,
+        >(transactions_root, index, proof, len_nodes);
+        let transaction_len = U32Variable::from_variables(self, &[transaction.len]);
+        let hash = self.keccak256_var(transaction.value.as_slice(), transaction_len);
+        self.assert_is_equal(hash, transaction_hash);
+        EthTransactionVariable {
+            hash: transaction_hash,
+            index,
+            encoding: transaction.value,
+            len: transaction.len,
+        }
+    }
+
+    /// Fetches the log at `log_index` of the receipt of `transaction_hash` and verifies that it is
+    /// the `log_index`-th log of the receipt.
+    ///
+    /// The receipt is proven against the receipts root of the block header, at the index of the
+    /// transaction proven against its transactions root. The log must have exactly three topics
+    /// and at most `LOG_DATA_LEN` bytes of data.
+    pub fn eth_get_transaction_log(
+        &mut self,
+        transaction_hash: Bytes32Variable,
+        block_hash: Bytes32Variable,
+        log_index: u64,
+    ) -> EthLogVariable {
+        let log = self.eth_get_transaction_log_witness(transaction_hash, block_hash, log_index);
+        let roots = self.eth_get_header_roots(block_hash);
+        let transaction =
+            self.eth_prove_transaction(transaction_hash, block_hash, roots.transactions_root);
+        let index = transaction.index;
+
+        let mut input_stream = VariableStream::new();
+        input_stream.write(&block_hash);
+        input_stream.write(&index);
+        let hint = EthReceiptHint::new(self);
+        let output_stream = self.async_hint(input_stream, hint);
+        let proof =
+            output_stream.read::<ArrayVariable<
+                ArrayVariable<ByteVariable, BLOCK_TRIE_NODE_LEN>,
+                BLOCK_TRIE_PROOF_LEN,
+            >>(self);
+        let lengths = output_stream.read::<ArrayVariable<U32Variable, BLOCK_TRIE_PROOF_LEN>>(self);
+        let receipt = self
+            .get_block_trie_item::<BLOCK_TRIE_NODE_LEN, BLOCK_TRIE_PROOF_LEN, RECEIPT_ENCODING_LEN>(
+                roots.receipts_root,
+                index,
+                &proof,
+                &lengths,
+            );
+
+        // The hash of the leaf holding the receipt commits to it, so it seeds the reads.
+        let seed = receipt.leaf_hash.as_bytes();
+        self.verify_receipt_log(&receipt.value, log_index, &log, &seed);
+        log
+    }
+
+    /// Asserts that `log` is the `log_index`-th log of `receipt`, the consensus encoding of a
+    /// transaction receipt padded with at least `LOG_DATA_LEN` zero bytes.
+    ///
+    /// `seed` must be a commitment to `receipt`, such as its hash, as it seeds the reads of
+    /// `get_fixed_subarray`.
+    pub fn verify_receipt_log<const ENCODING_LEN: usize>(
+        &mut self,
+        receipt: &ArrayVariable<ByteVariable, ENCODING_LEN>,
+        log_index: u64,
+        log: &EthLogVariable,
+        seed: &[ByteVariable],
+    ) {
+        let true_v = self._true();
+        let false_v = self._false();
+        let zero = self.zero::<Variable>();
+        let one = self.one::<Variable>();
+
+        // Receipts of typed transactions are prefixed by the transaction type, which is below 0x80.
+        let is_typed = self.not(receipt[0].as_be_bits()[0]);
+        let mut position = self.select(is_typed, one, zero);
+
+        // Enter the list `[status, cumulativeGasUsed, logsBloom, logs]` and skip to the logs.
+        let (is_list, header_len, _) = self.decode_rlp_header_at(receipt, position, seed);
+        self.assert_is_equal(is_list, true_v);
+        position = self.add(position, header_len);
+        for _ in 0..3 {
+            let (_, header_len, payload_len) = self.decode_rlp_header_at(receipt, position, seed);
+            position = self.add(position, header_len);
+            position = self.add(position, payload_len);
+        }
+
+        // Enter the list of logs and skip the logs before `log_index`.
+        let (is_list, header_len, _) = self.decode_rlp_header_at(receipt, position, seed);
+        self.assert_is_equal(is_list, true_v);
+        position = self.add(position, header_len);
+        for _ in 0..log_index {
+            let (_, header_len, payload_len) = self.decode_rlp_header_at(receipt, position, seed);
+            position = self.add(position, header_len);
+            position = self.add(position, payload_len);
+        }
+
+        // The log is the list `[address, topics, data]`.
+        let (is_list, header_len, payload_len) = self.decode_rlp_header_at(receipt, position, seed);
+        self.assert_is_equal(is_list, true_v);
+        position = self.add(position, header_len);
+        let log_end = self.add(position, payload_len);
+
+        let address = self.get_fixed_subarray::<ENCODING_LEN, 21>(receipt, position, seed);
+        let address_prefix = self.constant::<ByteVariable>(0x80 + 20);
+        self.assert_is_equal(address[0], address_prefix);
+        for (byte, expected) in address.as_slice()[1..].iter().zip(log.address.0 .0.iter()) {
+            self.assert_is_equal(*byte, *expected);
+        }
+        let address_len = self.constant::<Variable>(L::Field::from_canonical_usize(21));
+        position = self.add(position, address_len);
+
+        let (is_list, header_len, payload_len) = self.decode_rlp_header_at(receipt, position, seed);
+        self.assert_is_equal(is_list, true_v);
+        let topics_len = self.constant::<Variable>(L::Field::from_canonical_usize(3 * 33));
+        self.assert_is_equal(payload_len, topics_len);
+        position = self.add(position, header_len);
+        let topics = self.get_fixed_subarray::<ENCODING_LEN, 99>(receipt, position, seed);
+        let topic_prefix = self.constant::<ByteVariable>(0x80 + 32);
+        for (encoded, expected) in topics.as_slice().chunks(33).zip(log.topics.as_slice()) {
+            self.assert_is_equal(encoded[0], topic_prefix);
+            for (byte, expected) in encoded[1..].iter().zip(expected.as_bytes().iter()) {
+                self.assert_is_equal(*byte, *expected);
+            }
+        }
+        position = self.add(position, topics_len);
+
+        let (is_list, header_len, payload_len) = self.decode_rlp_header_at(receipt, position, seed);
+        self.assert_is_equal(is_list, false_v);
+        position = self.add(position, header_len);
+        let data = self.get_fixed_subarray::<ENCODING_LEN, LOG_DATA_LEN>(receipt, position, seed);
+        let data_len = U32Variable::from_variables(self, &[payload_len]);
+        let data_hash = self.curta_sha256_variable(data.as_slice(), data_len);
+        self.assert_is_equal(data_hash, log.data_hash);
+        position = self.add(position, payload_len);
+
+        // The log has no other fields.
+        self.assert_is_equal(position, log_end);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::env;
 
-    use ethers::providers::{Http, Provider};
-    use ethers::types::{U256, U64};
+    use ethers::providers::{Http, Middleware, Provider};
+    use ethers::types::{Log, TransactionReceipt, H256, I256, U256, U64};
+    use ethers::utils::rlp;
     use log::debug;
+    use sha2::{Digest, Sha256};
+    use tokio::runtime::Runtime;
 
     use super::*;
     use crate::backend::circuit::{CircuitBuild, DefaultParameters, GateRegistry, HintRegistry};
     use crate::frontend::eth::storage::utils::get_map_storage_location;
-    use crate::frontend::eth::storage::vars::{EthHeader, EthLog};
+    use crate::frontend::eth::storage::vars::{EthHeader, EthHeaderRoots, EthLog};
     use crate::prelude::DefaultBuilder;
     use crate::utils::{self, address, bytes32};
 
@@ -274,6 +656,267 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_eth_get_header_roots() {
+        utils::setup_logger();
+        dotenv::dotenv().ok();
+        let rpc_url = env::var("RPC_1").unwrap();
+        let provider = Provider::<Http>::try_from(rpc_url).unwrap();
+
+        let mut builder = DefaultBuilder::new();
+        builder.set_execution_client(provider);
+        let block_hash = builder.read::<Bytes32Variable>();
+        let roots = builder.eth_get_header_roots(block_hash);
+        builder.write(roots);
+        let circuit = builder.build();
+
+        // The roots of Ethereum block https://etherscan.io/block/17880427
+        let mut input = circuit.input();
+        input.write::<Bytes32Variable>(bytes32!(
+            "0x281dc31bb78779a1ede7bf0f4d2bc5f07ddebc9f9d1155e413d8804384604bbe"
+        ));
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        assert_eq!(
+            output.read::<EthHeaderRootsVariable>(),
+            EthHeaderRoots {
+                state_root: bytes32!(
+                    "0xff90251f501c864f21d696c811af4c3aa987006916bd0e31a6c06cc612e7632e"
+                ),
+                transactions_root: bytes32!(
+                    "0x8d0a3c10b76930ebda83551649856882b51455de61689184c9db535ef5c29e93"
+                ),
+                receipts_root: bytes32!(
+                    "0x8fa46ad6b448faefbfc010736a3d39595ca68eb8bdd4e6b4ab30513bab688068"
+                ),
+            }
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_eth_get_storage_at() {
+        utils::setup_logger();
+        dotenv::dotenv().ok();
+        let rpc_url = env::var("RPC_1").unwrap();
+        let provider = Provider::<Http>::try_from(rpc_url).unwrap();
+
+        let mut builder = DefaultBuilder::new();
+        builder.set_execution_client(provider);
+        let block_hash = builder.read::<Bytes32Variable>();
+        let address = builder.read::<AddressVariable>();
+        let location = builder.read::<Bytes32Variable>();
+        let value = builder.eth_get_storage_at(block_hash, address, location);
+        builder.write(value);
+        let circuit = builder.build();
+
+        // A non-empty slot, an empty slot of the same contract and a slot of an account without
+        // storage at Ethereum block https://etherscan.io/block/17880427
+        let block_hash =
+            bytes32!("0x281dc31bb78779a1ede7bf0f4d2bc5f07ddebc9f9d1155e413d8804384604bbe");
+        let contract = address!("0x55032650b14df07b85bF18A3a3eC8E0Af2e028d5");
+        let cases = [
+            (
+                contract,
+                bytes32!("0xad3228b676f7d3cd4284a5443f17f1962b36e491b30a40b2405849e597ba5fb5"),
+                bytes32!("0x0000000000000000000000dd4bc51496dc93a0c47008e820e0d80745476f2201"),
+            ),
+            (contract, H256::from_low_u64_be(1 << 40), H256::zero()),
+            (
+                address!("0x000000000000000000000000000000000000dEaD"),
+                H256::zero(),
+                H256::zero(),
+            ),
+        ];
+        for (address, location, expected) in cases {
+            let mut input = circuit.input();
+            input.write::<Bytes32Variable>(block_hash);
+            input.write::<AddressVariable>(address);
+            input.write::<Bytes32Variable>(location);
+            let (proof, mut output) = circuit.prove(&input);
+            circuit.verify(&proof, &input, &output);
+            assert_eq!(output.read::<Bytes32Variable>(), expected);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_erc20_balance_of() {
+        utils::setup_logger();
+        dotenv::dotenv().ok();
+        let rpc_url = env::var("RPC_1").unwrap();
+        let provider = Provider::<Http>::try_from(rpc_url).unwrap();
+
+        // This is the circuit definition
+        let mut builder = DefaultBuilder::new();
+        builder.set_execution_client(provider.clone());
+        let token = builder.evm_read::<AddressVariable>();
+        let holder = builder.evm_read::<AddressVariable>();
+        let balances_slot = builder.evm_read::<U256Variable>();
+        let block_hash = builder.evm_read::<Bytes32Variable>();
+        let balance = builder.erc20_balance_of(token, holder, balances_slot, block_hash);
+        builder.evm_write(balance);
+
+        // Build your circuit.
+        let circuit = builder.build();
+
+        // Read the USDC balance of Binance 14 at Ethereum block https://etherscan.io/block/17880427
+        let token = address!("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+        let holder = address!("0x28C6c06298d514Db089934071355E5743bf21d60");
+        let balances_slot = 9u128;
+        let block_hash =
+            bytes32!("0x281dc31bb78779a1ede7bf0f4d2bc5f07ddebc9f9d1155e413d8804384604bbe");
+        let mut input = circuit.input();
+        input.evm_write::<AddressVariable>(token);
+        input.evm_write::<AddressVariable>(holder);
+        input.evm_write::<U256Variable>(U256::from(balances_slot));
+        input.evm_write::<Bytes32Variable>(block_hash);
+
+        // Generate a proof.
+        let (proof, mut output) = circuit.prove(&input);
+
+        // Verify proof.
+        circuit.verify(&proof, &input, &output);
+
+        // Read output.
+        let balance = output.evm_read::<U256Variable>();
+        debug!("balance: {:?}", balance);
+        let location = get_map_storage_location(balances_slot, H256::from(holder));
+        let expected = Runtime::new()
+            .unwrap()
+            .block_on(provider.get_storage_at(token, location, Some(block_hash.into())))
+            .unwrap();
+        assert_eq!(balance, U256::from(expected.to_fixed_bytes()));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_storage_slot_delta() {
+        utils::setup_logger();
+        dotenv::dotenv().ok();
+        let rpc_url = env::var("RPC_1").unwrap();
+        let provider = Provider::<Http>::try_from(rpc_url).unwrap();
+
+        let mut builder = DefaultBuilder::new();
+        builder.set_execution_client(provider.clone());
+        let address = builder.read::<AddressVariable>();
+        let storage_key = builder.read::<Bytes32Variable>();
+        let block_hash_a = builder.read::<Bytes32Variable>();
+        let block_hash_b = builder.read::<Bytes32Variable>();
+        let delta = builder.storage_slot_delta(address, storage_key, block_hash_a, block_hash_b);
+        builder.write(delta);
+
+        let circuit = builder.build();
+
+        // The USDC balance of Binance 14 over the 100 block intervals before Ethereum block
+        // https://etherscan.io/block/17880427. The balance of the hot wallet moves both ways, so
+        // the first interval where it increased and the first where it decreased are proven.
+        let address = address!("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+        let holder = address!("0x28C6c06298d514Db089934071355E5743bf21d60");
+        let storage_key = get_map_storage_location(9u128, H256::from(holder));
+        let rt = Runtime::new().unwrap();
+        let block_at = |number: u64| {
+            let block = rt.block_on(provider.get_block(number)).unwrap().unwrap();
+            block.hash.unwrap()
+        };
+        let value_at = |block_hash: H256| {
+            let value = rt
+                .block_on(provider.get_storage_at(address, storage_key, Some(block_hash.into())))
+                .unwrap();
+            I256::from_raw(U256::from(value.to_fixed_bytes()))
+        };
+        let mut increase = None;
+        let mut decrease = None;
+        let mut block_hash_b =
+            bytes32!("0x281dc31bb78779a1ede7bf0f4d2bc5f07ddebc9f9d1155e413d8804384604bbe");
+        for number in (0..50).map(|i| 17880427 - 100 * (i + 1)) {
+            let block_hash_a = block_at(number);
+            let change = value_at(block_hash_b) - value_at(block_hash_a);
+            if change.is_positive() && increase.is_none() {
+                increase = Some((block_hash_a, block_hash_b, change));
+            } else if change.is_negative() && decrease.is_none() {
+                decrease = Some((block_hash_a, block_hash_b, change));
+            }
+            if increase.is_some() && decrease.is_some() {
+                break;
+            }
+            block_hash_b = block_hash_a;
+        }
+
+        for (block_hash_a, block_hash_b, expected) in [increase.unwrap(), decrease.unwrap()] {
+            assert_ne!(block_hash_a, block_hash_b);
+            let mut input = circuit.input();
+            input.write::<AddressVariable>(address);
+            input.write::<Bytes32Variable>(storage_key);
+            input.write::<Bytes32Variable>(block_hash_a);
+            input.write::<Bytes32Variable>(block_hash_b);
+            let (proof, mut output) = circuit.prove(&input);
+            circuit.verify(&proof, &input, &output);
+            assert_eq!(output.read::<I256Variable>(), expected);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_erc721_owner_of() {
+        utils::setup_logger();
+        dotenv::dotenv().ok();
+        let rpc_url = env::var("RPC_1").unwrap();
+        let provider = Provider::<Http>::try_from(rpc_url).unwrap();
+
+        // This is the circuit definition
+        let mut builder = DefaultBuilder::new();
+        builder.set_execution_client(provider.clone());
+        let token = builder.evm_read::<AddressVariable>();
+        let token_id = builder.evm_read::<U256Variable>();
+        let owners_slot = builder.evm_read::<U256Variable>();
+        let block_hash = builder.evm_read::<Bytes32Variable>();
+        let owner = builder.erc721_owner_of(token, token_id, owners_slot, block_hash);
+        builder.evm_write(owner);
+
+        // Build your circuit.
+        let circuit = builder.build();
+
+        // Read the owner of Doodle #1 at Ethereum block https://etherscan.io/block/17880427
+        let token = address!("0x8a90CAb2b38dba80c64b7734e58Ee1dB38B8992e");
+        let token_id = U256::from(1);
+        let owners_slot = 2u128;
+        let block_hash =
+            bytes32!("0x281dc31bb78779a1ede7bf0f4d2bc5f07ddebc9f9d1155e413d8804384604bbe");
+        let mut input = circuit.input();
+        input.evm_write::<AddressVariable>(token);
+        input.evm_write::<U256Variable>(token_id);
+        input.evm_write::<U256Variable>(U256::from(owners_slot));
+        input.evm_write::<Bytes32Variable>(block_hash);
+
+        // Generate a proof.
+        let (proof, mut output) = circuit.prove(&input);
+
+        // Verify proof.
+        circuit.verify(&proof, &input, &output);
+
+        // Read output.
+        let owner = output.evm_read::<AddressVariable>();
+        debug!("owner: {:?}", owner);
+        let mut token_id_bytes = [0u8; 32];
+        token_id.to_big_endian(&mut token_id_bytes);
+        let location = get_map_storage_location(owners_slot, H256::from(token_id_bytes));
+        let expected = Runtime::new()
+            .unwrap()
+            .block_on(provider.get_storage_at(token, location, Some(block_hash.into())))
+            .unwrap();
+        assert_eq!(owner, Address::from(expected));
+
+        // Doodles has 10000 tokens, so token #20000 is not minted.
+        let mut input = circuit.input();
+        input.evm_write::<AddressVariable>(token);
+        input.evm_write::<U256Variable>(U256::from(20000));
+        input.evm_write::<U256Variable>(U256::from(owners_slot));
+        input.evm_write::<Bytes32Variable>(block_hash);
+        assert!(circuit.mock_prove(&input).is_err());
+    }
+
     #[test]
     #[cfg_attr(feature = "ci", ignore)]
     #[allow(non_snake_case)]
@@ -417,4 +1060,110 @@ mod tests {
             .serialize(&gate_serializer, &hint_serializer)
             .unwrap();
     }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_eth_get_transaction_log() {
+        utils::setup_logger();
+        dotenv::dotenv().ok();
+        let rpc_url = env::var("RPC_1").unwrap();
+        let provider = Provider::<Http>::try_from(rpc_url).unwrap();
+
+        let mut builder = DefaultBuilder::new();
+        builder.set_execution_client(provider);
+        let transaction_hash = builder.read::<Bytes32Variable>();
+        let block_hash = builder.read::<Bytes32Variable>();
+        let log = builder.eth_get_transaction_log(transaction_hash, block_hash, 0);
+        builder.write::<EthLogVariable>(log);
+        let circuit = builder.build();
+
+        // The first log of a transaction of Ethereum block https://etherscan.io/block/17880427
+        let mut input = circuit.input();
+        input.write::<Bytes32Variable>(bytes32!(
+            "0xead2251970404128e6f9bdff0133badb7338c5fa7ea4eec24e88af85a6d03cf2"
+        ));
+        input.write::<Bytes32Variable>(bytes32!(
+            "0x281dc31bb78779a1ede7bf0f4d2bc5f07ddebc9f9d1155e413d8804384604bbe"
+        ));
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        assert_eq!(
+            output.read::<EthLogVariable>(),
+            EthLog {
+                address: address!("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"),
+                topics: [
+                    bytes32!("0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"),
+                    bytes32!("0x00000000000000000000000059b4bb1f5d943cf71a10df63f6b743ee4a4489ee"),
+                    bytes32!("0x000000000000000000000000def1c0ded9bec7f1a1670819833240f027b25eff")
+                ]
+                .to_vec(),
+                data_hash: bytes32!(
+                    "0x5cdda96947975d4afbc971c9aa8bb2cc684e158d10a0d878b3a5b8b0f895262c"
+                )
+            }
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_verify_receipt_log() {
+        utils::setup_logger();
+        const ENCODING_LEN: usize = 1024;
+
+        // The logs have empty, short and long data, so that every kind of string header is read.
+        let logs = (0..3u64)
+            .map(|i| Log {
+                address: Address::from_low_u64_be(i + 1),
+                topics: (0..3).map(|j| H256::from_low_u64_be(10 * i + j)).collect(),
+                data: vec![i as u8 + 1; 32 * i as usize].into(),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let receipt = TransactionReceipt {
+            status: Some(U64::from(1)),
+            cumulative_gas_used: U256::from(100_000),
+            logs: logs.clone(),
+            transaction_type: Some(U64::from(2)),
+            ..Default::default()
+        };
+        let mut encoding = vec![2u8];
+        encoding.extend_from_slice(&rlp::encode(&receipt));
+        encoding.resize(ENCODING_LEN, 0);
+
+        let log_index = 2;
+        let mut builder = DefaultBuilder::new();
+        let receipt = builder.read::<ArrayVariable<ByteVariable, ENCODING_LEN>>();
+        let log = builder.read::<EthLogVariable>();
+        let receipt_len = builder.constant::<U32Variable>(ENCODING_LEN as u32);
+        let seed = builder.keccak256_var(receipt.as_slice(), receipt_len);
+        builder.verify_receipt_log(&receipt, log_index, &log, &seed.as_bytes());
+        let circuit = builder.build();
+
+        let log_value = |log: &Log| EthLog {
+            address: log.address,
+            topics: log.topics.clone(),
+            data_hash: H256::from_slice(Sha256::digest(&log.data).as_ref()),
+        };
+        let valid = log_value(&logs[log_index as usize]);
+        let mut tampered_address = valid.clone();
+        tampered_address.address = Address::from_low_u64_be(1);
+        let mut tampered_topic = valid.clone();
+        tampered_topic.topics[1] = H256::from_low_u64_be(1);
+        let mut tampered_data = valid.clone();
+        tampered_data.data_hash = log_value(&logs[1]).data_hash;
+
+        let cases = [
+            (valid, true),
+            (log_value(&logs[1]), false),
+            (tampered_address, false),
+            (tampered_topic, false),
+            (tampered_data, false),
+        ];
+        for (log, is_valid) in cases {
+            let mut input = circuit.input();
+            input.write::<ArrayVariable<ByteVariable, ENCODING_LEN>>(encoding.clone());
+            input.write::<EthLogVariable>(log);
+            assert_eq!(circuit.mock_prove(&input).is_ok(), is_valid);
+        }
+    }
 }
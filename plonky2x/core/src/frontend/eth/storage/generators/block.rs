@@ -1,20 +1,27 @@
 use core::fmt::Debug;
 use core::marker::PhantomData;
 
-use ethers::providers::Middleware;
-use ethers::types::{Block, H256};
+use async_trait::async_trait;
+use ethers::types::{Block, Bytes, H256, U256};
+use ethers::utils::keccak256;
+use ethers::utils::rlp::RlpStream;
 use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
 use plonky2::iop::target::Target;
 use plonky2::iop::witness::PartitionWitness;
 use plonky2::plonk::circuit_data::CommonCircuitData;
 use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use tokio::runtime::Runtime;
 
 use crate::backend::circuit::PlonkParameters;
 use crate::frontend::builder::CircuitBuilder;
 use crate::frontend::eth::storage::vars::{EthHeader, EthHeaderVariable};
-use crate::frontend::vars::{Bytes32Variable, CircuitVariable};
-use crate::utils::eth::get_provider;
+use crate::frontend::hint::asynchronous::hint::AsyncHint;
+use crate::frontend::vars::{
+    ArrayVariable, ByteVariable, Bytes32Variable, CircuitVariable, ValueStream,
+};
+use crate::utils::eth::get_rpc_client;
 
 #[derive(Debug, Clone)]
 pub struct EthBlockGenerator<L: PlonkParameters<D>, const D: usize> {
@@ -63,15 +70,16 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
         buffer: &mut GeneratedValues<L::Field>,
     ) {
         let block_hash = self.block_hash.get(witness);
-        let provider = get_provider(self.chain_id);
+        let client = get_rpc_client(self.chain_id);
         let rt = Runtime::new().expect("failed to create tokio runtime");
         let result: Block<H256> = rt
-            .block_on(async {
-                provider
-                    .get_block(block_hash)
-                    .await
-                    .expect("Failed to get block from RPC")
-            })
+            .block_on(
+                client.request::<Option<Block<H256>>>(
+                    "eth_getBlockByHash",
+                    json!([block_hash, false]),
+                ),
+            )
+            .expect("Failed to get block from RPC")
             .expect("No matching block found");
 
         let value = EthHeader {
@@ -128,3 +136,113 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
         })
     }
 }
+
+/// The maximum length in bytes of an encoded block header.
+pub const HEADER_ENCODING_LEN: usize = 800;
+
+/// The fields of a block header in the order of its encoding, and whether they are quantities,
+/// which are encoded as integers, rather than byte strings. The fields from `baseFeePerGas` were
+/// added by later forks and are only encoded if the block has them.
+const HEADER_FIELDS: [(&str, bool); 21] = [
+    ("parentHash", false),
+    ("sha3Uncles", false),
+    ("miner", false),
+    ("stateRoot", false),
+    ("transactionsRoot", false),
+    ("receiptsRoot", false),
+    ("logsBloom", false),
+    ("difficulty", true),
+    ("number", true),
+    ("gasLimit", true),
+    ("gasUsed", true),
+    ("timestamp", true),
+    ("extraData", false),
+    ("mixHash", false),
+    ("nonce", false),
+    ("baseFeePerGas", true),
+    ("withdrawalsRoot", false),
+    ("blobGasUsed", true),
+    ("excessBlobGas", true),
+    ("parentBeaconBlockRoot", false),
+    ("requestsHash", false),
+];
+
+/// Returns the RLP encoding of the header of `block`, as returned by `eth_getBlockByHash`, whose
+/// hash is the block hash.
+pub fn encode_header(block: &Value) -> Vec<u8> {
+    let fields = HEADER_FIELDS
+        .iter()
+        .filter_map(|(name, is_quantity)| {
+            block
+                .get(name)
+                .filter(|value| !value.is_null())
+                .map(|value| (value.clone(), *is_quantity))
+        })
+        .collect::<Vec<_>>();
+    let mut stream = RlpStream::new_list(fields.len());
+    for (value, is_quantity) in fields {
+        if is_quantity {
+            let quantity: U256 = serde_json::from_value(value).expect("invalid header quantity");
+            stream.append(&quantity);
+        } else {
+            let bytes: Bytes = serde_json::from_value(value).expect("invalid header bytes");
+            stream.append(&bytes.to_vec());
+        }
+    }
+    stream.out().to_vec()
+}
+
+/// Input: (block_hash: bytes32)
+/// Output: (header: bytes[HEADER_ENCODING_LEN])
+///
+/// Fetches the RLP encoding of a block header, padded with zeros.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EthHeaderHint<L: PlonkParameters<D>, const D: usize> {
+    chain_id: u64,
+    _phantom: PhantomData<L>,
+}
+
+impl<L: PlonkParameters<D>, const D: usize> EthHeaderHint<L, D> {
+    pub fn new(builder: &CircuitBuilder<L, D>) -> EthHeaderHint<L, D> {
+        let chain_id = builder.get_chain_id();
+        EthHeaderHint {
+            chain_id,
+            _phantom: PhantomData::<L>,
+        }
+    }
+}
+
+#[async_trait]
+impl<L: PlonkParameters<D>, const D: usize> AsyncHint<L, D> for EthHeaderHint<L, D> {
+    async fn hint(
+        &self,
+        input_stream: &mut ValueStream<L, D>,
+        output_stream: &mut ValueStream<L, D>,
+    ) {
+        let block_hash = input_stream.read_value::<Bytes32Variable>();
+
+        let block: Value = get_rpc_client(self.chain_id)
+            .request("eth_getBlockByHash", json!([block_hash, false]))
+            .await
+            .expect("Failed to get block from RPC");
+        assert!(!block.is_null(), "No matching block found");
+
+        let mut header = encode_header(&block);
+        assert_eq!(
+            H256::from(keccak256(&header)),
+            block_hash,
+            "encoded header does not match the block hash"
+        );
+        assert!(
+            header.len() <= HEADER_ENCODING_LEN,
+            "header of {} bytes is too long",
+            header.len()
+        );
+        header.resize(HEADER_ENCODING_LEN, 0);
+        output_stream.write_value::<ArrayVariable<ByteVariable, HEADER_ENCODING_LEN>>(header);
+    }
+
+    fn chain_id(&self) -> Option<u64> {
+        Some(self.chain_id)
+    }
+}
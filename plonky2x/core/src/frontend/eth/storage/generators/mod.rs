@@ -1,7 +1,38 @@
 mod block;
 mod storage;
 
-pub use block::EthBlockGenerator;
+pub use block::{encode_header, EthBlockGenerator, EthHeaderHint, HEADER_ENCODING_LEN};
+use plonky2::iop::generator::WitnessGeneratorRef;
+use plonky2::plonk::circuit_data::CommonCircuitData;
 pub use storage::{
-    EthLogGenerator, EthStorageKeyGenerator, EthStorageProofGenerator, EthStorageProofHint,
+    encode_receipt, EthLogGenerator, EthReceiptHint, EthStorageKeyGenerator,
+    EthStorageMptProofHint, EthStorageProofGenerator, EthStorageProofHint, EthTransactionHint,
+    ACCOUNT_PROOF_LEN, BLOCK_TRIE_NODE_LEN, BLOCK_TRIE_PROOF_LEN, LOG_DATA_LEN,
+    RECEIPT_ENCODING_LEN, STORAGE_PROOF_ENCODING_LEN, STORAGE_PROOF_LEN, TRANSACTION_ENCODING_LEN,
 };
+
+use crate::backend::circuit::PlonkParameters;
+
+/// Returns the id of the chain queried by `generator` if it is one of the generators of this module
+/// that call an execution client, which all serialize their chain id first.
+///
+/// The generators of a circuit are type-erased, so their serialization is the only way to read it
+/// back, including for circuits that were loaded from disk.
+pub fn generator_chain_id<L: PlonkParameters<D>, const D: usize>(
+    generator: &WitnessGeneratorRef<L::Field, D>,
+    common_data: &CommonCircuitData<L::Field, D>,
+) -> Option<u64> {
+    let id = generator.0.id();
+    let rpc_generator_ids = [
+        EthBlockGenerator::<L, D>::id(),
+        EthStorageProofGenerator::<L, D>::id(),
+        EthLogGenerator::<L, D>::id(),
+    ];
+    if !rpc_generator_ids.contains(&id) {
+        return None;
+    }
+    let mut bytes = Vec::new();
+    generator.0.serialize(&mut bytes, common_data).ok()?;
+    let chain_id_bytes = bytes.get(..8)?.try_into().ok()?;
+    Some(u64::from_be_bytes(chain_id_bytes))
+}
@@ -2,9 +2,9 @@ use core::fmt::Debug;
 use core::marker::PhantomData;
 
 use async_trait::async_trait;
-use ethers::providers::Middleware;
-use ethers::types::{EIP1186ProofResponse, TransactionReceipt};
-use futures::executor;
+use ethers::types::{BlockId, Bytes, EIP1186ProofResponse, TransactionReceipt, H256, U64};
+use ethers::utils::rlp;
+use futures::future::join_all;
 use log::debug;
 use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
 use plonky2::iop::target::Target;
@@ -12,19 +12,34 @@ use plonky2::iop::witness::PartitionWitness;
 use plonky2::plonk::circuit_data::CommonCircuitData;
 use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use sha2::Digest;
 use tokio::runtime::Runtime;
 
 use crate::backend::circuit::PlonkParameters;
 use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::eth::mpt::builder::transform_proof_to_padded;
+use crate::frontend::eth::mpt::trie::trie_root_and_proof;
 use crate::frontend::eth::storage::utils::get_map_storage_location;
 use crate::frontend::eth::storage::vars::{EthLog, EthLogVariable};
 use crate::frontend::eth::utils::u256_to_h256_be;
 use crate::frontend::eth::vars::AddressVariable;
 use crate::frontend::hint::asynchronous::hint::AsyncHint;
+use crate::frontend::hint::asynchronous::rpc::{
+    max_rpc_response_size_or, RpcClient, DEFAULT_MAX_PROOF_SIZE,
+};
 use crate::frontend::uint::uint256::U256Variable;
-use crate::frontend::vars::{Bytes32Variable, CircuitVariable, ValueStream};
-use crate::utils::eth::get_provider;
+use crate::frontend::vars::{
+    ArrayVariable, ByteVariable, Bytes32Variable, CircuitVariable, U32Variable, ValueStream,
+};
+use crate::utils::eth::get_rpc_client;
+
+/// Returns the client that fetches storage proofs for `chain_id`, whose responses are limited to
+/// the size of a proof rather than to the size of a block.
+fn get_proof_client(chain_id: u64) -> RpcClient {
+    get_rpc_client(chain_id)
+        .with_max_response_size(max_rpc_response_size_or(DEFAULT_MAX_PROOF_SIZE))
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EthStorageProofHint<L: PlonkParameters<D>, const D: usize> {
@@ -53,14 +68,117 @@ impl<L: PlonkParameters<D>, const D: usize> AsyncHint<L, D> for EthStorageProofH
         let address = input_stream.read_value::<AddressVariable>();
         let location = input_stream.read_value::<Bytes32Variable>();
 
-        let provider = get_provider(self.chain_id);
-        let result = provider
-            .get_proof(address, vec![location], Some(block_hash.into()))
+        let result: EIP1186ProofResponse = get_proof_client(self.chain_id)
+            .request(
+                "eth_getProof",
+                json!([address, [location], BlockId::from(block_hash)]),
+            )
             .await
             .expect("Failed to get proof");
         let value = u256_to_h256_be(result.storage_proof[0].value);
         output_stream.write_value::<Bytes32Variable>(value);
     }
+
+    fn chain_id(&self) -> Option<u64> {
+        Some(self.chain_id)
+    }
+}
+
+/// The maximum length in bytes of a node of a storage proof.
+pub const STORAGE_PROOF_ENCODING_LEN: usize = 600;
+
+/// The maximum number of nodes of a storage proof.
+pub const STORAGE_PROOF_LEN: usize = 16;
+
+/// The maximum number of nodes of an account proof, whose nodes are at most
+/// `STORAGE_PROOF_ENCODING_LEN` bytes long like those of storage proofs.
+pub const ACCOUNT_PROOF_LEN: usize = 12;
+
+/// Input: (block_hash: bytes32, address: address, storage_key: bytes32)
+/// Output: (account_proof: bytes[ACCOUNT_PROOF_LEN], account_lengths: u32[ACCOUNT_PROOF_LEN], storage_proof: bytes[STORAGE_PROOF_LEN], storage_lengths: u32[STORAGE_PROOF_LEN])
+///
+/// Fetches the merkle patricia proofs of an account against the state root and of one of its
+/// storage slots against its storage root, padded so that they can be verified with
+/// `get_mpt_value`. The proofs of an absent account or slot end where its path leaves the trie.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EthStorageMptProofHint<L: PlonkParameters<D>, const D: usize> {
+    chain_id: u64,
+    _phantom: PhantomData<L>,
+}
+
+impl<L: PlonkParameters<D>, const D: usize> EthStorageMptProofHint<L, D> {
+    pub fn new(builder: &CircuitBuilder<L, D>) -> EthStorageMptProofHint<L, D> {
+        let chain_id = builder.get_chain_id();
+        EthStorageMptProofHint {
+            chain_id,
+            _phantom: PhantomData::<L>,
+        }
+    }
+}
+
+#[async_trait]
+impl<L: PlonkParameters<D>, const D: usize> AsyncHint<L, D> for EthStorageMptProofHint<L, D> {
+    async fn hint(
+        &self,
+        input_stream: &mut ValueStream<L, D>,
+        output_stream: &mut ValueStream<L, D>,
+    ) {
+        let block_hash = input_stream.read_value::<Bytes32Variable>();
+        let address = input_stream.read_value::<AddressVariable>();
+        let location = input_stream.read_value::<Bytes32Variable>();
+
+        let result: EIP1186ProofResponse = get_proof_client(self.chain_id)
+            .request(
+                "eth_getProof",
+                json!([address, [location], BlockId::from(block_hash)]),
+            )
+            .await
+            .expect("Failed to get proof");
+        let account_proof = result
+            .account_proof
+            .iter()
+            .map(|node| node.to_vec())
+            .collect();
+        let (account_proof, account_lengths) = transform_proof_to_padded::<
+            STORAGE_PROOF_ENCODING_LEN,
+            ACCOUNT_PROOF_LEN,
+        >(account_proof);
+        // The proof of a slot of an account that does not exist is empty.
+        let storage_proof = result
+            .storage_proof
+            .first()
+            .map(|proof| proof.proof.iter().map(|node| node.to_vec()).collect())
+            .unwrap_or_default();
+        let (storage_proof, storage_lengths) = transform_proof_to_padded::<
+            STORAGE_PROOF_ENCODING_LEN,
+            STORAGE_PROOF_LEN,
+        >(storage_proof);
+
+        output_stream.write_value::<ArrayVariable<
+            ArrayVariable<ByteVariable, STORAGE_PROOF_ENCODING_LEN>,
+            ACCOUNT_PROOF_LEN,
+        >>(account_proof);
+        output_stream.write_value::<ArrayVariable<U32Variable, ACCOUNT_PROOF_LEN>>(
+            account_lengths
+                .into_iter()
+                .map(|length| length as u32)
+                .collect(),
+        );
+        output_stream.write_value::<ArrayVariable<
+            ArrayVariable<ByteVariable, STORAGE_PROOF_ENCODING_LEN>,
+            STORAGE_PROOF_LEN,
+        >>(storage_proof);
+        output_stream.write_value::<ArrayVariable<U32Variable, STORAGE_PROOF_LEN>>(
+            storage_lengths
+                .into_iter()
+                .map(|length| length as u32)
+                .collect(),
+        );
+    }
+
+    fn chain_id(&self) -> Option<u64> {
+        Some(self.chain_id)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -120,14 +238,18 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
         let address = self.address.get(witness);
         let location = self.storage_key.get(witness);
         let block_hash = self.block_hash.get(witness);
-        let provider = get_provider(self.chain_id);
-        let result: EIP1186ProofResponse = executor::block_on(async {
+        let client = get_proof_client(self.chain_id);
+        let rt = Runtime::new().expect("failed to create tokio runtime");
+        let result: EIP1186ProofResponse = rt.block_on(async {
             debug!(
                 "querying proof {:?} {:?} {:?}",
                 address, location, block_hash
             );
-            let proof = provider
-                .get_proof(address, vec![location], Some(block_hash.into()))
+            let proof: EIP1186ProofResponse = client
+                .request(
+                    "eth_getProof",
+                    json!([address, [location], BlockId::from(block_hash)]),
+                )
                 .await
                 .expect("Failed to get proof");
             debug!("got proof {:?}", proof.storage_proof[0].value);
@@ -272,6 +394,198 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
     }
 }
 
+/// The maximum length in bytes of an encoded transaction receipt.
+pub const RECEIPT_ENCODING_LEN: usize = 2048;
+
+/// The maximum length in bytes of the data of a log whose receipt is verified.
+pub const LOG_DATA_LEN: usize = 256;
+
+/// The maximum length in bytes of an encoded transaction.
+pub const TRANSACTION_ENCODING_LEN: usize = 2048;
+
+/// The maximum length in bytes of a node of a transaction or receipt proof, whose leaf holds a
+/// whole transaction or receipt after a header of at most 40 bytes.
+pub const BLOCK_TRIE_NODE_LEN: usize = 2048 + 64;
+
+/// The maximum number of nodes of a transaction or receipt proof. The keys `rlp(index)` of blocks
+/// with fewer than 65536 transactions have at most 6 nibbles, each consumed by a branch node,
+/// before the leaf.
+pub const BLOCK_TRIE_PROOF_LEN: usize = 7;
+
+/// Returns the consensus encoding of a transaction receipt, i.e. the RLP encoding of
+/// `[status, cumulativeGasUsed, logsBloom, logs]` prefixed by the transaction type for typed
+/// transactions.
+pub fn encode_receipt(receipt: &TransactionReceipt) -> Vec<u8> {
+    let mut encoding = Vec::new();
+    if let Some(transaction_type) = receipt.transaction_type {
+        if !transaction_type.is_zero() {
+            encoding.push(transaction_type.as_u64() as u8);
+        }
+    }
+    encoding.extend_from_slice(&rlp::encode(receipt));
+    encoding
+}
+
+/// Returns the entries `(rlp(index), item)` of the transactions or receipts trie of a block.
+fn block_trie_entries(items: Vec<Vec<u8>>) -> Vec<(Vec<u8>, Vec<u8>)> {
+    items
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| (rlp::encode(&index).to_vec(), item))
+        .collect()
+}
+
+/// Input: (block_hash: bytes32, transaction_hash: bytes32)
+/// Output: (transaction_index: u32, proof: bytes[BLOCK_TRIE_PROOF_LEN], lengths: u32[BLOCK_TRIE_PROOF_LEN])
+///
+/// Fetches the index of a transaction in its block and the merkle patricia proof of its encoding
+/// in the transactions trie, which is rebuilt from the raw transactions of the block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EthTransactionHint<L: PlonkParameters<D>, const D: usize> {
+    chain_id: u64,
+    _phantom: PhantomData<L>,
+}
+
+impl<L: PlonkParameters<D>, const D: usize> EthTransactionHint<L, D> {
+    pub fn new(builder: &CircuitBuilder<L, D>) -> EthTransactionHint<L, D> {
+        let chain_id = builder.get_chain_id();
+        EthTransactionHint {
+            chain_id,
+            _phantom: PhantomData::<L>,
+        }
+    }
+}
+
+#[async_trait]
+impl<L: PlonkParameters<D>, const D: usize> AsyncHint<L, D> for EthTransactionHint<L, D> {
+    async fn hint(
+        &self,
+        input_stream: &mut ValueStream<L, D>,
+        output_stream: &mut ValueStream<L, D>,
+    ) {
+        let block_hash = input_stream.read_value::<Bytes32Variable>();
+        let transaction_hash = input_stream.read_value::<Bytes32Variable>();
+        let client = get_rpc_client(self.chain_id);
+
+        let transaction: Value = client
+            .request("eth_getTransactionByHash", json!([transaction_hash]))
+            .await
+            .expect("Failed to get transaction");
+        let in_block: Option<H256> = serde_json::from_value(transaction["blockHash"].clone())
+            .expect("invalid transaction block hash");
+        assert_eq!(
+            in_block,
+            Some(block_hash),
+            "transaction {:?} is not in block {:?}",
+            transaction_hash,
+            block_hash
+        );
+        let index: U64 = serde_json::from_value(transaction["transactionIndex"].clone())
+            .expect("invalid transaction index");
+
+        let count: U64 = client
+            .request("eth_getBlockTransactionCountByHash", json!([block_hash]))
+            .await
+            .expect("Failed to get block transaction count");
+        let transactions = join_all((0..count.as_u64()).map(|i| {
+            client.request::<Bytes>(
+                "eth_getRawTransactionByBlockHashAndIndex",
+                json!([block_hash, U64::from(i)]),
+            )
+        }))
+        .await
+        .into_iter()
+        .map(|transaction| transaction.expect("Failed to get raw transaction").to_vec())
+        .collect::<Vec<_>>();
+        let encoding_len = transactions[index.as_usize()].len();
+        assert!(
+            encoding_len <= TRANSACTION_ENCODING_LEN,
+            "transaction of {} bytes is too long",
+            encoding_len
+        );
+
+        let key = rlp::encode(&index.as_usize()).to_vec();
+        let (_, proof) = trie_root_and_proof(&block_trie_entries(transactions), &key);
+        let (proof, lengths) =
+            transform_proof_to_padded::<BLOCK_TRIE_NODE_LEN, BLOCK_TRIE_PROOF_LEN>(proof);
+
+        output_stream.write_value::<U32Variable>(index.as_u32());
+        output_stream.write_value::<ArrayVariable<
+            ArrayVariable<ByteVariable, BLOCK_TRIE_NODE_LEN>,
+            BLOCK_TRIE_PROOF_LEN,
+        >>(proof);
+        output_stream.write_value::<ArrayVariable<U32Variable, BLOCK_TRIE_PROOF_LEN>>(
+            lengths.into_iter().map(|length| length as u32).collect(),
+        );
+    }
+
+    fn chain_id(&self) -> Option<u64> {
+        Some(self.chain_id)
+    }
+}
+
+/// Input: (block_hash: bytes32, transaction_index: u32)
+/// Output: (proof: bytes[BLOCK_TRIE_PROOF_LEN], lengths: u32[BLOCK_TRIE_PROOF_LEN])
+///
+/// Fetches the merkle patricia proof of the receipt of a transaction in the receipts trie, which
+/// is rebuilt from the receipts of the block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EthReceiptHint<L: PlonkParameters<D>, const D: usize> {
+    chain_id: u64,
+    _phantom: PhantomData<L>,
+}
+
+impl<L: PlonkParameters<D>, const D: usize> EthReceiptHint<L, D> {
+    pub fn new(builder: &CircuitBuilder<L, D>) -> EthReceiptHint<L, D> {
+        let chain_id = builder.get_chain_id();
+        EthReceiptHint {
+            chain_id,
+            _phantom: PhantomData::<L>,
+        }
+    }
+}
+
+#[async_trait]
+impl<L: PlonkParameters<D>, const D: usize> AsyncHint<L, D> for EthReceiptHint<L, D> {
+    async fn hint(
+        &self,
+        input_stream: &mut ValueStream<L, D>,
+        output_stream: &mut ValueStream<L, D>,
+    ) {
+        let block_hash = input_stream.read_value::<Bytes32Variable>();
+        let index = input_stream.read_value::<U32Variable>() as usize;
+
+        let receipts: Vec<TransactionReceipt> = get_rpc_client(self.chain_id)
+            .request("eth_getBlockReceipts", json!([block_hash]))
+            .await
+            .expect("Failed to get block receipts");
+        let receipts = receipts.iter().map(encode_receipt).collect::<Vec<_>>();
+        // Leave room to read a whole log data window at the end of the receipt.
+        assert!(
+            receipts[index].len() + LOG_DATA_LEN <= RECEIPT_ENCODING_LEN,
+            "receipt of {} bytes is too long",
+            receipts[index].len()
+        );
+
+        let key = rlp::encode(&index).to_vec();
+        let (_, proof) = trie_root_and_proof(&block_trie_entries(receipts), &key);
+        let (proof, lengths) =
+            transform_proof_to_padded::<BLOCK_TRIE_NODE_LEN, BLOCK_TRIE_PROOF_LEN>(proof);
+
+        output_stream.write_value::<ArrayVariable<
+            ArrayVariable<ByteVariable, BLOCK_TRIE_NODE_LEN>,
+            BLOCK_TRIE_PROOF_LEN,
+        >>(proof);
+        output_stream.write_value::<ArrayVariable<U32Variable, BLOCK_TRIE_PROOF_LEN>>(
+            lengths.into_iter().map(|length| length as u32).collect(),
+        );
+    }
+
+    fn chain_id(&self) -> Option<u64> {
+        Some(self.chain_id)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct EthLogGenerator<L: PlonkParameters<D>, const D: usize> {
     transaction_hash: Bytes32Variable,
@@ -327,17 +641,16 @@ impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D> for Eth
         // block_hash is unused
         let _block_hash = self.block_hash.get(witness);
 
-        let provider = get_provider(self.chain_id);
+        let client = get_rpc_client(self.chain_id);
 
         let rt = Runtime::new().unwrap();
 
         let result: TransactionReceipt = rt
-            .block_on(async {
-                provider
-                    .get_transaction_receipt(transaction_hash)
-                    .await
-                    .expect("Failed to call get_transaction_receipt")
-            })
+            .block_on(client.request::<Option<TransactionReceipt>>(
+                "eth_getTransactionReceipt",
+                json!([transaction_hash]),
+            ))
+            .expect("Failed to call get_transaction_receipt")
             .expect("No transaction receipt found");
 
         let log = &result.logs[self.log_index as usize];
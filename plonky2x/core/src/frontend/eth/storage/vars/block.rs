@@ -34,3 +34,13 @@ pub struct EthHeaderVariable {
     pub time: U256Variable,
     // pub extra: Bytes32Variable, // TODO: add back once we have arbitrary bytes variables
 }
+
+/// The roots of the tries committed to by a block header, as returned by `eth_get_header_roots`.
+#[derive(Debug, Clone, Copy, CircuitVariable)]
+#[value_name(EthHeaderRoots)]
+#[value_derive(PartialEq, Eq)]
+pub struct EthHeaderRootsVariable {
+    pub state_root: Bytes32Variable,
+    pub transactions_root: Bytes32Variable,
+    pub receipts_root: Bytes32Variable,
+}
@@ -5,9 +5,10 @@ use plonky2x_derive::CircuitVariable;
 
 use crate::backend::circuit::PlonkParameters;
 use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::eth::storage::generators::TRANSACTION_ENCODING_LEN;
 use crate::frontend::eth::vars::AddressVariable;
 use crate::frontend::vars::{Bytes32Variable, CircuitVariable, U256Variable};
-use crate::prelude::{ArrayVariable, Variable};
+use crate::prelude::{ArrayVariable, ByteVariable, U32Variable, Variable};
 
 #[derive(Debug, Clone, Copy, CircuitVariable)]
 #[value_name(EthProof)]
@@ -32,3 +33,18 @@ pub struct EthLogVariable {
     pub topics: ArrayVariable<Bytes32Variable, 3>,
     pub data_hash: Bytes32Variable,
 }
+
+/// A transaction proven against the transactions root of its block, as returned by
+/// `eth_get_transaction`.
+#[derive(Debug, Clone, CircuitVariable)]
+#[value_name(EthTransaction)]
+pub struct EthTransactionVariable {
+    /// The hash of the transaction, which commits to `encoding`.
+    pub hash: Bytes32Variable,
+    /// The index of the transaction in its block.
+    pub index: U32Variable,
+    /// The consensus encoding of the transaction, padded with zeros.
+    pub encoding: ArrayVariable<ByteVariable, TRANSACTION_ENCODING_LEN>,
+    /// The length of the encoding in bytes.
+    pub len: Variable,
+}
@@ -5,6 +5,7 @@ use plonky2::hash::hash_types::RichField;
 
 use crate::backend::circuit::PlonkParameters;
 use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::ops::Zero;
 use crate::frontend::vars::{
     ByteVariable, BytesVariable, CircuitVariable, EvmVariable, SSZVariable,
 };
@@ -126,3 +127,9 @@ impl SSZVariable for AddressVariable {
         Bytes32Variable(BytesVariable::<32>(bytes.try_into().unwrap()))
     }
 }
+
+impl<L: PlonkParameters<D>, const D: usize> Zero<L, D> for AddressVariable {
+    fn zero(builder: &mut CircuitBuilder<L, D>) -> Self {
+        Self(BytesVariable::zero(builder))
+    }
+}
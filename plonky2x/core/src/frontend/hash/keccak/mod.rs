@@ -8,6 +8,7 @@ use crate::frontend::vars::Bytes32Variable;
 use crate::prelude::{ByteVariable, CircuitBuilder, Variable};
 
 pub mod keccak256;
+pub mod variable;
 
 impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
     /// WARNING: DO NOT USE IN PRODUCTION, this is unconstrained!
@@ -67,4 +68,22 @@ mod tests {
         let input = circuit.input();
         let (_, _) = circuit.prove(&input);
     }
+
+    #[test]
+    fn test_keccak256_empty() {
+        env_logger::try_init().unwrap_or_default();
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let hash = builder.keccak256_witness(&[]);
+        builder.write(hash);
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        assert_eq!(
+            output.read::<Bytes32Variable>(),
+            bytes32!("0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+        );
+    }
 }
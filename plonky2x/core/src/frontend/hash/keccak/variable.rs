@@ -0,0 +1,305 @@
+//! A constrained implementation of keccak256 for inputs whose length is a circuit variable.
+//! Reference: https://keccak.team/keccak_specs_summary.html
+
+use crate::prelude::*;
+
+/// The number of bytes absorbed per keccak256 block.
+pub const KECCAK256_RATE_BYTES: usize = 136;
+
+const KECCAK_ROUNDS: usize = 24;
+
+const LANE_BITS: usize = 64;
+
+const ROUND_CONSTANTS: [u64; KECCAK_ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// The rotation offsets indexed by `[x][y]`.
+const ROTATION_OFFSETS: [[usize; 5]; 5] = [
+    [0, 36, 3, 41, 18],
+    [1, 44, 10, 45, 2],
+    [62, 6, 43, 15, 61],
+    [28, 55, 25, 21, 56],
+    [27, 20, 39, 8, 14],
+];
+
+/// A 64-bit lane of the keccak state, where index `i` holds bit `i` of the lane.
+type Lane = [BoolVariable; LANE_BITS];
+
+fn rotate_lane(lane: Lane, bits: usize) -> Lane {
+    let mut res = lane;
+    for i in 0..LANE_BITS {
+        res[i] = lane[(i + LANE_BITS - bits) % LANE_BITS];
+    }
+    res
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    fn xor_lanes(&mut self, a: Lane, b: Lane) -> Lane {
+        let mut res = a;
+        for i in 0..LANE_BITS {
+            res[i] = self.xor(a[i], b[i]);
+        }
+        res
+    }
+
+    /// The keccak-f[1600] permutation, where lane `(x, y)` of the state is at index `x + 5 * y`.
+    fn keccak_f(&mut self, state: [Lane; 25]) -> [Lane; 25] {
+        let mut a = state;
+        for round_constant in ROUND_CONSTANTS.iter() {
+            // θ step.
+            let mut c = [a[0]; 5];
+            for x in 0..5 {
+                for i in 0..LANE_BITS {
+                    let xor = self.xor3(
+                        a[x][i].variable,
+                        a[x + 5][i].variable,
+                        a[x + 10][i].variable,
+                    );
+                    c[x][i] = self.xor3(xor.variable, a[x + 15][i].variable, a[x + 20][i].variable);
+                }
+            }
+            for x in 0..5 {
+                let d = rotate_lane(c[(x + 1) % 5], 1);
+                let d = self.xor_lanes(c[(x + 4) % 5], d);
+                for y in 0..5 {
+                    a[x + 5 * y] = self.xor_lanes(a[x + 5 * y], d);
+                }
+            }
+
+            // ρ and π steps.
+            let mut b = a;
+            for x in 0..5 {
+                for y in 0..5 {
+                    b[y + 5 * ((2 * x + 3 * y) % 5)] =
+                        rotate_lane(a[x + 5 * y], ROTATION_OFFSETS[x][y]);
+                }
+            }
+
+            // χ step.
+            for x in 0..5 {
+                for y in 0..5 {
+                    for i in 0..LANE_BITS {
+                        let not_b = self.not(b[(x + 1) % 5 + 5 * y][i]);
+                        let and_b = self.and(not_b, b[(x + 2) % 5 + 5 * y][i]);
+                        a[x + 5 * y][i] = self.xor(b[x + 5 * y][i], and_b);
+                    }
+                }
+            }
+
+            // ι step. Xor with a constant bit is either the identity or a negation.
+            for i in 0..LANE_BITS {
+                if (round_constant >> i) & 1 == 1 {
+                    a[0][i] = self.not(a[0][i]);
+                }
+            }
+        }
+        a
+    }
+
+    /// Pad the given variable length input according to the keccak256 spec (pad10*1 with the
+    /// 0x01 domain byte). The returned bytes fill `input.len() / 136 + 1` blocks, and only the
+    /// blocks up to the one containing the runtime length are relevant.
+    fn pad_keccak256_variable_length(
+        &mut self,
+        input: &[ByteVariable],
+        length: U32Variable,
+        last_block: U32Variable,
+    ) -> Vec<ByteVariable> {
+        let max_num_blocks = input.len() / KECCAK256_RATE_BYTES + 1;
+        let zero_byte = self.constant::<ByteVariable>(0x00);
+        let padding_start_byte = self.constant::<ByteVariable>(0x01);
+        let true_t = self._true();
+        let false_t = self._false();
+
+        let mut padded_bytes = Vec::new();
+        let mut message_byte_selector = true_t;
+        for i in 0..max_num_blocks {
+            let curr_block = self.constant::<U32Variable>(i as u32);
+            let is_last_block = self.is_equal(curr_block, last_block);
+
+            for j in 0..KECCAK256_RATE_BYTES {
+                let idx = i * KECCAK256_RATE_BYTES + j;
+                let idx_t = self.constant::<U32Variable>(idx as u32);
+                let is_last_msg_byte = self.is_equal(idx_t, length);
+                let not_last_msg_byte = self.not(is_last_msg_byte);
+                message_byte_selector = self.select(
+                    message_byte_selector,
+                    not_last_msg_byte,
+                    message_byte_selector,
+                );
+
+                let msg_byte = input.get(idx).copied().unwrap_or(zero_byte);
+                let mut byte = self.select(message_byte_selector, msg_byte, zero_byte);
+                byte = self.select(is_last_msg_byte, padding_start_byte, byte);
+
+                if j == KECCAK256_RATE_BYTES - 1 {
+                    // The final padding bit is the most significant bit of the block's last byte.
+                    let mut end_byte = byte;
+                    end_byte.0[0] = true_t;
+                    byte = self.select(is_last_block, end_byte, byte);
+                }
+                padded_bytes.push(byte);
+            }
+        }
+        self.assert_is_equal(message_byte_selector, false_t);
+
+        padded_bytes
+    }
+
+    /// Computes the keccak256 hash of the first `length` bytes of `input`, where `length` is a
+    /// circuit variable. Bytes of `input` beyond `length` do not affect the result.
+    pub fn keccak256_var(
+        &mut self,
+        input: &[ByteVariable],
+        length: U32Variable,
+    ) -> Bytes32Variable {
+        // Check that length <= input.len(). This is needed to ensure that users cannot prove the
+        // hash of a longer message than they supplied.
+        let true_v = self._true();
+        let supplied_input_length = self.constant::<U32Variable>(input.len() as u32);
+        let is_length_valid = self.lte(length, supplied_input_length);
+        self.assert_is_equal(is_length_valid, true_v);
+
+        let rate = self.constant::<U32Variable>(KECCAK256_RATE_BYTES as u32);
+        let last_block = self.div(length, rate);
+        let padded = self.pad_keccak256_variable_length(input, length, last_block);
+
+        let false_t = self._false();
+        let mut state = [[false_t; LANE_BITS]; 25];
+        let mut digest = [[false_t; LANE_BITS]; 4];
+        for (i, block) in padded.chunks_exact(KECCAK256_RATE_BYTES).enumerate() {
+            for (lane, lane_bytes) in block.chunks_exact(LANE_BITS / 8).enumerate() {
+                let mut lane_bits = [false_t; LANE_BITS];
+                for (k, byte) in lane_bytes.iter().enumerate() {
+                    let be_bits = byte.as_be_bits();
+                    for bit in 0..8 {
+                        lane_bits[8 * k + bit] = be_bits[7 - bit];
+                    }
+                }
+                state[lane] = self.xor_lanes(state[lane], lane_bits);
+            }
+            state = self.keccak_f(state);
+
+            let curr_block = self.constant::<U32Variable>(i as u32);
+            let is_last_block = self.is_equal(curr_block, last_block);
+            for lane in 0..4 {
+                for bit in 0..LANE_BITS {
+                    digest[lane][bit] =
+                        self.select(is_last_block, state[lane][bit], digest[lane][bit]);
+                }
+            }
+        }
+
+        let digest_bytes = digest
+            .iter()
+            .flat_map(|lane| {
+                lane.chunks_exact(8).map(|bits| {
+                    let mut be_bits = [false_t; 8];
+                    for bit in 0..8 {
+                        be_bits[7 - bit] = bits[bit];
+                    }
+                    ByteVariable(be_bits)
+                })
+            })
+            .collect::<Vec<_>>();
+        Bytes32Variable(BytesVariable(digest_bytes.try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::H256;
+    use ethers::utils::keccak256;
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::utils::bytes32;
+
+    type L = DefaultParameters;
+    const D: usize = 2;
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_keccak256_var() {
+        env_logger::try_init().unwrap_or_default();
+
+        let mut rng = thread_rng();
+        let data: Vec<u8> = (0..200).map(|_| rng.gen()).collect();
+        let lengths = [100u32, 150];
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let input = builder.read::<ArrayVariable<ByteVariable, 200>>();
+        for _ in lengths.iter() {
+            let length = builder.read::<U32Variable>();
+            let hash = builder.keccak256_var(input.as_slice(), length);
+            builder.write(hash);
+        }
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<ByteVariable, 200>>(data.clone());
+        for length in lengths.iter() {
+            input.write::<U32Variable>(*length);
+        }
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        for length in lengths.iter() {
+            let hash = output.read::<Bytes32Variable>();
+            assert_eq!(hash, H256::from(keccak256(&data[..*length as usize])));
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_keccak256_var_empty() {
+        env_logger::try_init().unwrap_or_default();
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let length = builder.read::<U32Variable>();
+        let input = builder.read::<ArrayVariable<ByteVariable, 136>>();
+        let empty_hash = builder.keccak256_var(&[], length);
+        let prefix_hash = builder.keccak256_var(input.as_slice(), length);
+        builder.write(empty_hash);
+        builder.write(prefix_hash);
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.write::<U32Variable>(0);
+        input.write::<ArrayVariable<ByteVariable, 136>>(vec![1u8; 136]);
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        let expected = H256::from(keccak256([]));
+        assert_eq!(
+            expected,
+            bytes32!("0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+        );
+        assert_eq!(output.read::<Bytes32Variable>(), expected);
+        assert_eq!(output.read::<Bytes32Variable>(), expected);
+    }
+}
@@ -0,0 +1,358 @@
+//! The Poseidon hash over the BN254 scalar field, compatible with circomlib's `Poseidon` template.
+//!
+//! The round constants and MDS matrix are derived with the Grain LFSR described in the Poseidon
+//! paper (https://eprint.iacr.org/2019/458.pdf), which is how the reference implementation
+//! generates the parameters used by circomlib. Since the BN254 scalar field is not the circuit's
+//! native field, all arithmetic is done with non-native big integer gadgets.
+
+use std::collections::VecDeque;
+
+use num::{BigUint, Num, Zero};
+
+use crate::frontend::uint::num::biguint::{BigUintTarget, CircuitBuilderBiguint};
+use crate::frontend::uint::num::u32::gadgets::arithmetic_u32::U32Target;
+use crate::prelude::*;
+
+/// The modulus of the BN254 scalar field.
+pub const BN254_SCALAR_MODULUS: &str =
+    "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001";
+
+/// The number of bits of an element of the BN254 scalar field.
+const FIELD_BITS: usize = 254;
+
+/// The number of full rounds, split evenly before and after the partial rounds.
+const ROUNDS_FULL: usize = 8;
+
+/// The number of partial rounds, indexed by `t - 2` where `t` is the width of the state.
+const ROUNDS_PARTIAL: [usize; 16] = [
+    56, 57, 56, 60, 60, 63, 64, 63, 60, 66, 60, 65, 70, 60, 64, 68,
+];
+
+/// The maximum number of inputs supported by a single Poseidon permutation.
+pub const POSEIDON_BN254_MAX_INPUTS: usize = ROUNDS_PARTIAL.len();
+
+/// The Grain LFSR used to generate the Poseidon parameters.
+struct Grain {
+    state: VecDeque<bool>,
+}
+
+impl Grain {
+    fn new(width: usize, rounds_full: usize, rounds_partial: usize) -> Self {
+        let mut state = VecDeque::with_capacity(80);
+        // field = 1 (prime field), sbox = 0 (x^alpha), n, t, R_F, R_P, followed by ones.
+        for (value, bits) in [
+            (1, 2),
+            (0, 4),
+            (FIELD_BITS, 12),
+            (width, 12),
+            (rounds_full, 10),
+            (rounds_partial, 10),
+        ] {
+            for i in (0..bits).rev() {
+                state.push_back((value >> i) & 1 == 1);
+            }
+        }
+        state.extend([true; 30]);
+
+        let mut grain = Self { state };
+        for _ in 0..160 {
+            grain.step();
+        }
+        grain
+    }
+
+    fn step(&mut self) -> bool {
+        let bit = [62, 51, 38, 23, 13, 0]
+            .iter()
+            .fold(false, |acc, &i| acc ^ self.state[i]);
+        self.state.pop_front();
+        self.state.push_back(bit);
+        bit
+    }
+
+    /// Bits are produced in pairs, and the second bit is kept only if the first one is set.
+    fn next_bit(&mut self) -> bool {
+        loop {
+            let keep = self.step();
+            let bit = self.step();
+            if keep {
+                return bit;
+            }
+        }
+    }
+
+    /// Reads a big-endian integer of `FIELD_BITS` bits.
+    fn next_integer(&mut self) -> BigUint {
+        let mut value = BigUint::zero();
+        for _ in 0..FIELD_BITS {
+            value <<= 1;
+            if self.next_bit() {
+                value += 1u32;
+            }
+        }
+        value
+    }
+}
+
+/// The parameters of the Poseidon permutation over BN254 for a given state width.
+#[derive(Debug, Clone)]
+pub struct PoseidonBn254Params {
+    pub width: usize,
+    pub rounds_full: usize,
+    pub rounds_partial: usize,
+    /// The round constants, `width` per round.
+    pub round_constants: Vec<BigUint>,
+    /// The MDS matrix, indexed by `[row][column]`.
+    pub mds: Vec<Vec<BigUint>>,
+}
+
+impl PoseidonBn254Params {
+    /// Generates the parameters for hashing `num_inputs` field elements.
+    pub fn new(num_inputs: usize) -> Self {
+        assert!(
+            (1..=POSEIDON_BN254_MAX_INPUTS).contains(&num_inputs),
+            "poseidon_bn254 supports between 1 and {} inputs",
+            POSEIDON_BN254_MAX_INPUTS
+        );
+        let modulus = bn254_scalar_modulus();
+        let width = num_inputs + 1;
+        let rounds_full = ROUNDS_FULL;
+        let rounds_partial = ROUNDS_PARTIAL[width - 2];
+        let mut grain = Grain::new(width, rounds_full, rounds_partial);
+
+        let round_constants = (0..(rounds_full + rounds_partial) * width)
+            .map(|_| loop {
+                let value = grain.next_integer();
+                if value < modulus {
+                    break value;
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // The MDS matrix is the Cauchy matrix M[i][j] = 1 / (x_i + y_j), where the x_i and y_j are
+        // sampled until they are all distinct and no x_i + y_j is zero.
+        let mds = loop {
+            let elements = loop {
+                let elements = (0..2 * width)
+                    .map(|_| grain.next_integer() % &modulus)
+                    .collect::<Vec<_>>();
+                let all_distinct = elements
+                    .iter()
+                    .enumerate()
+                    .all(|(i, a)| elements[i + 1..].iter().all(|b| a != b));
+                if all_distinct {
+                    break elements;
+                }
+            };
+            let (xs, ys) = elements.split_at(width);
+            let sums = xs
+                .iter()
+                .map(|x| ys.iter().map(|y| (x + y) % &modulus).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            if sums.iter().flatten().any(|sum| sum.is_zero()) {
+                continue;
+            }
+            let exponent = &modulus - 2u32;
+            break sums
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|sum| sum.modpow(&exponent, &modulus))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+        };
+
+        Self {
+            width,
+            rounds_full,
+            rounds_partial,
+            round_constants,
+            mds,
+        }
+    }
+
+    fn is_full_round(&self, round: usize) -> bool {
+        round < self.rounds_full / 2 || round >= self.rounds_full / 2 + self.rounds_partial
+    }
+}
+
+/// Returns the modulus of the BN254 scalar field.
+pub fn bn254_scalar_modulus() -> BigUint {
+    BigUint::from_str_radix(BN254_SCALAR_MODULUS, 16).unwrap()
+}
+
+/// Computes the Poseidon hash of the given BN254 scalar field elements outside of the circuit.
+pub fn poseidon_bn254(inputs: &[BigUint]) -> BigUint {
+    let params = PoseidonBn254Params::new(inputs.len());
+    let modulus = bn254_scalar_modulus();
+    let five = BigUint::from(5u32);
+
+    let mut state = vec![BigUint::zero()];
+    state.extend(inputs.iter().map(|x| x % &modulus));
+    for round in 0..params.rounds_full + params.rounds_partial {
+        for (i, x) in state.iter_mut().enumerate() {
+            *x = (&*x + &params.round_constants[round * params.width + i]) % &modulus;
+        }
+        let num_sboxes = if params.is_full_round(round) {
+            params.width
+        } else {
+            1
+        };
+        for x in state.iter_mut().take(num_sboxes) {
+            *x = x.modpow(&five, &modulus);
+        }
+        state = params
+            .mds
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(state.iter())
+                    .fold(BigUint::zero(), |acc, (m, x)| acc + m * x)
+                    % &modulus
+            })
+            .collect();
+    }
+    state.swap_remove(0)
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    fn poseidon_bn254_sbox(&mut self, x: &BigUintTarget, modulus: &BigUintTarget) -> BigUintTarget {
+        let x2 = self.api.mul_biguint(x, x);
+        let x2 = self.api.rem_biguint(&x2, modulus);
+        let x4 = self.api.mul_biguint(&x2, &x2);
+        let x4 = self.api.rem_biguint(&x4, modulus);
+        let x5 = self.api.mul_biguint(&x4, x);
+        self.api.rem_biguint(&x5, modulus)
+    }
+
+    /// Computes the circomlib-compatible Poseidon hash of the given BN254 scalar field elements.
+    /// Inputs are reduced modulo the BN254 scalar field and the output is a canonical element.
+    pub fn poseidon_bn254(&mut self, inputs: &[U256Variable]) -> U256Variable {
+        let params = PoseidonBn254Params::new(inputs.len());
+        let modulus = self.api.constant_biguint(&bn254_scalar_modulus());
+
+        let mut state = vec![self.api.zero_biguint()];
+        for input in inputs.iter() {
+            let input = BigUintTarget {
+                limbs: input.limbs.iter().map(|x| U32Target::from(*x)).collect(),
+            };
+            state.push(self.api.rem_biguint(&input, &modulus));
+        }
+
+        let round_constants = params
+            .round_constants
+            .iter()
+            .map(|c| self.api.constant_biguint(c))
+            .collect::<Vec<_>>();
+        let mds = params
+            .mds
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|m| self.api.constant_biguint(m))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        for round in 0..params.rounds_full + params.rounds_partial {
+            for (i, x) in state.iter_mut().enumerate() {
+                let sum = self
+                    .api
+                    .add_biguint(x, &round_constants[round * params.width + i]);
+                *x = self.api.rem_biguint(&sum, &modulus);
+            }
+
+            let num_sboxes = if params.is_full_round(round) {
+                params.width
+            } else {
+                1
+            };
+            for x in state.iter_mut().take(num_sboxes) {
+                *x = self.poseidon_bn254_sbox(x, &modulus);
+            }
+
+            let mut next_state = Vec::with_capacity(params.width);
+            for row in mds.iter() {
+                let mut acc = self.api.zero_biguint();
+                for (m, x) in row.iter().zip(state.iter()) {
+                    let product = self.api.mul_biguint(m, x);
+                    acc = self.api.add_biguint(&acc, &product);
+                }
+                next_state.push(self.api.rem_biguint(&acc, &modulus));
+            }
+            state = next_state;
+        }
+
+        let mut limbs = [self.zero::<U32Variable>(); 8];
+        for (limb, target) in limbs.iter_mut().zip(state[0].limbs.iter()) {
+            *limb = (*target).into();
+        }
+        U256Variable { limbs }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::U256;
+
+    use super::*;
+
+    type L = DefaultParameters;
+    const D: usize = 2;
+
+    #[test]
+    fn test_poseidon_bn254_native() {
+        // Reference outputs of circomlibjs's `poseidon`.
+        let expected = [
+            (
+                vec![1u32],
+                "29176100eaa962bdc1fe6c654d6a3c130e96a4d1168b33848b897dc502820133",
+            ),
+            (
+                vec![1, 2],
+                "115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a",
+            ),
+            (
+                vec![1, 2, 3, 4],
+                "299c867db6c1fdd79dcefa40e4510b9837e60ebb1ce0663dbaa525df65250465",
+            ),
+        ];
+        for (inputs, hash) in expected {
+            let inputs = inputs.into_iter().map(BigUint::from).collect::<Vec<_>>();
+            assert_eq!(
+                poseidon_bn254(&inputs),
+                BigUint::from_str_radix(hash, 16).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_poseidon_bn254() {
+        env_logger::try_init().unwrap_or_default();
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let a = builder.read::<U256Variable>();
+        let b = builder.read::<U256Variable>();
+        let hash = builder.poseidon_bn254(&[a, b]);
+        builder.write(hash);
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.write::<U256Variable>(U256::from(1));
+        input.write::<U256Variable>(U256::from(2));
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        let hash = output.read::<U256Variable>();
+        assert_eq!(
+            hash,
+            U256::from_str_radix(
+                "115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a",
+                16
+            )
+            .unwrap()
+        );
+    }
+}
@@ -1,3 +1,4 @@
 //! An implementation of the Poseidon hash functions in a plonky2 circuit
 
+pub mod bn254;
 pub mod poseidon256;
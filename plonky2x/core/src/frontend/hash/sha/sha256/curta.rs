@@ -438,7 +438,8 @@ mod tests {
     #[test]
     #[cfg_attr(feature = "ci", ignore)]
     fn test_sha256_diff_sizes() {
-        // Confirm that Curta SHA256 works for all sizes from 0 to 256 bytes.
+        // Confirm that Curta SHA256 works for all sizes from 1 to 255 bytes. The empty message is
+        // checked against its known digest in `test_sha256_curta_empty`.
         let _ = env_logger::builder().is_test(true).try_init();
 
         let mut builder = CircuitBuilder::<L, D>::new();
@@ -467,4 +468,32 @@ mod tests {
         let (proof, output) = circuit.prove(&input);
         circuit.verify(&proof, &input, &output);
     }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_sha256_curta_empty() {
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let zero = builder.constant::<U32Variable>(0);
+        let expected_digest = builder.constant::<Bytes32Variable>(bytes32!(
+            "0xe3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        ));
+
+        // The empty message as a fixed input, as an empty variable input and as the empty prefix
+        // of a longer variable input.
+        let fixed_result = builder.curta_sha256(&[]);
+        let empty_variable_result = builder.curta_sha256_variable(&[], zero);
+        let msg = builder.constant::<BytesVariable<64>>([1u8; 64]);
+        let variable_result = builder.curta_sha256_variable(&msg.0, zero);
+
+        builder.assert_is_equal(fixed_result, expected_digest);
+        builder.assert_is_equal(empty_variable_result, expected_digest);
+        builder.assert_is_equal(variable_result, expected_digest);
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
 }
@@ -160,6 +160,7 @@ mod tests {
 
     use super::*;
     use crate::prelude::{DefaultParameters, U32Variable};
+    use crate::utils::bytes32;
     use crate::utils::hash::sha256;
 
     type L = DefaultParameters;
@@ -282,4 +283,20 @@ mod tests {
             circuit.test_default_serializers();
         }
     }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_sha256_empty() {
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let digest = builder.sha256(&[]);
+        let expected_digest = builder.constant::<Bytes32Variable>(bytes32!(
+            "0xe3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        ));
+        builder.assert_is_equal(digest, expected_digest);
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
 }
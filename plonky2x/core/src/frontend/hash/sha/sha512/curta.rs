@@ -274,6 +274,14 @@ mod tests {
         test_sha512_variable_length(&msg, 0, expected_digest);
     }
 
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_sha512_curta_variable_empty() {
+        let expected_digest = bytes!("cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e");
+
+        test_sha512_variable_length(&[], 0, expected_digest);
+    }
+
     // FAILED
     #[test]
     #[cfg_attr(feature = "ci", ignore)]
@@ -416,7 +424,9 @@ mod tests {
     #[test]
     #[cfg_attr(feature = "ci", ignore)]
     fn test_sha512_diff_sizes() {
-        // Confirm that Curta SHA512 works for all sizes from 0 to 256 bytes.
+        // Confirm that Curta SHA512 works for all sizes from 1 to 255 bytes. The empty message is
+        // checked against its known digest in `test_sha512_curta_empty` and
+        // `test_sha512_curta_variable_empty`.
         let _ = env_logger::builder().is_test(true).try_init();
 
         let mut builder = DefaultBuilder::new();
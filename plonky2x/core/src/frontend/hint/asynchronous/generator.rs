@@ -29,6 +29,8 @@ pub enum HintPoll {
 
 pub trait AsyncGeneratorData<L: PlonkParameters<D>, const D: usize>: HintGenerator<L, D> {
     fn generator(&self, tx: UnboundedSender<HintInMessage<L, D>>) -> AsyncHintRef<L, D>;
+
+    fn chain_id(&self) -> Option<u64>;
 }
 
 pub trait AsyncHintRunner<L: PlonkParameters<D>, const D: usize>:
@@ -65,6 +67,11 @@ impl<L: PlonkParameters<D>, const D: usize> AsyncHintDataRef<L, D> {
     pub(crate) fn new<H: AsyncHint<L, D>>(generator_data: AsyncHintData<L, H, D>) -> Self {
         Self(Box::new(generator_data))
     }
+
+    /// The id of the chain the underlying hint fetches data from, if any.
+    pub fn chain_id(&self) -> Option<u64> {
+        self.0.chain_id()
+    }
 }
 
 /// The wintess generator for asynchronous hints.
@@ -104,6 +111,10 @@ impl<L: PlonkParameters<D>, H: AsyncHint<L, D>, const D: usize> AsyncGeneratorDa
     fn generator(&self, tx: UnboundedSender<HintInMessage<L, D>>) -> AsyncHintRef<L, D> {
         AsyncHintRef::new(self.generator(tx))
     }
+
+    fn chain_id(&self) -> Option<u64> {
+        self.hint.chain_id()
+    }
 }
 
 impl<L: PlonkParameters<D>, H: AsyncHint<L, D>, const D: usize> AsyncHintData<L, H, D> {
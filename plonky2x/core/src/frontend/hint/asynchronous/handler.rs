@@ -3,6 +3,7 @@ use tokio::sync::mpsc::UnboundedReceiver;
 use tokio::task::JoinSet;
 
 use super::channel::HintInMessage;
+use super::recording::{record_response, HintRecorder};
 use crate::prelude::PlonkParameters;
 
 /// A handler for asynchronous hints.
@@ -12,11 +13,18 @@ use crate::prelude::PlonkParameters;
 #[derive(Debug)]
 pub struct HintHandler<L: PlonkParameters<D>, const D: usize> {
     rx: UnboundedReceiver<HintInMessage<L, D>>,
+    recorder: Option<HintRecorder>,
 }
 
 impl<L: PlonkParameters<D>, const D: usize> HintHandler<L, D> {
     pub fn new(rx: UnboundedReceiver<HintInMessage<L, D>>) -> Self {
-        Self { rx }
+        Self { rx, recorder: None }
+    }
+
+    /// Records the responses of the hints, or replays them without running the hints.
+    pub fn with_recorder(mut self, recorder: Option<HintRecorder>) -> Self {
+        self.recorder = recorder;
+        self
     }
 
     /// Run the handler.
@@ -31,10 +39,24 @@ impl<L: PlonkParameters<D>, const D: usize> HintHandler<L, D> {
                 Some(message) = self.rx.recv() => {
                     let HintInMessage { hint, tx, inputs } = message;
 
-                    set.spawn(async move {
-                        let outputs = hint.hint_fn(inputs).await;
-                        tx.send(outputs)
-                    });
+                    match self.recorder.clone() {
+                        Some(HintRecorder::Replay(recording)) => {
+                            tx.send(recording.response(&hint.id(), &inputs)?)?;
+                        }
+                        Some(HintRecorder::Record(recording)) => {
+                            set.spawn(async move {
+                                let outputs = hint.hint_fn(inputs.clone()).await;
+                                record_response(&recording, hint.id(), &inputs, &outputs);
+                                tx.send(outputs)
+                            });
+                        }
+                        None => {
+                            set.spawn(async move {
+                                let outputs = hint.hint_fn(inputs).await;
+                                tx.send(outputs)
+                            });
+                        }
+                    }
                 }
                 Some(result) = set.join_next() => {
                     result??;
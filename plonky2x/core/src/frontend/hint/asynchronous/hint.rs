@@ -67,6 +67,14 @@ pub trait AsyncHint<L: PlonkParameters<D>, const D: usize>:
     fn id() -> String {
         std::any::type_name::<Self>().to_string()
     }
+
+    /// The id of the chain this hint fetches data from, if any.
+    ///
+    /// Hints that query an execution client should override this so that the circuit can report
+    /// which chains it needs an RPC for.
+    fn chain_id(&self) -> Option<u64> {
+        None
+    }
 }
 
 /// A version of `AsyncHint` that that is [object safe][1] and can be used as a trait object.
@@ -76,6 +84,9 @@ pub trait AsyncHint<L: PlonkParameters<D>, const D: usize>:
 pub(crate) trait AnyAsyncHint<L: PlonkParameters<D>, const D: usize>:
     'static + Debug + Send + Send + Sync
 {
+    /// The id of the underlying hint.
+    fn id(&self) -> String;
+
     #[allow(unused_variables)]
     async fn hint_fn(&self, input_stream: ValueStream<L, D>) -> ValueStream<L, D> {
         unimplemented!("Implement this method")
@@ -88,6 +99,10 @@ pub struct AnyHint<H>(pub H);
 
 #[async_trait]
 impl<L: PlonkParameters<D>, H: AsyncHint<L, D>, const D: usize> AnyAsyncHint<L, D> for AnyHint<H> {
+    fn id(&self) -> String {
+        H::id()
+    }
+
     async fn hint_fn(&self, input_stream: ValueStream<L, D>) -> ValueStream<L, D> {
         self.0.hint_fn(input_stream).await
     }
@@ -0,0 +1,220 @@
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use plonky2::field::types::{Field, PrimeField64};
+use plonky2::iop::generator::{GeneratedValues, WitnessGeneratorRef};
+use plonky2::iop::target::Target;
+use plonky2::iop::wire::Wire;
+use plonky2::iop::witness::{PartitionWitness, Witness};
+use serde::{Deserialize, Serialize};
+
+use crate::frontend::vars::ValueStream;
+use crate::prelude::PlonkParameters;
+
+/// The response of an asynchronous hint to a request, as canonical field elements.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct HintResponse {
+    pub hint_id: String,
+    pub inputs: Vec<u64>,
+    pub outputs: Vec<u64>,
+}
+
+/// A target set by a recorded generator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum RecordedTarget {
+    Wire { row: usize, column: usize },
+    Virtual { index: usize },
+}
+
+impl From<Target> for RecordedTarget {
+    fn from(target: Target) -> Self {
+        match target {
+            Target::Wire(Wire { row, column }) => Self::Wire { row, column },
+            Target::VirtualTarget { index } => Self::Virtual { index },
+        }
+    }
+}
+
+impl From<RecordedTarget> for Target {
+    fn from(target: RecordedTarget) -> Self {
+        match target {
+            RecordedTarget::Wire { row, column } => Target::wire(row, column),
+            RecordedTarget::Virtual { index } => Target::VirtualTarget { index },
+        }
+    }
+}
+
+/// The values set by a synchronous generator that fetches external data, given the values of the
+/// targets it watches.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct GeneratorResponse {
+    pub generator_id: String,
+    pub inputs: Vec<u64>,
+    pub outputs: Vec<(RecordedTarget, u64)>,
+}
+
+/// The responses of all the hints and generators that fetch external data, such as storage
+/// proofs, receipts and beacon states, while generating a witness. A recording is enough to
+/// regenerate the witness offline.
+///
+/// Asynchronous hints are recorded by their input and output streams. The synchronous generators
+/// that fetch data, listed in the `FetchingGeneratorRegistry`, are recorded by the targets they
+/// set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HintRecording {
+    pub responses: Vec<HintResponse>,
+    /// Recordings saved before synchronous generators were recorded do not have this field.
+    #[serde(default)]
+    pub generator_responses: Vec<GeneratorResponse>,
+}
+
+impl HintRecording {
+    /// Saves the recording as JSON, sorted so that recordings of the same witness are identical.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut recording = self.clone();
+        recording.responses.sort();
+        recording.responses.dedup();
+        recording.generator_responses.sort();
+        recording.generator_responses.dedup();
+        if let Some(parent) = path.as_ref().parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(&recording)?)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Returns the recorded response of the hint with id `hint_id` to `inputs`.
+    pub fn response<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        hint_id: &str,
+        inputs: &ValueStream<L, D>,
+    ) -> Result<ValueStream<L, D>> {
+        let inputs = stream_to_u64s(inputs);
+        self.responses
+            .iter()
+            .find(|response| response.hint_id == hint_id && response.inputs == inputs)
+            .map(|response| {
+                ValueStream::from_values(
+                    response
+                        .outputs
+                        .iter()
+                        .map(|v| L::Field::from_canonical_u64(*v))
+                        .collect(),
+                )
+            })
+            .ok_or_else(|| anyhow!("no recorded response for hint {}", hint_id))
+    }
+}
+
+/// Either records the responses of the asynchronous hints, or replays them from a recording
+/// instead of running the hints.
+#[derive(Debug, Clone)]
+pub enum HintRecorder {
+    Record(Arc<Mutex<HintRecording>>),
+    Replay(Arc<HintRecording>),
+}
+
+impl HintRecorder {
+    pub fn record() -> Self {
+        Self::Record(Arc::new(Mutex::new(HintRecording::default())))
+    }
+
+    pub fn replay(recording: HintRecording) -> Self {
+        Self::Replay(Arc::new(recording))
+    }
+
+    /// Returns the responses recorded so far, or the recording being replayed.
+    pub fn recording(&self) -> HintRecording {
+        match self {
+            Self::Record(recording) => recording.lock().unwrap().clone(),
+            Self::Replay(recording) => recording.as_ref().clone(),
+        }
+    }
+
+    /// Runs a synchronous `generator` that fetches external data once all the targets it watches
+    /// are set, recording the values it generates or replaying them instead of running it.
+    pub(crate) fn run_generator<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        generator: &WitnessGeneratorRef<L::Field, D>,
+        witness: &PartitionWitness<L::Field>,
+        out_buffer: &mut GeneratedValues<L::Field>,
+    ) -> Result<bool> {
+        let watch_list = generator.0.watch_list();
+        if !witness.contains_all(&watch_list) {
+            return Ok(false);
+        }
+        let generator_id = generator.0.id();
+        let inputs = witness
+            .get_targets(&watch_list)
+            .iter()
+            .map(|v| v.to_canonical_u64())
+            .collect::<Vec<_>>();
+
+        match self {
+            Self::Record(recording) => {
+                let mut buffer = GeneratedValues::empty();
+                let finished = generator.0.run(witness, &mut buffer);
+                if finished {
+                    let outputs = buffer
+                        .target_values
+                        .iter()
+                        .map(|(target, value)| ((*target).into(), value.to_canonical_u64()))
+                        .collect();
+                    let response = GeneratorResponse {
+                        generator_id,
+                        inputs,
+                        outputs,
+                    };
+                    recording.lock().unwrap().generator_responses.push(response);
+                }
+                out_buffer.target_values.extend(buffer.target_values);
+                Ok(finished)
+            }
+            Self::Replay(recording) => {
+                let response = recording
+                    .generator_responses
+                    .iter()
+                    .find(|response| {
+                        response.generator_id == generator_id && response.inputs == inputs
+                    })
+                    .ok_or_else(|| {
+                        anyhow!("no recorded response for generator {}", generator_id)
+                    })?;
+                for (target, value) in response.outputs.iter() {
+                    out_buffer.set_target((*target).into(), L::Field::from_canonical_u64(*value));
+                }
+                Ok(true)
+            }
+        }
+    }
+}
+
+fn stream_to_u64s<L: PlonkParameters<D>, const D: usize>(stream: &ValueStream<L, D>) -> Vec<u64> {
+    stream
+        .clone()
+        .read_all()
+        .iter()
+        .map(|v| v.to_canonical_u64())
+        .collect()
+}
+
+/// Records the response of the hint with id `hint_id`.
+pub(crate) fn record_response<L: PlonkParameters<D>, const D: usize>(
+    recording: &Mutex<HintRecording>,
+    hint_id: String,
+    inputs: &ValueStream<L, D>,
+    outputs: &ValueStream<L, D>,
+) {
+    let response = HintResponse {
+        hint_id,
+        inputs: stream_to_u64s(inputs),
+        outputs: stream_to_u64s(outputs),
+    };
+    recording.lock().unwrap().responses.push(response);
+}
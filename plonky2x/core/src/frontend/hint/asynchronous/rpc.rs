@@ -0,0 +1,256 @@
+//! Size-limited reads of the RPC responses fetched during witness generation.
+//!
+//! A misbehaving RPC could return a huge response that exhausts memory during witness
+//! generation, so the clients shared by the hints and generators, [`RpcClient`] for execution
+//! nodes and [`BeaconClient`](crate::utils::eth::beacon::BeaconClient) for beacon nodes, read
+//! responses in chunks and abort as soon as they exceed their maximum response size.
+//!
+//! The defaults can be overridden with the `MAX_RPC_RESPONSE_SIZE` environment variable for
+//! execution nodes, and with `MAX_BEACON_RESPONSE_SIZE` for beacon nodes. A hint whose responses
+//! are much smaller than the default sets its own limit on the shared client, e.g.
+//! `get_rpc_client(chain_id).with_max_response_size(..)`. The limit cannot be a field of the
+//! hint: asynchronous hints are serialized with bincode, which cannot skip missing fields, so a
+//! new field would break loading circuits that were saved before.
+
+use std::env;
+use std::io::Read;
+
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// The environment variable that overrides the maximum size of the responses of execution nodes.
+pub const MAX_RPC_RESPONSE_SIZE_ENV: &str = "MAX_RPC_RESPONSE_SIZE";
+
+/// The environment variable that overrides the maximum size of the responses of beacon nodes.
+pub const MAX_BEACON_RESPONSE_SIZE_ENV: &str = "MAX_BEACON_RESPONSE_SIZE";
+
+/// The default maximum size in bytes of a JSON-RPC response, such as a block or its receipts.
+pub const DEFAULT_MAX_RESPONSE_SIZE: usize = 1 << 26;
+
+/// The default maximum size in bytes of an `eth_getProof` response, which holds the account and
+/// storage proofs of a single slot.
+pub const DEFAULT_MAX_PROOF_SIZE: usize = 1 << 22;
+
+/// The default maximum size in bytes of an ssz serialized beacon state.
+pub const DEFAULT_MAX_BEACON_STATE_SIZE: usize = 1 << 30;
+
+/// Returns the maximum response size set by the environment variable `env_var`, or `default` if
+/// it is not set.
+pub fn max_response_size_or(env_var: &str, default: usize) -> usize {
+    dotenv::dotenv().ok();
+    match env::var(env_var) {
+        Ok(size) if !size.is_empty() => size
+            .parse()
+            .unwrap_or_else(|_| panic!("{} must be a number of bytes", env_var)),
+        _ => default,
+    }
+}
+
+/// Returns the maximum size of the responses of execution nodes, which is `default` unless
+/// `MAX_RPC_RESPONSE_SIZE` is set.
+pub fn max_rpc_response_size_or(default: usize) -> usize {
+    max_response_size_or(MAX_RPC_RESPONSE_SIZE_ENV, default)
+}
+
+fn size_exceeded(url: &reqwest::Url, size: u64, max_size: usize) -> anyhow::Error {
+    anyhow!(
+        "response from {} of at least {} bytes exceeds the maximum size of {} bytes",
+        url,
+        size,
+        max_size
+    )
+}
+
+/// Reads the body of `response`, failing if it is larger than `max_size` bytes.
+pub async fn read_response(mut response: reqwest::Response, max_size: usize) -> Result<Vec<u8>> {
+    let url = response.url().clone();
+    let exceeded = |size: u64| size_exceeded(&url, size, max_size);
+    if let Some(length) = response.content_length() {
+        if length > max_size as u64 {
+            return Err(exceeded(length));
+        }
+    }
+
+    // The content length may be missing or wrong, so check the size while reading.
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > max_size {
+            return Err(exceeded((body.len() + chunk.len()) as u64));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// Reads the body of the blocking `response`, failing if it is larger than `max_size` bytes.
+pub fn read_blocking_response(
+    response: reqwest::blocking::Response,
+    max_size: usize,
+) -> Result<Vec<u8>> {
+    let url = response.url().clone();
+    if let Some(length) = response.content_length() {
+        if length > max_size as u64 {
+            return Err(size_exceeded(&url, length, max_size));
+        }
+    }
+
+    // Read one byte more than allowed to detect a body that is too large.
+    let mut body = Vec::new();
+    response.take(max_size as u64 + 1).read_to_end(&mut body)?;
+    if body.len() > max_size {
+        return Err(size_exceeded(&url, body.len() as u64, max_size));
+    }
+    Ok(body)
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse {
+    #[serde(default)]
+    result: Value,
+    error: Option<Value>,
+}
+
+/// Calls the JSON-RPC `method` with `params` at `url`, failing if the response is larger than
+/// `max_size` bytes.
+pub async fn json_rpc_request<T: DeserializeOwned>(
+    url: &str,
+    method: &str,
+    params: Value,
+    max_size: usize,
+) -> Result<T> {
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": params,
+    });
+    let response = reqwest::Client::new()
+        .post(url)
+        .json(&request)
+        .send()
+        .await?
+        .error_for_status()?;
+    let body = read_response(response, max_size).await?;
+    let response: JsonRpcResponse = serde_json::from_slice(&body)?;
+    if let Some(error) = response.error {
+        return Err(anyhow!("{} failed: {}", method, error));
+    }
+    Ok(serde_json::from_value(response.result)?)
+}
+
+/// A JSON-RPC client for an execution node, shared by the hints and generators that query it.
+#[derive(Debug, Clone)]
+pub struct RpcClient {
+    url: String,
+    max_response_size: usize,
+}
+
+impl RpcClient {
+    /// Creates a client whose responses are limited to `MAX_RPC_RESPONSE_SIZE` bytes if it is set,
+    /// and to `DEFAULT_MAX_RESPONSE_SIZE` bytes otherwise.
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            max_response_size: max_rpc_response_size_or(DEFAULT_MAX_RESPONSE_SIZE),
+        }
+    }
+
+    /// Sets the maximum size in bytes of the responses read by the client.
+    pub fn with_max_response_size(mut self, max_response_size: usize) -> Self {
+        self.max_response_size = max_response_size;
+        self
+    }
+
+    /// Calls the JSON-RPC `method` with `params`, failing if the response is too large.
+    pub async fn request<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        json_rpc_request(&self.url, method, params, self.max_response_size).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::utils::reqwest::ReqwestClient;
+
+    /// Serves a single request with a JSON-RPC response whose result is a string of
+    /// `result_len` bytes, and returns the url of the server.
+    async fn serve_once(result_len: usize, with_content_length: bool) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": "a".repeat(result_len),
+        })
+        .to_string();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 4096];
+            let _ = socket.read(&mut request).await;
+            let mut header = "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n".to_string();
+            if with_content_length {
+                header += &format!("content-length: {}\r\n", body.len());
+            }
+            header += "connection: close\r\n\r\n";
+            // The client may hang up as soon as the response is too large.
+            let _ = socket.write_all(header.as_bytes()).await;
+            let _ = socket.write_all(body.as_bytes()).await;
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_json_rpc_request_max_size() {
+        let url = serve_once(1000, true).await;
+        let result = json_rpc_request::<String>(&url, "eth_test", json!([]), 2000)
+            .await
+            .unwrap();
+        assert_eq!(result.len(), 1000);
+
+        // Oversized responses are rejected, whether or not they declare their length.
+        for with_content_length in [true, false] {
+            let url = serve_once(1 << 20, with_content_length).await;
+            let err = json_rpc_request::<String>(&url, "eth_test", json!([]), 2000)
+                .await
+                .unwrap_err();
+            assert!(err
+                .to_string()
+                .contains("exceeds the maximum size of 2000 bytes"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shared_clients_max_size() {
+        let url = serve_once(1 << 20, true).await;
+        let err = RpcClient::new(url)
+            .with_max_response_size(2000)
+            .request::<String>("eth_test", json!([]))
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("exceeds the maximum size of 2000 bytes"));
+
+        // The blocking fetches of the beacon client are limited as well.
+        for with_content_length in [true, false] {
+            let url = serve_once(1 << 20, with_content_length).await;
+            let result = tokio::task::spawn_blocking(move || {
+                ReqwestClient::new()
+                    .with_max_response_size(2000)
+                    .fetch(&url)
+            })
+            .await
+            .unwrap();
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .contains("exceeds the maximum size of 2000 bytes"));
+        }
+    }
+}
@@ -4,7 +4,7 @@ use plonky2::iop::generator::{GeneratedValues, WitnessGenerator};
 use plonky2::iop::target::Target;
 use plonky2::iop::witness::{PartitionWitness, Witness};
 use plonky2::plonk::circuit_data::CommonCircuitData;
-use plonky2::util::serialization::{Buffer, IoError, IoResult};
+use plonky2::util::serialization::{Buffer, IoError, IoResult, Write};
 
 use super::hint::Hint;
 use crate::frontend::hint::HintGenerator;
@@ -12,11 +12,21 @@ use crate::frontend::vars::{ValueStream, VariableStream};
 use crate::prelude::{CircuitVariable, PlonkParameters};
 use crate::utils::serde::BufferWrite;
 
+/// Precedes the format version of a serialized hint. Hints serialized before the format was
+/// versioned start with the length of their input stream instead, which is never `usize::MAX`.
+pub(crate) const HINT_FORMAT_MARKER: usize = usize::MAX;
+
+/// The serialization format of hints. Version 1 adds the input check flag, which is off for the
+/// unversioned hints serialized before.
+pub(crate) const HINT_FORMAT_VERSION: usize = 1;
+
 #[derive(Debug, Clone)]
 pub struct HintSimpleGenerator<L, H> {
     pub(crate) input_stream: VariableStream,
     pub(crate) output_stream: VariableStream,
     pub(crate) hint: H,
+    /// Whether to assert that the hint read exactly the values of its input stream.
+    pub(crate) input_check: bool,
     _marker: PhantomData<L>,
 }
 
@@ -26,9 +36,15 @@ impl<L, H> HintSimpleGenerator<L, H> {
             input_stream,
             output_stream,
             hint,
+            input_check: false,
             _marker: PhantomData,
         }
     }
+
+    pub fn with_input_check(mut self, input_check: bool) -> Self {
+        self.input_check = input_check;
+        self
+    }
 }
 
 impl<L: PlonkParameters<D>, const D: usize, H: Hint<L, D>> HintGenerator<L, D>
@@ -69,6 +85,10 @@ impl<L: PlonkParameters<D>, const D: usize, H: Hint<L, D>> WitnessGenerator<L::F
 
         self.hint.hint(&mut input_stream, &mut output_stream);
 
+        if self.input_check {
+            input_stream.assert_fully_read();
+        }
+
         let output_values = output_stream.read_all();
         let output_vars = self.output_stream.real_all();
         assert_eq!(
@@ -88,11 +108,14 @@ impl<L: PlonkParameters<D>, const D: usize, H: Hint<L, D>> WitnessGenerator<L::F
         dst: &mut Vec<u8>,
         _common_data: &CommonCircuitData<L::Field, D>,
     ) -> IoResult<()> {
+        dst.write_usize(HINT_FORMAT_MARKER)?;
+        dst.write_usize(HINT_FORMAT_VERSION)?;
         self.input_stream.serialize_to_writer(dst)?;
         self.output_stream.serialize_to_writer(dst)?;
 
         let bytes = bincode::serialize(&self.hint).map_err(|_| IoError)?;
-        dst.write_bytes(&bytes)
+        dst.write_bytes(&bytes)?;
+        dst.write_bool(self.input_check)
     }
 
     fn deserialize(
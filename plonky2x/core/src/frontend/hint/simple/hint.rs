@@ -59,7 +59,8 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
     ) -> OutputVariableStream<L, D> {
         let output_stream = VariableStream::new();
 
-        let generator = HintSimpleGenerator::new(input_stream, output_stream.clone(), hint);
+        let generator = HintSimpleGenerator::new(input_stream, output_stream.clone(), hint)
+            .with_input_check(self.hint_input_check);
         let hint_id = self.hints.len();
         self.hints.push(Box::new(generator));
 
@@ -73,6 +74,7 @@ mod tests {
     use serde::{Deserialize, Serialize};
 
     use super::*;
+    use crate::backend::circuit::CircuitBuild;
     use crate::prelude::*;
 
     #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -133,4 +135,44 @@ mod tests {
         assert_eq!(byte_plus_two, 7u8);
         assert_eq!(c_plus_3, 4u8);
     }
+
+    fn hint_input_check_circuit<H: Hint<DefaultParameters, 2>>(
+        hint: H,
+        nb_inputs: usize,
+    ) -> CircuitBuild<DefaultParameters, 2> {
+        let mut builder = DefaultBuilder::new();
+        builder.set_hint_input_check();
+
+        let mut input_stream = VariableStream::new();
+        for _ in 0..nb_inputs {
+            let a = builder.read::<ByteVariable>();
+            input_stream.write(&a);
+        }
+        let output_stream = builder.hint(input_stream, hint);
+        let b = output_stream.read::<ByteVariable>(&mut builder);
+        builder.write(b);
+
+        builder.build()
+    }
+
+    #[test]
+    fn test_hint_input_check() {
+        let circuit = hint_input_check_circuit(AddSome { amount: 2 }, 1);
+        let mut input = circuit.input();
+        input.write::<ByteVariable>(5u8);
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        assert_eq!(output.read::<ByteVariable>(), 7u8);
+    }
+
+    #[test]
+    #[should_panic(expected = "hint did not read its whole input stream: read 1 of 2 elements")]
+    fn test_hint_input_check_mismatch() {
+        // `AddSome` only reads the first of the two input bytes.
+        let circuit = hint_input_check_circuit(AddSome { amount: 2 }, 2);
+        let mut input = circuit.input();
+        input.write::<ByteVariable>(5u8);
+        input.write::<ByteVariable>(1u8);
+        circuit.prove(&input);
+    }
 }
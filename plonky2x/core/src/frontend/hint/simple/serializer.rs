@@ -2,12 +2,12 @@ use core::marker::PhantomData;
 
 use plonky2::iop::generator::WitnessGeneratorRef;
 use plonky2::plonk::circuit_data::CommonCircuitData;
-use plonky2::util::serialization::{Buffer, IoError, IoResult};
+use plonky2::util::serialization::{Buffer, IoError, IoResult, Read};
 
-use super::generator::HintSimpleGenerator;
+use super::generator::{HintSimpleGenerator, HINT_FORMAT_MARKER, HINT_FORMAT_VERSION};
 use super::hint::Hint;
 use crate::backend::circuit::Serializer;
-use crate::frontend::vars::VariableStream;
+use crate::frontend::vars::{Variable, VariableStream};
 use crate::prelude::PlonkParameters;
 use crate::utils::serde::BufferRead;
 
@@ -35,12 +35,25 @@ impl<L: PlonkParameters<D>, H: Hint<L, D>, const D: usize>
         buf: &mut Buffer,
         _common_data: &CommonCircuitData<L::Field, D>,
     ) -> IoResult<WitnessGeneratorRef<L::Field, D>> {
-        let input_stream = VariableStream::deserialize_from_reader(buf)?;
+        let first = buf.read_usize()?;
+        let (version, input_len) = match first {
+            HINT_FORMAT_MARKER => (buf.read_usize()?, buf.read_usize()?),
+            input_len => (0, input_len),
+        };
+        if version > HINT_FORMAT_VERSION {
+            return Err(IoError);
+        }
+        let input_variables = (0..input_len)
+            .map(|_| buf.read_target().map(Variable))
+            .collect::<IoResult<Vec<_>>>()?;
+        let input_stream = VariableStream::from_variables(input_variables);
         let output_stream = VariableStream::deserialize_from_reader(buf)?;
 
         let bytes = buf.read_bytes()?;
         let hint: H = bincode::deserialize(&bytes).map_err(|_| IoError)?;
-        let hint_generator = HintSimpleGenerator::<L, H>::new(input_stream, output_stream, hint);
+        let input_check = version >= 1 && buf.read_bool()?;
+        let hint_generator = HintSimpleGenerator::<L, H>::new(input_stream, output_stream, hint)
+            .with_input_check(input_check);
 
         Ok(WitnessGeneratorRef::new(hint_generator))
     }
@@ -63,6 +76,10 @@ mod tests {
     use super::*;
     use crate::backend::circuit::CircuitBuild;
     use crate::prelude::*;
+    use crate::utils::serde::BufferWrite;
+
+    type L = DefaultParameters;
+    const D: usize = 2;
 
     #[derive(Debug, Clone, Serialize, Deserialize)]
     struct AddSome {
@@ -125,4 +142,59 @@ mod tests {
         let byte_plus_one = output.read::<ByteVariable>();
         assert_eq!(byte_plus_one, 7u8);
     }
+
+    #[test]
+    fn test_hint_serialization_versions() {
+        let mut builder = DefaultBuilder::new();
+        let a = builder.read::<ByteVariable>();
+        let b = builder.init::<ByteVariable>();
+        let mut input_stream = VariableStream::new();
+        input_stream.write(&a);
+        let mut output_stream = VariableStream::new();
+        output_stream.write(&b);
+        let common_data = builder.build().data.common;
+
+        let serializer = SimpleHintSerializer::<L, AddSome>::new();
+        let hint = AddSome { amount: 2 };
+        let serialize = |generator: &WitnessGeneratorRef<<L as PlonkParameters<D>>::Field, D>| {
+            let mut bytes = Vec::new();
+            serializer
+                .write(&mut bytes, generator, &common_data)
+                .unwrap();
+            bytes
+        };
+        let generator = |input_check: bool| {
+            WitnessGeneratorRef::new(
+                HintSimpleGenerator::<L, _>::new(
+                    input_stream.clone(),
+                    output_stream.clone(),
+                    hint.clone(),
+                )
+                .with_input_check(input_check),
+            )
+        };
+
+        // The input check flag round-trips.
+        for input_check in [true, false] {
+            let bytes = serialize(&generator(input_check));
+            let read = serializer
+                .read(&mut Buffer::new(&bytes), &common_data)
+                .unwrap();
+            assert_eq!(serialize(&read), bytes);
+        }
+
+        // Hints serialized before the format was versioned are read with the check off.
+        let mut legacy_bytes = Vec::new();
+        input_stream.serialize_to_writer(&mut legacy_bytes).unwrap();
+        output_stream
+            .serialize_to_writer(&mut legacy_bytes)
+            .unwrap();
+        legacy_bytes
+            .write_bytes(&bincode::serialize(&hint).unwrap())
+            .unwrap();
+        let read = serializer
+            .read(&mut Buffer::new(&legacy_bytes), &common_data)
+            .unwrap();
+        assert_eq!(serialize(&read), serialize(&generator(false)));
+    }
 }
@@ -0,0 +1,154 @@
+//! An SSZ merkleizer that accepts leaves one at a time and only keeps one pending subtree root
+//! per level, so that very large lists can be merkleized without materializing all leaves.
+
+use crate::frontend::builder::counter::Counter;
+use crate::frontend::merkle::utils::log2_ceil_usize;
+use crate::frontend::vars::{Bytes32Variable, SSZVariable};
+use crate::prelude::{CircuitBuilder, PlonkParameters, U64Variable};
+use crate::utils::eth::beacon::ssz::zero_hashes;
+
+/// Computes the SSZ merkle root of up to `capacity` leaves, where missing leaves are zero chunks.
+#[derive(Debug, Clone)]
+pub struct IncrementalMerkleizer {
+    depth: usize,
+    leaves: Counter,
+    /// `branch[i]` is the root of the complete subtree of height `i` that is waiting for its
+    /// right sibling, if any.
+    branch: Vec<Option<Bytes32Variable>>,
+}
+
+impl IncrementalMerkleizer {
+    /// Creates a merkleizer for a tree of `capacity` leaves, rounded up to a power of two.
+    pub fn new(capacity: usize) -> Self {
+        let depth = log2_ceil_usize(capacity);
+        Self {
+            depth,
+            leaves: Counter::new(),
+            branch: vec![None; depth + 1],
+        }
+    }
+
+    /// The number of leaves pushed so far.
+    pub fn len(&self) -> usize {
+        self.leaves.count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.count() == 0
+    }
+
+    /// Appends a leaf, hashing together every subtree that it completes, and returns its index.
+    pub fn push<L: PlonkParameters<D>, const D: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<L, D>,
+        leaf: Bytes32Variable,
+    ) -> usize {
+        assert!(
+            self.len() < 1 << self.depth,
+            "merkleizer is full with {} leaves",
+            self.len()
+        );
+        let mut node = leaf;
+        let mut height = 0;
+        while let Some(left) = self.branch[height].take() {
+            node = builder.curta_sha256_pair(left, node);
+            height += 1;
+        }
+        self.branch[height] = Some(node);
+        self.leaves.increment()
+    }
+
+    /// Appends a chunk of leaves.
+    pub fn extend<L: PlonkParameters<D>, const D: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<L, D>,
+        leaves: &[Bytes32Variable],
+    ) {
+        for leaf in leaves.iter() {
+            self.push(builder, *leaf);
+        }
+    }
+
+    /// Returns the root of the tree, padding the remaining leaves with zero chunks.
+    pub fn finalize<L: PlonkParameters<D>, const D: usize>(
+        self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Bytes32Variable {
+        if let Some(root) = self.branch[self.depth] {
+            return root;
+        }
+        let zero_hashes = zero_hashes(self.depth);
+
+        // Fold the pending subtrees from the bottom up. `node` is the root of the rightmost
+        // non-empty subtree of the current height.
+        let mut node: Option<Bytes32Variable> = None;
+        for (height, zero_hash) in zero_hashes[..self.depth].iter().enumerate() {
+            node = match (self.branch[height], node) {
+                (Some(left), Some(right)) => Some(builder.curta_sha256_pair(left, right)),
+                (Some(left), None) | (None, Some(left)) => {
+                    let right = builder.constant::<Bytes32Variable>(*zero_hash);
+                    Some(builder.curta_sha256_pair(left, right))
+                }
+                (None, None) => None,
+            };
+        }
+        node.unwrap_or_else(|| builder.constant::<Bytes32Variable>(zero_hashes[self.depth]))
+    }
+
+    /// Returns the root of an SSZ list, which is the root of the tree with `length` mixed in.
+    pub fn finalize_list<L: PlonkParameters<D>, const D: usize>(
+        self,
+        builder: &mut CircuitBuilder<L, D>,
+        length: U64Variable,
+    ) -> Bytes32Variable {
+        let root = self.finalize(builder);
+        let length_leaf = length.hash_tree_root(builder);
+        builder.curta_sha256_pair(root, length_leaf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::H256;
+
+    use super::*;
+    use crate::prelude::DefaultParameters;
+
+    type L = DefaultParameters;
+    const D: usize = 2;
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_incremental_merkleizer() {
+        env_logger::try_init().unwrap_or_default();
+
+        let nb_leaves = 1000;
+        let capacity = 1024;
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let leaves = (0..nb_leaves)
+            .map(|i| builder.constant::<Bytes32Variable>(H256::from_low_u64_be(i as u64 + 1)))
+            .collect::<Vec<_>>();
+
+        // Push the first leaf on its own and the rest in chunks.
+        let mut merkleizer = IncrementalMerkleizer::new(capacity);
+        assert_eq!(merkleizer.push(&mut builder, leaves[0]), 0);
+        for chunk in leaves[1..].chunks(37) {
+            merkleizer.extend(&mut builder, chunk);
+        }
+        assert_eq!(merkleizer.len(), nb_leaves);
+        let incremental_root = merkleizer.finalize(&mut builder);
+
+        let zero = builder.constant::<Bytes32Variable>(H256::zero());
+        let mut padded = leaves.clone();
+        padded.resize(capacity, zero);
+        let root = builder.ssz_hash_leafs(&padded);
+        builder.assert_is_equal(incremental_root, root);
+        builder.write(root);
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+}
@@ -0,0 +1,179 @@
+//! Keccak merkle trees with sorted-pair hashing, as used by OpenZeppelin's `MerkleProof` and
+//! `StandardMerkleTree`, e.g. for merkle airdrops.
+//!
+//! Inner nodes are `keccak256(min(a, b) || max(a, b))`, so proofs do not need direction bits.
+
+use crate::prelude::*;
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Hashes a pair of nodes, smallest first, like OpenZeppelin's `_hashPair`.
+    pub fn keccak256_sorted_pair(
+        &mut self,
+        a: Bytes32Variable,
+        b: Bytes32Variable,
+    ) -> Bytes32Variable {
+        let a_u256 = a.as_u256(self);
+        let b_u256 = b.as_u256(self);
+        let a_first = self.lte(a_u256, b_u256);
+        let left = self.select(a_first, a, b);
+        let right = self.select(a_first, b, a);
+
+        let input = [left.as_bytes(), right.as_bytes()].concat();
+        let length = self.constant::<U32Variable>(input.len() as u32);
+        self.keccak256_var(&input, length)
+    }
+
+    /// Computes the root of the tree with the given leaf hashes, laid out like OpenZeppelin's
+    /// `StandardMerkleTree`: the `2n - 1` nodes are stored in an array where node `i` has the
+    /// children `2i + 1` and `2i + 2`, and the leaves fill the end of the array in reverse order.
+    ///
+    /// `StandardMerkleTree` sorts the leaf hashes before building the tree by default, so they
+    /// should be given sorted to reproduce its root.
+    pub fn keccak256_merkle_root(&mut self, leaves: &[Bytes32Variable]) -> Bytes32Variable {
+        assert!(
+            !leaves.is_empty(),
+            "cannot compute the root of an empty tree"
+        );
+        let nb_nodes = 2 * leaves.len() - 1;
+        let mut tree = vec![None; nb_nodes];
+        for (i, leaf) in leaves.iter().enumerate() {
+            tree[nb_nodes - 1 - i] = Some(*leaf);
+        }
+        for i in (0..nb_nodes - leaves.len()).rev() {
+            let left = tree[2 * i + 1].unwrap();
+            let right = tree[2 * i + 2].unwrap();
+            tree[i] = Some(self.keccak256_sorted_pair(left, right));
+        }
+        tree[0].unwrap()
+    }
+
+    /// Computes the root of the tree containing `leaf` from the siblings of its path to the root,
+    /// like OpenZeppelin's `MerkleProof.processProof`.
+    pub fn keccak256_merkle_root_from_proof(
+        &mut self,
+        leaf: Bytes32Variable,
+        proof: &[Bytes32Variable],
+    ) -> Bytes32Variable {
+        proof.iter().fold(leaf, |node, sibling| {
+            self.keccak256_sorted_pair(node, *sibling)
+        })
+    }
+
+    /// Verifies that `leaf` is in the tree with the given root, like OpenZeppelin's
+    /// `MerkleProof.verify`.
+    pub fn verify_keccak_merkle_proof(
+        &mut self,
+        leaf: Bytes32Variable,
+        proof: &[Bytes32Variable],
+        root: Bytes32Variable,
+    ) {
+        let expected_root = self.keccak256_merkle_root_from_proof(leaf, proof);
+        self.assert_is_equal(root, expected_root);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::H256;
+    use ethers::utils::keccak256;
+
+    use super::*;
+
+    type L = DefaultParameters;
+    const D: usize = 2;
+
+    fn hash_pair(a: H256, b: H256) -> H256 {
+        let (left, right) = if a <= b { (a, b) } else { (b, a) };
+        H256::from(keccak256([left.as_bytes(), right.as_bytes()].concat()))
+    }
+
+    /// Builds the nodes of a `StandardMerkleTree` with the given leaf hashes.
+    fn make_merkle_tree(leaves: &[H256]) -> Vec<H256> {
+        let nb_nodes = 2 * leaves.len() - 1;
+        let mut tree = vec![H256::zero(); nb_nodes];
+        for (i, leaf) in leaves.iter().enumerate() {
+            tree[nb_nodes - 1 - i] = *leaf;
+        }
+        for i in (0..nb_nodes - leaves.len()).rev() {
+            tree[i] = hash_pair(tree[2 * i + 1], tree[2 * i + 2]);
+        }
+        tree
+    }
+
+    /// Returns the proof of the node at `index` of the tree.
+    fn get_proof(tree: &[H256], mut index: usize) -> Vec<H256> {
+        let mut proof = Vec::new();
+        while index > 0 {
+            let sibling = if index % 2 == 1 { index + 1 } else { index - 1 };
+            proof.push(tree[sibling]);
+            index = (index - 1) / 2;
+        }
+        proof
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_verify_keccak_merkle_proof() {
+        env_logger::try_init().unwrap_or_default();
+
+        // The tree of the OpenZeppelin merkle-tree README, built with
+        // `StandardMerkleTree.of(values, ["address", "uint256"])` from the values
+        // [0x1111111111111111111111111111111111111111, 5000000000000000000] and
+        // [0x2222222222222222222222222222222222222222, 2500000000000000000].
+        let root_value =
+            bytes32!("0xd4dee0beab2d53f2cc83e567171bd2820e49898130a22622b10ead383e90bd77");
+        let leaf_values = [
+            bytes32!("0xeb02c421cfa48976e66dfb29120745909ea3a0f843456c263cf8f1253483e283"),
+            bytes32!("0xb92c48e9d7abe27fd8dfd6b5dfdbfb1c9a463f80c712b66f3a5180a090cccafc"),
+        ];
+
+        // A larger tree, whose leaves do not fill a perfect binary tree.
+        let mut tree_leaf_values = (0..5u8)
+            .map(|i| H256::from(keccak256([i])))
+            .collect::<Vec<_>>();
+        tree_leaf_values.sort();
+        let tree_values = make_merkle_tree(&tree_leaf_values);
+        let tree_index = tree_values.len() - 1;
+        let tree_proof_values = get_proof(&tree_values, tree_index);
+        assert_eq!(tree_proof_values.len(), 3);
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let root = builder.read::<Bytes32Variable>();
+        let leaf = builder.read::<Bytes32Variable>();
+        let proof = builder.read::<ArrayVariable<Bytes32Variable, 1>>();
+        builder.verify_keccak_merkle_proof(leaf, proof.as_slice(), root);
+        let leaves = builder.read::<ArrayVariable<Bytes32Variable, 2>>();
+        let computed_root = builder.keccak256_merkle_root(leaves.as_slice());
+        builder.assert_is_equal(root, computed_root);
+
+        let tree_root = builder.read::<Bytes32Variable>();
+        let tree_leaf = builder.read::<Bytes32Variable>();
+        let tree_proof = builder.read::<ArrayVariable<Bytes32Variable, 3>>();
+        builder.verify_keccak_merkle_proof(tree_leaf, tree_proof.as_slice(), tree_root);
+        let tree_leaves = builder.read::<ArrayVariable<Bytes32Variable, 5>>();
+        let computed_tree_root = builder.keccak256_merkle_root(tree_leaves.as_slice());
+        builder.assert_is_equal(tree_root, computed_tree_root);
+
+        let circuit = builder.build();
+        let write_input = |proof: H256| {
+            let mut input = circuit.input();
+            input.write::<Bytes32Variable>(root_value);
+            input.write::<Bytes32Variable>(leaf_values[0]);
+            input.write::<ArrayVariable<Bytes32Variable, 1>>(vec![proof]);
+            input.write::<ArrayVariable<Bytes32Variable, 2>>(leaf_values.to_vec());
+            input.write::<Bytes32Variable>(tree_values[0]);
+            input.write::<Bytes32Variable>(tree_values[tree_index]);
+            input.write::<ArrayVariable<Bytes32Variable, 3>>(tree_proof_values.clone());
+            input.write::<ArrayVariable<Bytes32Variable, 5>>(tree_leaf_values.clone());
+            input
+        };
+
+        let input = write_input(leaf_values[1]);
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        // A wrong proof is rejected.
+        let input = write_input(leaf_values[0]);
+        assert!(circuit.mock_prove(&input).is_err());
+    }
+}
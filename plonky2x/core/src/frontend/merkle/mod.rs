@@ -1,3 +1,6 @@
+pub mod incremental;
+pub mod keccak;
+pub mod proof;
 pub mod simple;
 pub mod tendermint;
 pub mod tree;
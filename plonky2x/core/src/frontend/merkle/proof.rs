@@ -0,0 +1,168 @@
+//! Verification of merkle inclusion proofs for binary trees whose inner nodes are
+//! `sha256(left || right)`, which covers both SSZ and simple merkle trees.
+
+use crate::prelude::*;
+
+/// The position of the proven leaf in the tree.
+///
+/// In both conventions, the direction bits of the path are read from the least significant bit,
+/// starting at the leaf, and a set bit means that the node is the right child of its parent.
+#[derive(Debug, Clone, Copy)]
+pub enum MerkleIndex {
+    /// A generalized index as used by SSZ, where the root is `1` and the children of node `i` are
+    /// `2i` and `2i + 1`. The leaf at position `p` of a tree of depth `d` has the generalized
+    /// index `2^d + p`, so the depth is given by the most significant bit.
+    Generalized(U64Variable),
+    /// The position of the leaf among the `2^depth` leaves of a simple merkle tree.
+    Leaf { index: U64Variable, depth: usize },
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Returns the bits of the path from the leaf to the root, checking that the index refers to
+    /// a leaf at depth `depth`.
+    fn merkle_path_bits(&mut self, index: MerkleIndex, depth: usize) -> Vec<BoolVariable> {
+        let true_v = self._true();
+        let false_v = self._false();
+        let (bits, top_bit) = match index {
+            MerkleIndex::Generalized(gindex) => (self.to_le_bits(gindex), Some(depth)),
+            MerkleIndex::Leaf {
+                index,
+                depth: leaf_depth,
+            } => {
+                assert_eq!(leaf_depth, depth, "branch length does not match the depth");
+                (self.to_le_bits(index), None)
+            }
+        };
+        assert!(depth < bits.len(), "merkle tree is too deep");
+
+        // A generalized index has a single set bit above the path, and a leaf index has none.
+        for (i, bit) in bits.iter().enumerate().skip(depth) {
+            let expected = if top_bit == Some(i) { true_v } else { false_v };
+            self.assert_is_equal(*bit, expected);
+        }
+        bits[..depth].to_vec()
+    }
+
+    /// Computes the root of the tree containing `leaf` with the given branch, which lists the
+    /// siblings of the path from the leaf to the root.
+    pub fn merkle_root_from_proof(
+        &mut self,
+        leaf: Bytes32Variable,
+        branch: &[Bytes32Variable],
+        index: MerkleIndex,
+    ) -> Bytes32Variable {
+        let bits = self.merkle_path_bits(index, branch.len());
+        let mut node = leaf;
+        for (sibling, is_right) in branch.iter().zip(bits) {
+            let left = self.select(is_right, *sibling, node);
+            let right = self.select(is_right, node, *sibling);
+            node = self.curta_sha256_pair(left, right);
+        }
+        node
+    }
+
+    /// Verifies that `leaf` is in the tree with the given root at the position given by `index`,
+    /// which is either a generalized index or a leaf index, see [`MerkleIndex`].
+    pub fn verify_merkle_proof(
+        &mut self,
+        root: Bytes32Variable,
+        leaf: Bytes32Variable,
+        branch: &[Bytes32Variable],
+        index: MerkleIndex,
+    ) {
+        let expected_root = self.merkle_root_from_proof(leaf, branch, index);
+        self.assert_is_equal(root, expected_root);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::H256;
+
+    use super::*;
+    use crate::utils::hash::sha256;
+
+    type L = DefaultParameters;
+    const D: usize = 2;
+
+    /// Returns the layers of the tree from the leaves to the root.
+    fn merkle_layers(leaves: Vec<H256>) -> Vec<Vec<H256>> {
+        let mut layers = vec![leaves];
+        while layers.last().unwrap().len() > 1 {
+            let layer = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| {
+                    let data = [pair[0].as_bytes(), pair[1].as_bytes()].concat();
+                    H256::from(sha256(&data))
+                })
+                .collect();
+            layers.push(layer);
+        }
+        layers
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_verify_merkle_proof() {
+        env_logger::try_init().unwrap_or_default();
+
+        let depth = 3;
+        let position: usize = 5;
+        let leaves = (0..1 << depth)
+            .map(|i| H256::from_low_u64_be(i as u64 + 1))
+            .collect::<Vec<_>>();
+        let layers = merkle_layers(leaves);
+        let root_value = layers[depth][0];
+        let branch_value = (0..depth)
+            .map(|i| layers[i][(position >> i) ^ 1])
+            .collect::<Vec<_>>();
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let root = builder.read::<Bytes32Variable>();
+        let leaf = builder.read::<Bytes32Variable>();
+        let branch = builder.read::<ArrayVariable<Bytes32Variable, 3>>();
+        let gindex = builder.read::<U64Variable>();
+        let index = builder.read::<U64Variable>();
+
+        // The same leaf is proven with both conventions, and with the SSZ gadget.
+        builder.verify_merkle_proof(
+            root,
+            leaf,
+            branch.as_slice(),
+            MerkleIndex::Generalized(gindex),
+        );
+        builder.verify_merkle_proof(
+            root,
+            leaf,
+            branch.as_slice(),
+            MerkleIndex::Leaf { index, depth },
+        );
+        builder.ssz_verify_proof(root, leaf, branch.as_slice(), gindex);
+
+        let circuit = builder.build();
+        let write_input = |gindex: u64, index: u64| {
+            let mut input = circuit.input();
+            input.write::<Bytes32Variable>(root_value);
+            input.write::<Bytes32Variable>(layers[0][position]);
+            input.write::<ArrayVariable<Bytes32Variable, 3>>(branch_value.clone());
+            input.write::<U64Variable>(gindex);
+            input.write::<U64Variable>(index);
+            input
+        };
+
+        let input = write_input((1 << depth) + position as u64, position as u64);
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        // Using the leaf index as a generalized index or the reverse is rejected.
+        let input = write_input(position as u64, position as u64);
+        assert!(circuit.mock_prove(&input).is_err());
+        let input = write_input(
+            (1 << depth) + position as u64,
+            (1 << depth) + position as u64,
+        );
+        assert!(circuit.mock_prove(&input).is_err());
+    }
+}
@@ -58,7 +58,8 @@ pub trait TendermintMerkleTree {
 /// Adds pre-image prefix of 0x01 to inner nodes and 0x00 to leaf nodes for second pre-image resistance.
 /// Computed root hash is independent of the number of empty leaves, unlike the simple Merkle Tree.
 impl<L: PlonkParameters<D>, const D: usize> TendermintMerkleTree for CircuitBuilder<L, D> {
-    /// Leaf should already be hashed.
+    /// Leaf should already be hashed. `path_indices[i]` is set if the node at height `i` of the
+    /// path is a right child, i.e. it is bit `i` of the leaf index.
     fn get_root_from_merkle_proof_hashed_leaf<const PROOF_DEPTH: usize>(
         &mut self,
         proof: &ArrayVariable<Bytes32Variable, PROOF_DEPTH>,
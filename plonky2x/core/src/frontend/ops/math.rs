@@ -2,7 +2,7 @@
 
 use crate::backend::circuit::PlonkParameters;
 use crate::frontend::builder::CircuitBuilder;
-use crate::prelude::BoolVariable;
+use crate::prelude::{BoolVariable, CircuitVariable};
 
 /// The addition operation.
 ///
@@ -205,11 +205,42 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         let upper_bound_satisfied = self.lt(variable, rhs);
         self.and(lower_bound_satisfied, upper_bound_satisfied)
     }
+
+    /// The absolute difference operation (|lhs - rhs|).
+    pub fn abs_diff<V>(&mut self, lhs: V, rhs: V) -> V
+    where
+        V: CircuitVariable + LessThanOrEqual<L, D, V> + Sub<L, D, V, Output = V>,
+    {
+        let lhs_lte_rhs = self.lte(lhs.clone(), rhs.clone());
+        let larger = self.select(lhs_lte_rhs, rhs.clone(), lhs.clone());
+        let smaller = self.select(lhs_lte_rhs, lhs, rhs);
+        self.sub(larger, smaller)
+    }
+
+    /// The clamp operation, which returns `min` if `variable < min`, `max` if `variable > max`,
+    /// and `variable` otherwise. Assumes that `min <= max`.
+    pub fn clamp<V>(&mut self, variable: V, min: V, max: V) -> V
+    where
+        V: CircuitVariable + LessThanOrEqual<L, D, V>,
+    {
+        let below_min = self.lt(variable.clone(), min.clone());
+        let above_max = self.gt(variable.clone(), max.clone());
+        let capped = self.select(above_max, max, variable);
+        self.select(below_min, min, capped)
+    }
 }
 
+#[cfg(test)]
 mod tests {
-    #[allow(unused_imports)]
-    use crate::prelude::{BoolVariable, DefaultBuilder, U32Variable};
+    use ethers::types::U256;
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::Field;
+
+    use crate::frontend::eth::vars::AddressVariable;
+    use crate::prelude::{
+        BoolVariable, Bytes32Variable, CircuitVariable, DefaultBuilder, U128Variable, U256Variable,
+        U32Variable, U64Variable,
+    };
 
     #[test]
     fn test_math_lt() {
@@ -326,4 +357,110 @@ mod tests {
             circuit.verify(&proof, &input, &output);
         }
     }
+
+    #[test]
+    fn test_math_abs_diff() {
+        let mut builder = DefaultBuilder::new();
+
+        let v0 = builder.read::<U256Variable>();
+        let v1 = builder.read::<U256Variable>();
+        let result = builder.read::<U256Variable>();
+        let computed_result = builder.abs_diff(v0, v1);
+        builder.assert_is_equal(result, computed_result);
+
+        let circuit = builder.build();
+
+        let test_cases = [
+            (U256::from(100), U256::from(42), U256::from(58)),
+            (U256::from(42), U256::from(100), U256::from(58)),
+            (U256::MAX, U256::MAX, U256::zero()),
+            (U256::zero(), U256::MAX, U256::MAX),
+        ];
+
+        for test_case in test_cases.iter() {
+            let mut input = circuit.input();
+            input.write::<U256Variable>(test_case.0);
+            input.write::<U256Variable>(test_case.1);
+            input.write::<U256Variable>(test_case.2);
+
+            let (proof, output) = circuit.prove(&input);
+            circuit.verify(&proof, &input, &output);
+        }
+    }
+
+    #[test]
+    fn test_math_clamp() {
+        let mut builder = DefaultBuilder::new();
+
+        let variable = builder.read::<U256Variable>();
+        let min = builder.read::<U256Variable>();
+        let max = builder.read::<U256Variable>();
+        let result = builder.read::<U256Variable>();
+        let computed_result = builder.clamp(variable, min, max);
+        builder.assert_is_equal(result, computed_result);
+
+        let circuit = builder.build();
+
+        let (min, max) = (U256::from(10), U256::from(100));
+        let test_cases = [
+            (U256::from(3), U256::from(10)),
+            (U256::from(10), U256::from(10)),
+            (U256::from(42), U256::from(42)),
+            (U256::from(100), U256::from(100)),
+            (U256::from(1000), U256::from(100)),
+            (U256::MAX, U256::from(100)),
+        ];
+
+        for test_case in test_cases.iter() {
+            let mut input = circuit.input();
+            input.write::<U256Variable>(test_case.0);
+            input.write::<U256Variable>(min);
+            input.write::<U256Variable>(max);
+            input.write::<U256Variable>(test_case.1);
+
+            let (proof, output) = circuit.prove(&input);
+            circuit.verify(&proof, &input, &output);
+        }
+    }
+
+    #[test]
+    fn test_zero_is_constant() {
+        let mut builder = DefaultBuilder::new();
+
+        let zero = builder.zero::<U256Variable>();
+        for variable in zero.variables() {
+            assert_eq!(
+                builder.api.target_as_constant(variable.0),
+                Some(GoldilocksField::ZERO)
+            );
+        }
+        let expected = builder.constant::<U256Variable>(U256::zero());
+        builder.assert_is_equal(zero, expected);
+
+        let mut variables = Vec::new();
+        variables.extend(builder.zero::<U64Variable>().variables());
+        variables.extend(builder.zero::<U128Variable>().variables());
+        variables.extend(builder.zero::<Bytes32Variable>().variables());
+        variables.extend(builder.zero::<AddressVariable>().variables());
+        variables.extend(builder.zero::<BoolVariable>().variables());
+        for variable in variables {
+            assert_eq!(
+                builder.api.target_as_constant(variable.0),
+                Some(GoldilocksField::ZERO)
+            );
+        }
+
+        let one = builder.one::<BoolVariable>();
+        let constant_true = builder.constant_bool(true);
+        assert_eq!(
+            builder.api.target_as_constant(one.variable.0),
+            Some(GoldilocksField::ONE)
+        );
+        builder.assert_is_equal(one, constant_true);
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
 }
@@ -0,0 +1,127 @@
+//! Signed 256-bit integers in two's complement, like the EVM's `int256`.
+
+use ethers::types::I256;
+use plonky2::hash::hash_types::RichField;
+
+use crate::frontend::uint::uint256::U256Variable;
+use crate::prelude::{BoolVariable, CircuitBuilder, CircuitVariable, PlonkParameters, Variable};
+
+/// A signed 256-bit integer, stored as the `U256Variable` of its two's complement encoding.
+#[derive(Debug, Clone, Copy)]
+pub struct I256Variable(pub U256Variable);
+
+impl CircuitVariable for I256Variable {
+    type ValueType<F: RichField> = I256;
+
+    fn init_unsafe<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Self {
+        Self(U256Variable::init_unsafe(builder))
+    }
+
+    fn variables(&self) -> Vec<Variable> {
+        self.0.variables()
+    }
+
+    fn from_variables_unsafe(variables: &[Variable]) -> Self {
+        Self(U256Variable::from_variables_unsafe(variables))
+    }
+
+    fn assert_is_valid<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) {
+        self.0.assert_is_valid(builder)
+    }
+
+    fn nb_elements() -> usize {
+        U256Variable::nb_elements()
+    }
+
+    fn elements<F: RichField>(value: Self::ValueType<F>) -> Vec<F> {
+        U256Variable::elements::<F>(value.into_raw())
+    }
+
+    fn from_elements<F: RichField>(elements: &[F]) -> Self::ValueType<F> {
+        I256::from_raw(U256Variable::from_elements::<F>(elements))
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Returns true if the most significant bit of the value is set.
+    fn u256_msb(&mut self, value: U256Variable) -> BoolVariable {
+        self.to_be_bits(value)[0]
+    }
+
+    /// Returns true if `value` is negative.
+    pub fn i256_is_negative(&mut self, value: I256Variable) -> BoolVariable {
+        self.u256_msb(value.0)
+    }
+
+    /// Computes the signed difference `a - b` of two unsigned values.
+    ///
+    /// The difference is computed on 257 bits, i.e. the wrapping difference and its borrow. It
+    /// fits in an `I256Variable` if and only if the borrow equals the sign bit of the wrapping
+    /// difference, which is asserted, so the circuit only fails if `|a - b|` exceeds `int256`.
+    pub fn i256_sub_u256(&mut self, a: U256Variable, b: U256Variable) -> I256Variable {
+        let difference = self.sub(a, b);
+        let borrow = self.lt(a, b);
+        let sign = self.u256_msb(difference);
+        self.assert_is_equal(borrow, sign);
+
+        // In range, the wrapping difference is the two's complement encoding of `a - b`.
+        I256Variable(difference)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::U256;
+
+    use super::*;
+    use crate::prelude::DefaultBuilder;
+
+    #[test]
+    fn test_i256_sub_u256() {
+        let mut builder = DefaultBuilder::new();
+        let a = builder.read::<U256Variable>();
+        let b = builder.read::<U256Variable>();
+        let difference = builder.i256_sub_u256(a, b);
+        let is_negative = builder.i256_is_negative(difference);
+        builder.write(difference);
+        builder.write(is_negative);
+
+        let circuit = builder.build();
+        let test_cases = [
+            (U256::from(1000), U256::from(1), I256::from(999i64)),
+            (U256::from(1), U256::from(1000), I256::from(-999i64)),
+            (U256::MAX >> 1, U256::zero(), I256::MAX),
+            (U256::zero(), U256::MAX >> 1, -I256::MAX),
+            (U256::MAX, U256::MAX - 5, I256::from(5i64)),
+            (U256::MAX - 5, U256::MAX, I256::from(-5i64)),
+            (U256::MAX, (U256::MAX >> 1) + 1, I256::MAX),
+            (U256::MAX >> 1, U256::MAX, I256::MIN),
+        ];
+        for (a, b, expected) in test_cases {
+            let mut input = circuit.input();
+            input.write::<U256Variable>(a);
+            input.write::<U256Variable>(b);
+            let (proof, mut output) = circuit.prove(&input);
+            circuit.verify(&proof, &input, &output);
+            assert_eq!(output.read::<I256Variable>(), expected);
+            assert_eq!(output.read::<BoolVariable>(), expected.is_negative());
+        }
+
+        // Differences that do not fit in an int256 are rejected.
+        for (a, b) in [
+            (U256::MAX, U256::MAX >> 1),
+            (U256::MAX, U256::zero()),
+            (U256::zero(), U256::MAX),
+        ] {
+            let mut input = circuit.input();
+            input.write::<U256Variable>(a);
+            input.write::<U256Variable>(b);
+            assert!(circuit.mock_prove(&input).is_err());
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use core::fmt::Debug;
 
+pub mod int256;
+pub mod modular;
 pub mod uint128;
 pub mod uint256;
 pub mod uint32;
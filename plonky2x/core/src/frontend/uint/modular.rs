@@ -0,0 +1,208 @@
+//! Modular arithmetic over a runtime modulus given as a `U256Variable`.
+//!
+//! Unlike the non-native field gadgets, the modulus is not fixed when the circuit is built, which
+//! matches the `mulmod` semantics of the EVM.
+
+use ethers::types::U256;
+use num::{BigInt, BigUint, Integer, One, Zero};
+use serde::{Deserialize, Serialize};
+
+use crate::frontend::hint::simple::hint::Hint;
+use crate::frontend::uint::num::biguint::{BigUintTarget, CircuitBuilderBiguint};
+use crate::frontend::uint::num::u32::gadgets::arithmetic_u32::U32Target;
+use crate::prelude::*;
+
+fn u256_to_biguint(value: U256) -> BigUint {
+    let mut bytes = [0u8; 32];
+    value.to_little_endian(&mut bytes);
+    BigUint::from_bytes_le(&bytes)
+}
+
+fn biguint_to_u256(value: &BigUint) -> U256 {
+    U256::from_little_endian(&value.to_bytes_le())
+}
+
+/// Computes the inverse of `a` modulo `modulus` with the extended Euclidean algorithm, or `None`
+/// if `a` and `modulus` are not coprime.
+pub fn mod_inverse(a: &BigUint, modulus: &BigUint) -> Option<BigUint> {
+    if modulus.is_zero() {
+        return None;
+    }
+    let a = BigInt::from(a.clone());
+    let modulus = BigInt::from(modulus.clone());
+    let extended = a.extended_gcd(&modulus);
+    if !extended.gcd.is_one() {
+        return None;
+    }
+    extended.x.mod_floor(&modulus).to_biguint()
+}
+
+/// Input: (a: u256, modulus: u256)
+/// Output: (inverse: u256)
+///
+/// Computes the inverse of `a` modulo `modulus`, or zero if it does not exist.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct U256ModInverseHint;
+
+impl<L: PlonkParameters<D>, const D: usize> Hint<L, D> for U256ModInverseHint {
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        let a = u256_to_biguint(input_stream.read_value::<U256Variable>());
+        let modulus = u256_to_biguint(input_stream.read_value::<U256Variable>());
+
+        let inverse = mod_inverse(&a, &modulus).unwrap_or_else(BigUint::zero);
+        output_stream.write_value::<U256Variable>(biguint_to_u256(&inverse));
+    }
+}
+
+fn u256_to_biguint_target(x: &U256Variable) -> BigUintTarget {
+    BigUintTarget {
+        limbs: x.limbs.iter().map(|l| U32Target::from(*l)).collect(),
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Computes `(a * b) % modulus` without overflowing, like the EVM's `MULMOD`, which returns
+    /// zero when the modulus is zero.
+    pub fn mulmod(
+        &mut self,
+        a: U256Variable,
+        b: U256Variable,
+        modulus: U256Variable,
+    ) -> U256Variable {
+        // The remainder generator cannot divide by zero, so divide by one instead and select zero.
+        let zero = self.zero::<U256Variable>();
+        let one = self.one::<U256Variable>();
+        let is_zero_modulus = self.is_equal(modulus, zero);
+        let modulus = self.select(is_zero_modulus, one, modulus);
+
+        let a = u256_to_biguint_target(&a);
+        let b = u256_to_biguint_target(&b);
+        let modulus = u256_to_biguint_target(&modulus);
+
+        let product = self.api.mul_biguint(&a, &b);
+        let remainder = self.api.rem_biguint(&product, &modulus);
+
+        let mut limbs = [self.zero::<U32Variable>(); 8];
+        for (limb, target) in limbs.iter_mut().zip(remainder.limbs.iter()) {
+            *limb = (*target).into();
+        }
+        let remainder = U256Variable { limbs };
+        self.select(is_zero_modulus, zero, remainder)
+    }
+
+    /// Computes the inverse of `a` modulo `modulus`, which is usually a prime.
+    ///
+    /// The inverse is witnessed by a hint and constrained to be reduced and to satisfy
+    /// `a * inverse ≡ 1 (mod modulus)`, so the circuit is unsatisfiable if `a` is not invertible.
+    pub fn mod_inverse(&mut self, a: U256Variable, modulus: U256Variable) -> U256Variable {
+        let mut input_stream = VariableStream::new();
+        input_stream.write(&a);
+        input_stream.write(&modulus);
+        let output_stream = self.hint(input_stream, U256ModInverseHint);
+        let inverse = output_stream.read::<U256Variable>(self);
+
+        let is_reduced = self.lt(inverse, modulus);
+        let true_v = self._true();
+        self.assert_is_equal(is_reduced, true_v);
+
+        let product = self.mulmod(a, inverse, modulus);
+        let one = self.one::<U256Variable>();
+        self.assert_is_equal(product, one);
+
+        inverse
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type L = DefaultParameters;
+    const D: usize = 2;
+
+    /// The modulus of the secp256k1 base field.
+    const SECP256K1_P: &str = "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f";
+
+    #[test]
+    fn test_mod_inverse_native() {
+        let modulus = BigUint::from(17u32);
+        assert_eq!(
+            mod_inverse(&BigUint::from(3u32), &modulus),
+            Some(BigUint::from(6u32))
+        );
+        assert_eq!(mod_inverse(&BigUint::zero(), &modulus), None);
+        assert_eq!(
+            mod_inverse(&BigUint::from(4u32), &BigUint::from(8u32)),
+            None
+        );
+        assert_eq!(mod_inverse(&BigUint::one(), &BigUint::zero()), None);
+    }
+
+    #[test]
+    fn test_mulmod() {
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let a = builder.read::<U256Variable>();
+        let b = builder.read::<U256Variable>();
+        let modulus = builder.read::<U256Variable>();
+        let result = builder.mulmod(a, b, modulus);
+        builder.write(result);
+
+        let circuit = builder.build();
+        let test_cases = [
+            (U256::MAX, U256::MAX, U256::from(12345), U256::from(315)),
+            (U256::from(7), U256::from(5), U256::from(6), U256::from(5)),
+            // Like the EVM, a zero modulus gives zero.
+            (U256::from(7), U256::from(5), U256::zero(), U256::zero()),
+        ];
+        for (a, b, modulus, expected) in test_cases {
+            let mut input = circuit.input();
+            input.write::<U256Variable>(a);
+            input.write::<U256Variable>(b);
+            input.write::<U256Variable>(modulus);
+            let (proof, mut output) = circuit.prove(&input);
+            circuit.verify(&proof, &input, &output);
+            assert_eq!(output.read::<U256Variable>(), expected);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_mod_inverse() {
+        env_logger::try_init().unwrap_or_default();
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let a = builder.read::<U256Variable>();
+        let modulus = builder.read::<U256Variable>();
+        let inverse = builder.mod_inverse(a, modulus);
+        let product = builder.mulmod(a, inverse, modulus);
+        builder.write(inverse);
+        builder.write(product);
+
+        let circuit = builder.build();
+        let modulus = U256::from_str_radix(SECP256K1_P, 16).unwrap();
+        let a = U256::from_str_radix(
+            "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            16,
+        )
+        .unwrap();
+        let mut input = circuit.input();
+        input.write::<U256Variable>(a);
+        input.write::<U256Variable>(modulus);
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        let inverse = output.read::<U256Variable>();
+        let product = output.read::<U256Variable>();
+        assert_eq!(product, U256::one());
+        assert_eq!(
+            u256_to_biguint(a) * u256_to_biguint(inverse) % u256_to_biguint(modulus),
+            BigUint::one()
+        );
+
+        // Zero has no inverse, so the circuit is unsatisfiable.
+        let mut input = circuit.input();
+        input.write::<U256Variable>(U256::zero());
+        input.write::<U256Variable>(modulus);
+        assert!(circuit.mock_prove(&input).is_err());
+    }
+}
@@ -333,6 +333,31 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
             self.assert_is_equal(accumulator1, accumulator2);
         }
     }
+
+    /// Rotates `array` to the left by `n` positions, so that `result[i] = array[(i + n) % N]`.
+    /// This is a permutation of the wires and adds no constraints.
+    pub fn rotate_array<V: CircuitVariable, const N: usize>(
+        &mut self,
+        array: &ArrayVariable<V, N>,
+        n: usize,
+    ) -> ArrayVariable<V, N> {
+        let mut elements = array.as_vec();
+        if N > 0 {
+            elements.rotate_left(n % N);
+        }
+        ArrayVariable::new(elements)
+    }
+
+    /// Reverses the order of the elements of `array`. This is a permutation of the wires and adds
+    /// no constraints.
+    pub fn reverse_array<V: CircuitVariable, const N: usize>(
+        &mut self,
+        array: &ArrayVariable<V, N>,
+    ) -> ArrayVariable<V, N> {
+        let mut elements = array.as_vec();
+        elements.reverse();
+        ArrayVariable::new(elements)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -580,4 +605,38 @@ mod tests {
         let (proof, output) = circuit.prove(&input);
         circuit.verify(&proof, &input, &output);
     }
+
+    #[test]
+    fn test_rotate_and_reverse_array() {
+        let mut builder = DefaultBuilder::new();
+        let array = builder.read::<ArrayVariable<Variable, 5>>();
+
+        let rotated = builder.rotate_array(&array, 2);
+        let rotated_by_len = builder.rotate_array(&array, 7);
+        let reversed = builder.reverse_array(&array);
+        let reversed_twice = builder.reverse_array(&reversed);
+
+        // Both operations only move wires around.
+        assert_eq!(rotated_by_len, rotated);
+        assert_eq!(reversed_twice, array);
+        assert_eq!(builder.rotate_array(&array, 5), array);
+
+        builder.write(rotated);
+        builder.write(reversed);
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        let values = (1..=5)
+            .map(GoldilocksField::from_canonical_u64)
+            .collect::<Vec<_>>();
+        input.write::<ArrayVariable<Variable, 5>>(values);
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        let rotated = output.read::<ArrayVariable<Variable, 5>>();
+        let reversed = output.read::<ArrayVariable<Variable, 5>>();
+        let expected = |v: [u64; 5]| v.map(GoldilocksField::from_canonical_u64).to_vec();
+        assert_eq!(rotated, expected([3, 4, 5, 1, 2]));
+        assert_eq!(reversed, expected([5, 4, 3, 2, 1]));
+    }
 }
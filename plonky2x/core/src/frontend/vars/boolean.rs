@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 use super::{CircuitVariable, Variable};
 use crate::backend::circuit::PlonkParameters;
 use crate::frontend::builder::CircuitBuilder;
-use crate::frontend::ops::{BitAnd, BitOr, BitXor, Not};
+use crate::frontend::ops::{BitAnd, BitOr, BitXor, Not, One, Zero};
 
 /// A variable in the circuit representing a boolean value.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -128,6 +128,18 @@ impl<L: PlonkParameters<D>, const D: usize> Not<L, D> for BoolVariable {
     }
 }
 
+impl<L: PlonkParameters<D>, const D: usize> Zero<L, D> for BoolVariable {
+    fn zero(builder: &mut CircuitBuilder<L, D>) -> Self {
+        builder._false()
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> One<L, D> for BoolVariable {
+    fn one(builder: &mut CircuitBuilder<L, D>) -> Self {
+        builder._true()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::prelude::*;
@@ -1,14 +1,16 @@
 use std::fmt::Debug;
 
 use array_macro::array;
+use plonky2::field::types::Field;
 use plonky2::hash::hash_types::RichField;
 use plonky2::iop::target::{BoolTarget, Target};
 use serde::{Deserialize, Serialize};
 
-use super::{BoolVariable, CircuitVariable, EvmVariable, Variable};
+use super::{BoolVariable, CircuitVariable, EvmVariable, U32Variable, Variable};
 use crate::backend::circuit::PlonkParameters;
 use crate::frontend::builder::CircuitBuilder;
 use crate::frontend::ops::{BitAnd, BitOr, BitXor, Not, RotateLeft, RotateRight, Shl, Shr, Zero};
+use crate::frontend::uint::uint64::U64Variable;
 
 /// A variable in the circuit representing a byte value. Under the hood, it is represented as
 /// eight bits stored in big endian.
@@ -289,6 +291,74 @@ impl<L: PlonkParameters<D>, const D: usize> Zero<L, D> for ByteVariable {
     }
 }
 
+/// The maximum number of ASCII digits that can be parsed into a `U64Variable` without overflow.
+pub const MAX_U64_ASCII_DIGITS: usize = 19;
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Parses big-endian ASCII digits into their numeric value. The proof fails if any byte is not
+    /// in '0'..='9'.
+    pub fn ascii_digits_to_u64(&mut self, digits: &[ByteVariable]) -> U64Variable {
+        assert!(
+            digits.len() <= MAX_U64_ASCII_DIGITS,
+            "at most {} digits are supported, got {}",
+            MAX_U64_ASCII_DIGITS,
+            digits.len()
+        );
+        let false_v = self._false();
+        let true_v = self._true();
+        let zero_limb = self.zero::<U32Variable>();
+        let ten = self.constant::<U64Variable>(10);
+
+        let mut value = self.zero::<U64Variable>();
+        for digit in digits.iter() {
+            // An ASCII digit is 0x30 to 0x39, so the high nibble is 0b0011 and the low nibble is
+            // at most 0b1001.
+            let bits = digit.as_be_bits();
+            for (bit, expected) in bits[..4].iter().zip([false_v, false_v, true_v, true_v]) {
+                self.assert_is_equal(*bit, expected);
+            }
+            let above_eight = self.or(bits[5], bits[6]);
+            let above_nine = self.and(bits[4], above_eight);
+            self.assert_is_equal(above_nine, false_v);
+
+            let mut nibble = [false_v; 8];
+            nibble[4..].copy_from_slice(&bits[4..]);
+            let digit_value = ByteVariable(nibble).to_variable(self);
+            let digit_value = U64Variable {
+                limbs: [
+                    U32Variable::from_variables_unsafe(&[digit_value]),
+                    zero_limb,
+                ],
+            };
+
+            value = self.mul(value, ten);
+            value = self.add(value, digit_value);
+        }
+        value
+    }
+
+    /// Formats `value` as `N` big-endian ASCII digits, padded with leading zeros. The proof fails
+    /// if `value` has more than `N` digits.
+    pub fn u64_to_ascii_digits<const N: usize>(&mut self, value: U64Variable) -> [ByteVariable; N] {
+        let ten = self.constant::<U64Variable>(10);
+        let ascii_zero = self.constant::<Variable>(L::Field::from_canonical_u8(b'0'));
+
+        let mut digits = Vec::with_capacity(N);
+        let mut rest = value;
+        for _ in 0..N {
+            let digit = self.rem(rest, ten);
+            rest = self.div(rest, ten);
+            let ascii_digit = self.add(digit.limbs[0].variable, ascii_zero);
+            digits.push(ByteVariable::from_variable(self, ascii_digit));
+        }
+        let zero = self.zero::<U64Variable>();
+        self.assert_is_equal(rest, zero);
+
+        digits.reverse();
+        digits.try_into().unwrap()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use itertools::Itertools;
@@ -405,4 +475,27 @@ mod tests {
         assert_eq!(left_nibble, expected_left_nibble);
         assert_eq!(right_nibble, expected_right_nibble);
     }
+
+    #[test]
+    fn test_ascii_digits() {
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let digits = builder.read::<BytesVariable<5>>();
+        let value = builder.ascii_digits_to_u64(&digits.0);
+        builder.write(value);
+        let formatted = builder.u64_to_ascii_digits::<7>(value);
+        builder.write(BytesVariable(formatted));
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.write::<BytesVariable<5>>(*b"12345");
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        assert_eq!(output.read::<U64Variable>(), 12345);
+        assert_eq!(output.read::<BytesVariable<7>>(), *b"0012345");
+
+        // A non-digit byte fails the proof.
+        let mut input = circuit.input();
+        input.write::<BytesVariable<5>>(*b"12a45");
+        assert!(circuit.mock_prove(&input).is_err());
+    }
 }
@@ -8,6 +8,7 @@ use super::{
 };
 use crate::backend::circuit::PlonkParameters;
 use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::ops::Zero;
 
 /// A variable in the circuit representing a byte32 value.
 #[derive(Debug, Clone, Copy)]
@@ -110,6 +111,12 @@ impl SSZVariable for Bytes32Variable {
     }
 }
 
+impl<L: PlonkParameters<D>, const D: usize> Zero<L, D> for Bytes32Variable {
+    fn zero(builder: &mut CircuitBuilder<L, D>) -> Self {
+        Self(BytesVariable::zero(builder))
+    }
+}
+
 impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
     // Returns a Bytes32Variable with the first `num_bits` set to 0.
     pub fn mask_be_bits(&mut self, original: Bytes32Variable, num_bits: usize) -> Bytes32Variable {
@@ -118,6 +118,22 @@ impl VariableStream {
         (0..len).map(|_| self.read::<V>()).collect()
     }
 
+    /// Read a variable without advancing the stream.
+    pub fn peek<V: CircuitVariable>(&self) -> V {
+        let variables = self.0.peek_exact(V::nb_elements());
+        V::from_variables_unsafe(variables)
+    }
+
+    /// The index of the next element to be read.
+    pub fn position(&self) -> usize {
+        self.0.position()
+    }
+
+    /// Move the read position of the stream to the given element index.
+    pub fn seek(&mut self, position: usize) {
+        self.0.seek(position)
+    }
+
     pub fn write<V: CircuitVariable>(&mut self, value: &V) {
         self.0.write_slice(&value.variables());
     }
@@ -171,6 +187,22 @@ impl<L: PlonkParameters<D>, const D: usize> ValueStream<L, D> {
         (0..len).map(|_| self.read_value::<V>()).collect()
     }
 
+    /// Read a value without advancing the stream.
+    pub fn peek_value<V: CircuitVariable>(&self) -> V::ValueType<L::Field> {
+        let elements = self.0.peek_exact(V::nb_elements());
+        V::from_elements::<L::Field>(elements)
+    }
+
+    /// The index of the next element to be read.
+    pub fn position(&self) -> usize {
+        self.0.position()
+    }
+
+    /// Move the read position of the stream to the given element index.
+    pub fn seek(&mut self, position: usize) {
+        self.0.seek(position)
+    }
+
     pub fn write_slice(&mut self, values: &[L::Field]) {
         self.0.write_slice(values);
     }
@@ -182,6 +214,16 @@ impl<L: PlonkParameters<D>, const D: usize> ValueStream<L, D> {
     pub fn write_value<V: CircuitVariable>(&mut self, value: V::ValueType<L::Field>) {
         self.0.write_slice(&V::elements::<L::Field>(value));
     }
+
+    /// Asserts that the reader consumed every value of the stream.
+    pub fn assert_fully_read(&self) {
+        assert!(
+            self.0.position() == self.0.data().len(),
+            "hint did not read its whole input stream: read {} of {} elements",
+            self.0.position(),
+            self.0.data().len()
+        );
+    }
 }
 
 impl<L: PlonkParameters<D>, const D: usize> Default for ValueStream<L, D> {
@@ -189,3 +231,50 @@ impl<L: PlonkParameters<D>, const D: usize> Default for ValueStream<L, D> {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{DefaultParameters, U32Variable};
+
+    type L = DefaultParameters;
+    const D: usize = 2;
+
+    #[test]
+    fn test_value_stream_peek() {
+        let mut stream = ValueStream::<L, D>::new();
+        stream.write_value::<U32Variable>(7);
+        stream.write_value::<U32Variable>(11);
+
+        assert_eq!(stream.peek_value::<U32Variable>(), 7);
+        assert_eq!(stream.peek_value::<U32Variable>(), 7);
+        assert_eq!(stream.position(), 0);
+        assert_eq!(stream.read_value::<U32Variable>(), 7);
+        assert_eq!(stream.position(), 1);
+        assert_eq!(stream.peek_value::<U32Variable>(), 11);
+
+        stream.seek(0);
+        assert_eq!(stream.read_vec::<U32Variable>(2), vec![7, 11]);
+    }
+
+    #[test]
+    fn test_variable_stream_peek() {
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let a = builder.init::<U32Variable>();
+        let b = builder.init::<U32Variable>();
+
+        let mut stream = VariableStream::new();
+        stream.write(&a);
+        stream.write(&b);
+
+        let peeked = stream.peek::<U32Variable>();
+        assert_eq!(stream.position(), 0);
+        let read = stream.read::<U32Variable>();
+        assert_eq!(peeked.variables(), read.variables());
+        assert_eq!(read.variables(), a.variables());
+
+        stream.seek(0);
+        stream.read::<U32Variable>();
+        assert_eq!(stream.peek::<U32Variable>().variables(), b.variables());
+    }
+}
@@ -5,15 +5,20 @@ use ethers::types::{H256, U256};
 use itertools::Itertools;
 use log::{debug, info};
 use num::BigInt;
-use reqwest::blocking::Client;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use serde_with::serde_as;
 
+use crate::frontend::hint::asynchronous::rpc::{
+    max_response_size_or, read_response, DEFAULT_MAX_BEACON_STATE_SIZE,
+    MAX_BEACON_RESPONSE_SIZE_ENV,
+};
 use crate::utils::hash::sha256;
 use crate::utils::reqwest::ReqwestClient;
 use crate::utils::serde::deserialize_bigint;
 
+pub mod ssz;
+
 /// A client used for connecting and querying a beacon node.
 #[derive(Debug, Clone)]
 pub struct BeaconClient {
@@ -341,15 +346,149 @@ pub struct GetBeaconHeadersFromOffsetRange {
     pub headers: Vec<String>,
 }
 
+/// The light client header according to the consensus spec.
+/// Reference: https://github.com/ethereum/consensus-specs/blob/dev/specs/altair/light-client/sync-protocol.md#lightclientheader
+#[derive(Debug, Deserialize)]
+pub struct LightClientHeader {
+    pub beacon: BeaconHeader,
+}
+
+/// The sync committee according to the consensus spec.
+/// Reference: https://github.com/ethereum/consensus-specs/blob/dev/specs/altair/beacon-chain.md#synccommittee
+#[derive(Debug, Deserialize)]
+pub struct SyncCommittee {
+    pub pubkeys: Vec<String>,
+    pub aggregate_pubkey: String,
+}
+
+/// The sync aggregate according to the consensus spec.
+/// Reference: https://github.com/ethereum/consensus-specs/blob/dev/specs/altair/beacon-chain.md#syncaggregate
+#[derive(Debug, Deserialize)]
+pub struct SyncAggregate {
+    pub sync_committee_bits: String,
+    pub sync_committee_signature: String,
+}
+
+/// The light client update according to the consensus spec.
+/// Reference: https://github.com/ethereum/consensus-specs/blob/dev/specs/altair/light-client/sync-protocol.md#lightclientupdate
+#[derive(Debug, Deserialize)]
+pub struct LightClientUpdate {
+    pub attested_header: LightClientHeader,
+    pub next_sync_committee: SyncCommittee,
+    pub next_sync_committee_branch: Vec<String>,
+    pub finalized_header: LightClientHeader,
+    pub finality_branch: Vec<String>,
+    pub sync_aggregate: SyncAggregate,
+    pub signature_slot: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LightClientUpdateContainer {
+    data: LightClientUpdate,
+}
+
+/// A deposit included in a beacon block, with its proof against the deposit root of the state.
+/// Reference: https://github.com/ethereum/consensus-specs/blob/dev/specs/phase0/beacon-chain.md#deposit
+#[derive(Debug, Clone, Deserialize)]
+pub struct BeaconDeposit {
+    pub proof: Vec<String>,
+    pub data: BeaconDepositData,
+}
+
+/// Reference: https://github.com/ethereum/consensus-specs/blob/dev/specs/phase0/beacon-chain.md#depositdata
+#[derive(Debug, Clone, Deserialize)]
+pub struct BeaconDepositData {
+    pub pubkey: String,
+    pub withdrawal_credentials: String,
+    pub amount: String,
+    pub signature: String,
+}
+
+/// The deposits of a beacon block along with the state root of the block.
+#[derive(Debug, Clone)]
+pub struct BeaconBlockDeposits {
+    pub state_root: String,
+    pub deposits: Vec<BeaconDeposit>,
+}
+
+/// The forks of the beacon chain, which determine the layout of the beacon state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum BeaconFork {
+    Phase0,
+    Altair,
+    Bellatrix,
+    Capella,
+    Deneb,
+    Electra,
+}
+
+/// The fork versions of mainnet, goerli, sepolia and holesky, indexed by fork.
+const BEACON_FORK_VERSIONS: [[u32; 6]; 4] = [
+    [
+        0x00000000, 0x01000000, 0x02000000, 0x03000000, 0x04000000, 0x05000000,
+    ],
+    [
+        0x00001020, 0x01001020, 0x02001020, 0x03001020, 0x04001020, 0x05001020,
+    ],
+    [
+        0x90000069, 0x90000070, 0x90000071, 0x90000072, 0x90000073, 0x90000074,
+    ],
+    [
+        0x01017000, 0x02017000, 0x03017000, 0x04017000, 0x05017000, 0x06017000,
+    ],
+];
+
+impl BeaconFork {
+    pub const ALL: [BeaconFork; 6] = [
+        BeaconFork::Phase0,
+        BeaconFork::Altair,
+        BeaconFork::Bellatrix,
+        BeaconFork::Capella,
+        BeaconFork::Deneb,
+        BeaconFork::Electra,
+    ];
+
+    /// Returns the fork with the given `fork_version`, if it is a fork version of a known network.
+    pub fn from_fork_version(fork_version: [u8; 4]) -> Option<Self> {
+        let version = u32::from_be_bytes(fork_version);
+        BEACON_FORK_VERSIONS
+            .iter()
+            .find_map(|versions| versions.iter().position(|v| *v == version))
+            .map(|index| Self::ALL[index])
+    }
+
+    /// The position of the fork in the order of forks, starting at zero for phase0.
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    /// The versions of the fork on each of the known networks.
+    pub fn fork_versions(&self) -> Vec<[u8; 4]> {
+        BEACON_FORK_VERSIONS
+            .iter()
+            .map(|versions| versions[self.index()].to_be_bytes())
+            .collect()
+    }
+}
+
 impl BeaconClient {
-    /// Creates a new BeaconClient based on a rpc url.
+    /// Creates a new BeaconClient based on a rpc url. Responses are limited to the size of a beacon
+    /// state, unless `MAX_BEACON_RESPONSE_SIZE` is set.
     pub fn new(rpc_url: String) -> Self {
+        let max_response_size =
+            max_response_size_or(MAX_BEACON_RESPONSE_SIZE_ENV, DEFAULT_MAX_BEACON_STATE_SIZE);
         Self {
             rpc_url,
-            client: ReqwestClient::new(),
+            client: ReqwestClient::new().with_max_response_size(max_response_size),
         }
     }
 
+    /// Sets the maximum size in bytes of the responses read by the client.
+    pub fn with_max_response_size(mut self, max_response_size: usize) -> Self {
+        self.client = self.client.with_max_response_size(max_response_size);
+        self
+    }
+
     /// Gets the block root at `head`.
     pub fn get_finalized_block_root_sync(&self) -> Result<String> {
         self.get_finalized_block_root()
@@ -396,7 +535,7 @@ impl BeaconClient {
         );
         info!("{}", endpoint);
         let response = self.client.fetch_async(&endpoint).await?;
-        let response: CustomResponse<GetBeaconPartialValidatorsRoot> = response.json().await?;
+        let response: CustomResponse<GetBeaconPartialValidatorsRoot> = response.json()?;
         assert!(response.success);
         Ok(response.result)
     }
@@ -415,7 +554,7 @@ impl BeaconClient {
         );
         info!("{}", endpoint);
         let response = self.client.fetch_async(&endpoint).await?;
-        let response: CustomResponse<Vec<String>> = response.json().await?;
+        let response: CustomResponse<Vec<String>> = response.json()?;
         assert!(response.success);
         Ok(response.result)
     }
@@ -434,7 +573,7 @@ impl BeaconClient {
         );
         info!("{}", endpoint);
         let response = self.client.fetch_async(&endpoint).await?;
-        let response: CustomResponse<Vec<BeaconValidator>> = response.json().await?;
+        let response: CustomResponse<Vec<BeaconValidator>> = response.json()?;
         assert!(response.success);
         Ok(response.result)
     }
@@ -444,11 +583,7 @@ impl BeaconClient {
     pub fn get_validators_root(&self, beacon_id: String) -> Result<GetBeaconValidatorsRoot> {
         let endpoint = format!("{}/api/beacon/proof/validator/{}", self.rpc_url, beacon_id);
         debug!("{}", endpoint);
-        let client = Client::new();
-        let response = client
-            .get(&endpoint)
-            .timeout(Duration::new(120, 0))
-            .send()?;
+        let response = self.client.fetch(&endpoint)?;
         let response: CustomResponse<GetBeaconValidatorsRoot> = response.json()?;
         assert!(response.success);
         debug!("done {}", endpoint);
@@ -520,8 +655,7 @@ impl BeaconClient {
             self.rpc_url, beacon_id, pubkey
         );
         info!("{}", endpoint);
-        let client = Client::new();
-        let response = client.get(endpoint).timeout(Duration::new(120, 0)).send()?;
+        let response = self.client.fetch(&endpoint)?;
         let response: CustomResponse<GetBeaconValidator> = response.json()?;
         assert!(response.success);
         Ok(response.result)
@@ -549,18 +683,14 @@ impl BeaconClient {
         );
         info!("{}", endpoint);
         let response = self.client.fetch_async(&endpoint).await?;
-        let response: CustomResponse<GetBeaconPartialBalancesRoot> = response.json().await?;
+        let response: CustomResponse<GetBeaconPartialBalancesRoot> = response.json()?;
         assert!(response.success);
         Ok(response.result)
     }
 
     pub fn get_balance_witness(&self, beacon_id: String, idx: u64) -> Result<u64> {
         let endpoint = format!("{}/api/beacon/balance/{}/{}", self.rpc_url, beacon_id, idx);
-        let client = reqwest::blocking::Client::new();
-        let response = client
-            .get(endpoint)
-            .timeout(Duration::from_secs(300))
-            .send()?;
+        let response = self.client.fetch(&endpoint)?;
         let response: GetBeaconBalanceWitness = response.json()?;
         Ok(response.balance)
     }
@@ -576,11 +706,7 @@ impl BeaconClient {
             self.rpc_url, beacon_id, start_idx, end_idx
         );
         debug!("{}", endpoint);
-        let client = reqwest::blocking::Client::new();
-        let response = client
-            .get(endpoint)
-            .timeout(Duration::from_secs(300))
-            .send()?;
+        let response = self.client.fetch(&endpoint)?;
         let response: GetBeaconBalanceBatchWitness = response.json()?;
         Ok(response.balances)
     }
@@ -665,7 +791,7 @@ impl BeaconClient {
         let endpoint = format!("{}/api/beacon/proof/withdrawal/{}", self.rpc_url, beacon_id);
         info!("{}", endpoint);
         let response = self.client.fetch_async(&endpoint).await?;
-        let response: CustomResponse<GetBeaconWithdrawals> = response.json().await?;
+        let response: CustomResponse<GetBeaconWithdrawals> = response.json()?;
         assert!(response.success);
         Ok(response.result)
     }
@@ -694,7 +820,7 @@ impl BeaconClient {
         );
         info!("{}", endpoint);
         let response = self.client.fetch_async(&endpoint).await?;
-        let response: CustomResponse<GetBeaconHistoricalBlock> = response.json().await?;
+        let response: CustomResponse<GetBeaconHistoricalBlock> = response.json()?;
         assert!(response.success);
         Ok(response.result)
     }
@@ -706,8 +832,7 @@ impl BeaconClient {
             self.rpc_url, beacon_id
         );
         info!("{}", endpoint);
-        let client = Client::new();
-        let response = client.get(endpoint).timeout(Duration::new(60, 0)).send()?;
+        let response = self.client.fetch(&endpoint)?;
         let response: CustomResponse<GetBeaconExecutionPayload> = response.json()?;
         assert!(response.success);
         Ok(response.result)
@@ -717,8 +842,7 @@ impl BeaconClient {
     pub fn get_slot_number(&self, beacon_id: String) -> Result<GetBeaconSlotNumber> {
         let endpoint = format!("{}/api/beacon/proof/slot/{}", self.rpc_url, beacon_id);
         info!("{}", endpoint);
-        let client = Client::new();
-        let response = client.get(endpoint).timeout(Duration::new(60, 0)).send()?;
+        let response = self.client.fetch(&endpoint)?;
         let response: CustomResponse<GetBeaconSlotNumber> = response.json()?;
         assert!(response.success);
         Ok(response.result)
@@ -729,11 +853,112 @@ impl BeaconClient {
         let endpoint = format!("{}/eth/v1/beacon/headers/{}", self.rpc_url, beacon_id);
         info!("{}", endpoint);
         let response = self.client.fetch_async(&endpoint).await?;
-        let parsed: BeaconData<BeaconHeaderContainer> = response.json().await?;
+        let parsed: BeaconData<BeaconHeaderContainer> = response.json()?;
 
         Ok(parsed.data.header.message)
     }
 
+    /// Gets the light client update for the given sync committee period.
+    pub fn get_light_client_update(&self, period: u64) -> Result<LightClientUpdate> {
+        let endpoint = format!(
+            "{}/eth/v1/beacon/light_client/updates?start_period={}&count=1",
+            self.rpc_url, period
+        );
+        info!("{}", endpoint);
+        let response = self.client.fetch(&endpoint)?;
+        let mut parsed: Vec<LightClientUpdateContainer> = response.json()?;
+        parsed
+            .pop()
+            .map(|update| update.data)
+            .ok_or_else(|| anyhow::anyhow!("no light client update for period {}", period))
+    }
+
+    /// Gets the deposits of the block at the given `block_id`.
+    pub fn get_block_deposits(&self, block_id: String) -> Result<BeaconBlockDeposits> {
+        let endpoint = format!("{}/eth/v2/beacon/blocks/{}", self.rpc_url, block_id);
+        info!("{}", endpoint);
+        let response = self.client.fetch(&endpoint)?;
+        let parsed: Value = response.json()?;
+        let message = &parsed["data"]["message"];
+        let state_root = message["state_root"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("failed to parse response"))?;
+        let deposits = serde_json::from_value(message["body"]["deposits"].clone())?;
+        Ok(BeaconBlockDeposits {
+            state_root: state_root.to_string(),
+            deposits,
+        })
+    }
+
+    /// Gets the genesis validators root of the chain.
+    pub fn get_genesis_validators_root(&self) -> Result<String> {
+        let endpoint = format!("{}/eth/v1/beacon/genesis", self.rpc_url);
+        let response = self.client.fetch(&endpoint)?;
+        let parsed: Value = response.json()?;
+        parsed["data"]["genesis_validators_root"]
+            .as_str()
+            .map(|root| root.to_string())
+            .ok_or_else(|| anyhow::anyhow!("failed to parse response"))
+    }
+
+    /// Gets the current fork version of the state at the given `state_id`.
+    pub fn get_fork_version(&self, state_id: String) -> Result<String> {
+        let endpoint = format!("{}/eth/v1/beacon/states/{}/fork", self.rpc_url, state_id);
+        let response = self.client.fetch(&endpoint)?;
+        let parsed: Value = response.json()?;
+        parsed["data"]["current_version"]
+            .as_str()
+            .map(|version| version.to_string())
+            .ok_or_else(|| anyhow::anyhow!("failed to parse response"))
+    }
+
+    /// Gets the fork of the state at the given `state_id` from its current fork version.
+    pub fn get_fork(&self, state_id: String) -> Result<BeaconFork> {
+        let fork_version = self.get_fork_version(state_id)?;
+        let fork_version: [u8; 4] = hex::decode(fork_version.trim_start_matches("0x"))?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("fork version is not 4 bytes"))?;
+        BeaconFork::from_fork_version(fork_version)
+            .ok_or_else(|| anyhow::anyhow!("unknown fork version 0x{}", hex::encode(fork_version)))
+    }
+
+    /// Gets the validator indices of the current sync committee of the state at `state_id`, in
+    /// committee order.
+    pub fn get_sync_committee_indices(&self, state_id: String) -> Result<Vec<u64>> {
+        let endpoint = format!(
+            "{}/eth/v1/beacon/states/{}/sync_committees",
+            self.rpc_url, state_id
+        );
+        let response = self.client.fetch(&endpoint)?;
+        let parsed: Value = response.json()?;
+        parsed["data"]["validators"]
+            .as_array()
+            .and_then(|validators| {
+                validators
+                    .iter()
+                    .map(|index| index.as_str()?.parse().ok())
+                    .collect::<Option<Vec<_>>>()
+            })
+            .ok_or_else(|| anyhow::anyhow!("failed to parse response"))
+    }
+
+    /// Gets the ssz serialized beacon state at the given `state_id`, failing if it is larger than
+    /// the maximum response size of the client.
+    pub async fn get_state_ssz(&self, state_id: String) -> Result<Vec<u8>> {
+        let endpoint = format!("{}/eth/v2/debug/beacon/states/{}", self.rpc_url, state_id);
+        info!("{}", endpoint);
+        let response = self
+            .client
+            .client_async
+            .get(&endpoint)
+            .header("Accept", "application/octet-stream")
+            .timeout(Duration::from_secs(900))
+            .send()
+            .await?
+            .error_for_status()?;
+        read_response(response, self.client.max_response_size).await
+    }
+
     pub fn get_block_roots(&self, beacon_id: String) -> Result<GetBeaconBlockRoots> {
         let endpoint = format!("{}/api/beacon/proof/blockRoots/{}", self.rpc_url, beacon_id);
         info!("{}", endpoint);
@@ -774,7 +999,9 @@ impl BeaconClient {
 mod tests {
     extern crate dotenv;
 
-    use std::env;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::{env, thread};
 
     use log::debug;
 
@@ -872,4 +1099,81 @@ mod tests {
         debug!("{:?}", result);
         Ok(())
     }
+
+    #[test]
+    fn test_beacon_fork_from_fork_version() {
+        assert_eq!(
+            BeaconFork::from_fork_version([0, 0, 0, 0]),
+            Some(BeaconFork::Phase0)
+        );
+        assert_eq!(
+            BeaconFork::from_fork_version([0x04, 0, 0, 0]),
+            Some(BeaconFork::Deneb)
+        );
+        assert_eq!(
+            BeaconFork::from_fork_version([0x90, 0, 0, 0x72]),
+            Some(BeaconFork::Capella)
+        );
+        assert_eq!(
+            BeaconFork::from_fork_version([0x02, 0x01, 0x70, 0]),
+            Some(BeaconFork::Altair)
+        );
+        assert_eq!(BeaconFork::from_fork_version([0xff, 0, 0, 0]), None);
+
+        for fork in BeaconFork::ALL {
+            for fork_version in fork.fork_versions() {
+                assert_eq!(BeaconFork::from_fork_version(fork_version), Some(fork));
+            }
+        }
+    }
+
+    /// Serves `/eth/v1/beacon/states/{slot}/fork` with the mainnet fork versions, using the
+    /// altair and capella fork slots.
+    fn mock_fork_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&mut stream);
+                let mut request = String::new();
+                reader.read_line(&mut request).unwrap();
+                // Drain the headers before responding.
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    line.clear();
+                }
+                let slot = request
+                    .split('/')
+                    .nth(5)
+                    .and_then(|slot| slot.parse::<u64>().ok())
+                    .unwrap();
+                let fork_version = match slot {
+                    0..=2375679 => "0x00000000",
+                    2375680..=6209535 => "0x01000000",
+                    _ => "0x03000000",
+                };
+                let body = format!(
+                    "{{\"data\":{{\"previous_version\":\"0x00000000\",\"current_version\":\"{}\",\"epoch\":\"0\"}}}}",
+                    fork_version
+                );
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn test_get_fork() -> Result<()> {
+        let client = BeaconClient::new(mock_fork_server());
+        assert_eq!(client.get_fork("1000".to_string())?, BeaconFork::Phase0);
+        assert_eq!(client.get_fork("7000000".to_string())?, BeaconFork::Capella);
+        Ok(())
+    }
 }
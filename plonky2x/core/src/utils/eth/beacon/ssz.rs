@@ -0,0 +1,634 @@
+//! A schema driven simple serialize (ssz) deserializer.
+//!
+//! Given a description of an ssz type and its serialized bytes, this module can compute the hash
+//! tree root of the value and extract any field by path together with its merkle proof to the
+//! root. Reference: https://github.com/ethereum/consensus-specs/blob/dev/ssz/simple-serialize.md
+use ethers::types::H256;
+use serde::{Deserialize, Serialize};
+
+use super::BeaconFork;
+use crate::utils::hash::sha256;
+
+const BYTES_PER_CHUNK: usize = 32;
+
+const BITS_PER_CHUNK: usize = 256;
+
+const BYTES_PER_LENGTH_OFFSET: usize = 4;
+
+/// The description of an ssz type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SSZType {
+    /// An unsigned integer with the given number of bytes.
+    Uint(usize),
+    Bool,
+    /// A fixed length vector of bytes (i.e. `Bytes32`).
+    ByteVector(usize),
+    /// A variable length list of bytes with the given limit.
+    ByteList(usize),
+    Bitvector(usize),
+    Bitlist(usize),
+    Vector(Box<SSZType>, usize),
+    List(Box<SSZType>, usize),
+    Container(Vec<(String, SSZType)>),
+}
+
+/// A field extracted from serialized ssz along with its merkle proof to the root.
+#[derive(Debug, Clone)]
+pub struct SSZFieldProof {
+    /// The serialized bytes of the field.
+    pub value: Vec<u8>,
+    /// The hash tree root of the field.
+    pub leaf: H256,
+    /// The merkle branch from the leaf to the root, starting at the leaf.
+    pub branch: Vec<H256>,
+    /// The generalized index of the field relative to the root.
+    pub gindex: u64,
+}
+
+impl SSZType {
+    pub fn container(fields: &[(&str, SSZType)]) -> Self {
+        Self::Container(
+            fields
+                .iter()
+                .map(|(name, ty)| (name.to_string(), ty.clone()))
+                .collect(),
+        )
+    }
+
+    pub fn vector(ty: SSZType, len: usize) -> Self {
+        Self::Vector(Box::new(ty), len)
+    }
+
+    pub fn list(ty: SSZType, limit: usize) -> Self {
+        Self::List(Box::new(ty), limit)
+    }
+
+    fn is_basic(&self) -> bool {
+        matches!(self, Self::Uint(_) | Self::Bool)
+    }
+
+    /// The serialized size of the type, or `None` if the type is variable size.
+    pub fn fixed_size(&self) -> Option<usize> {
+        match self {
+            Self::Uint(nb_bytes) => Some(*nb_bytes),
+            Self::Bool => Some(1),
+            Self::ByteVector(len) => Some(*len),
+            Self::Bitvector(len) => Some(len.div_ceil(8)),
+            Self::Vector(ty, len) => ty.fixed_size().map(|size| size * len),
+            Self::Container(fields) => fields.iter().map(|(_, ty)| ty.fixed_size()).sum(),
+            Self::ByteList(_) | Self::Bitlist(_) | Self::List(_, _) => None,
+        }
+    }
+
+    /// The maximum number of chunks that are merkleized to compute the hash tree root.
+    fn chunk_limit(&self) -> usize {
+        match self {
+            Self::Uint(_) | Self::Bool => 1,
+            Self::ByteVector(len) | Self::ByteList(len) => len.div_ceil(BYTES_PER_CHUNK),
+            Self::Bitvector(len) | Self::Bitlist(len) => len.div_ceil(BITS_PER_CHUNK),
+            Self::Vector(ty, len) | Self::List(ty, len) => {
+                if ty.is_basic() {
+                    (len * ty.fixed_size().unwrap()).div_ceil(BYTES_PER_CHUNK)
+                } else {
+                    *len
+                }
+            }
+            Self::Container(fields) => fields.len(),
+        }
+    }
+
+    /// Computes the hash tree root of the serialized value.
+    pub fn hash_tree_root(&self, bytes: &[u8]) -> H256 {
+        let limit = self.chunk_limit();
+        match self {
+            Self::Uint(_) | Self::Bool | Self::ByteVector(_) | Self::Bitvector(_) => {
+                merkleize(&pack(bytes), limit)
+            }
+            Self::ByteList(_) => mix_in_length(merkleize(&pack(bytes), limit), bytes.len()),
+            Self::Bitlist(_) => {
+                let (bits, len) = bitlist_bits(bytes);
+                mix_in_length(merkleize(&pack(&bits), limit), len)
+            }
+            Self::Vector(ty, _) => merkleize(&element_chunks(ty, bytes), limit),
+            Self::List(ty, _) => {
+                let len = decode_elements(ty, bytes).len();
+                mix_in_length(merkleize(&element_chunks(ty, bytes), limit), len)
+            }
+            Self::Container(fields) => {
+                let chunks = decode_fields(fields, bytes)
+                    .into_iter()
+                    .zip(fields.iter())
+                    .map(|(value, (_, ty))| ty.hash_tree_root(value))
+                    .collect::<Vec<_>>();
+                merkleize(&chunks, limit)
+            }
+        }
+    }
+
+    /// Computes the generalized index of the field at `path` relative to the root of this type.
+    /// Path elements are either container field names or indices into vectors and lists of
+    /// composite types.
+    pub fn gindex(&self, path: &[String]) -> u64 {
+        if path.is_empty() {
+            return 1;
+        }
+        let (child_ty, index) = self.child(&path[0]);
+        let depth = ceil_log2(self.chunk_limit());
+        let mut local = (1u64 << depth) + index as u64;
+        if let Self::List(_, _) = self {
+            // The root of a list is hash(data_root, length), so the data root is the left child.
+            local += 1u64 << depth;
+        }
+        concat_gindex(local, child_ty.gindex(&path[1..]))
+    }
+
+    /// Extracts the field at `path` from the serialized value along with its merkle proof.
+    pub fn prove(&self, bytes: &[u8], path: &[String]) -> SSZFieldProof {
+        if path.is_empty() {
+            return SSZFieldProof {
+                value: bytes.to_vec(),
+                leaf: self.hash_tree_root(bytes),
+                branch: Vec::new(),
+                gindex: 1,
+            };
+        }
+
+        let (child_ty, index) = self.child(&path[0]);
+        let (values, chunks) = match self {
+            Self::Container(fields) => {
+                let values = decode_fields(fields, bytes);
+                let chunks = values
+                    .iter()
+                    .zip(fields.iter())
+                    .map(|(value, (_, ty))| ty.hash_tree_root(value))
+                    .collect::<Vec<_>>();
+                (values, chunks)
+            }
+            _ => {
+                let values = decode_elements(child_ty, bytes);
+                let chunks = values
+                    .iter()
+                    .map(|value| child_ty.hash_tree_root(value))
+                    .collect::<Vec<_>>();
+                (values, chunks)
+            }
+        };
+        assert!(index < values.len(), "index {} out of bounds", index);
+
+        let mut proof = child_ty.prove(values[index], &path[1..]);
+        proof
+            .branch
+            .extend(merkle_branch(&chunks, self.chunk_limit(), index));
+        if let Self::List(_, _) = self {
+            proof.branch.push(length_chunk(values.len()));
+        }
+        proof.gindex = self.gindex(path);
+        proof
+    }
+
+    /// Returns the type of the child at the given path element and its index in the merkle tree.
+    fn child(&self, key: &str) -> (&SSZType, usize) {
+        match self {
+            Self::Container(fields) => {
+                let index = fields
+                    .iter()
+                    .position(|(name, _)| name == key)
+                    .unwrap_or_else(|| panic!("container has no field {}", key));
+                (&fields[index].1, index)
+            }
+            Self::Vector(ty, len) | Self::List(ty, len) if !ty.is_basic() => {
+                let index = key
+                    .parse::<usize>()
+                    .unwrap_or_else(|_| panic!("expected an index but got {}", key));
+                assert!(index < *len, "index {} out of bounds", index);
+                (ty.as_ref(), index)
+            }
+            _ => panic!("cannot access {} of {:?}", key, self),
+        }
+    }
+}
+
+/// The `BeaconState` schema of `fork` (mainnet preset), from altair onwards. The layout of the
+/// state, and therefore the generalized index of each field, depends on the fork.
+/// Reference: https://github.com/ethereum/consensus-specs/blob/dev/specs/electra/beacon-chain.md#beaconstate
+pub fn beacon_state_schema(fork: BeaconFork) -> SSZType {
+    assert!(
+        fork >= BeaconFork::Altair,
+        "{:?} beacon states are not supported",
+        fork
+    );
+    let uint64 = SSZType::Uint(8);
+    let bytes4 = SSZType::ByteVector(4);
+    let bytes32 = SSZType::ByteVector(32);
+    let bls_pubkey = SSZType::ByteVector(48);
+    let registry_limit = 1 << 40;
+
+    let fork_container = SSZType::container(&[
+        ("previous_version", bytes4.clone()),
+        ("current_version", bytes4),
+        ("epoch", uint64.clone()),
+    ]);
+    let header = SSZType::container(&[
+        ("slot", uint64.clone()),
+        ("proposer_index", uint64.clone()),
+        ("parent_root", bytes32.clone()),
+        ("state_root", bytes32.clone()),
+        ("body_root", bytes32.clone()),
+    ]);
+    let eth1_data = SSZType::container(&[
+        ("deposit_root", bytes32.clone()),
+        ("deposit_count", uint64.clone()),
+        ("block_hash", bytes32.clone()),
+    ]);
+    let validator = SSZType::container(&[
+        ("pubkey", bls_pubkey.clone()),
+        ("withdrawal_credentials", bytes32.clone()),
+        ("effective_balance", uint64.clone()),
+        ("slashed", SSZType::Bool),
+        ("activation_eligibility_epoch", uint64.clone()),
+        ("activation_epoch", uint64.clone()),
+        ("exit_epoch", uint64.clone()),
+        ("withdrawable_epoch", uint64.clone()),
+    ]);
+    let checkpoint = SSZType::container(&[("epoch", uint64.clone()), ("root", bytes32.clone())]);
+    let sync_committee = SSZType::container(&[
+        ("pubkeys", SSZType::vector(bls_pubkey.clone(), 512)),
+        ("aggregate_pubkey", bls_pubkey.clone()),
+    ]);
+
+    let mut fields = vec![
+        ("genesis_time", uint64.clone()),
+        ("genesis_validators_root", bytes32.clone()),
+        ("slot", uint64.clone()),
+        ("fork", fork_container),
+        ("latest_block_header", header),
+        ("block_roots", SSZType::vector(bytes32.clone(), 8192)),
+        ("state_roots", SSZType::vector(bytes32.clone(), 8192)),
+        ("historical_roots", SSZType::list(bytes32.clone(), 1 << 24)),
+        ("eth1_data", eth1_data.clone()),
+        ("eth1_data_votes", SSZType::list(eth1_data, 2048)),
+        ("eth1_deposit_index", uint64.clone()),
+        ("validators", SSZType::list(validator, registry_limit)),
+        ("balances", SSZType::list(uint64.clone(), registry_limit)),
+        ("randao_mixes", SSZType::vector(bytes32.clone(), 65536)),
+        ("slashings", SSZType::vector(uint64.clone(), 8192)),
+        (
+            "previous_epoch_participation",
+            SSZType::list(SSZType::Uint(1), registry_limit),
+        ),
+        (
+            "current_epoch_participation",
+            SSZType::list(SSZType::Uint(1), registry_limit),
+        ),
+        ("justification_bits", SSZType::Bitvector(4)),
+        ("previous_justified_checkpoint", checkpoint.clone()),
+        ("current_justified_checkpoint", checkpoint.clone()),
+        ("finalized_checkpoint", checkpoint),
+        (
+            "inactivity_scores",
+            SSZType::list(uint64.clone(), registry_limit),
+        ),
+        ("current_sync_committee", sync_committee.clone()),
+        ("next_sync_committee", sync_committee),
+    ];
+    if fork == BeaconFork::Altair {
+        return SSZType::container(&fields);
+    }
+
+    let mut execution_payload_header = vec![
+        ("parent_hash", bytes32.clone()),
+        ("fee_recipient", SSZType::ByteVector(20)),
+        ("state_root", bytes32.clone()),
+        ("receipts_root", bytes32.clone()),
+        ("logs_bloom", SSZType::ByteVector(256)),
+        ("prev_randao", bytes32.clone()),
+        ("block_number", uint64.clone()),
+        ("gas_limit", uint64.clone()),
+        ("gas_used", uint64.clone()),
+        ("timestamp", uint64.clone()),
+        ("extra_data", SSZType::ByteList(32)),
+        ("base_fee_per_gas", SSZType::Uint(32)),
+        ("block_hash", bytes32.clone()),
+        ("transactions_root", bytes32.clone()),
+    ];
+    if fork >= BeaconFork::Capella {
+        execution_payload_header.push(("withdrawals_root", bytes32.clone()));
+    }
+    if fork >= BeaconFork::Deneb {
+        execution_payload_header.push(("blob_gas_used", uint64.clone()));
+        execution_payload_header.push(("excess_blob_gas", uint64.clone()));
+    }
+    fields.push((
+        "latest_execution_payload_header",
+        SSZType::container(&execution_payload_header),
+    ));
+    if fork == BeaconFork::Bellatrix {
+        return SSZType::container(&fields);
+    }
+
+    let historical_summary = SSZType::container(&[
+        ("block_summary_root", bytes32.clone()),
+        ("state_summary_root", bytes32.clone()),
+    ]);
+    fields.extend([
+        ("next_withdrawal_index", uint64.clone()),
+        ("next_withdrawal_validator_index", uint64.clone()),
+        (
+            "historical_summaries",
+            SSZType::list(historical_summary, 1 << 24),
+        ),
+    ]);
+    if fork <= BeaconFork::Deneb {
+        return SSZType::container(&fields);
+    }
+
+    let pending_deposit = SSZType::container(&[
+        ("pubkey", bls_pubkey),
+        ("withdrawal_credentials", bytes32),
+        ("amount", uint64.clone()),
+        ("signature", SSZType::ByteVector(96)),
+        ("slot", uint64.clone()),
+    ]);
+    let pending_partial_withdrawal = SSZType::container(&[
+        ("validator_index", uint64.clone()),
+        ("amount", uint64.clone()),
+        ("withdrawable_epoch", uint64.clone()),
+    ]);
+    let pending_consolidation = SSZType::container(&[
+        ("source_index", uint64.clone()),
+        ("target_index", uint64.clone()),
+    ]);
+    fields.extend([
+        ("deposit_requests_start_index", uint64.clone()),
+        ("deposit_balance_to_consume", uint64.clone()),
+        ("exit_balance_to_consume", uint64.clone()),
+        ("earliest_exit_epoch", uint64.clone()),
+        ("consolidation_balance_to_consume", uint64.clone()),
+        ("earliest_consolidation_epoch", uint64),
+        ("pending_deposits", SSZType::list(pending_deposit, 1 << 27)),
+        (
+            "pending_partial_withdrawals",
+            SSZType::list(pending_partial_withdrawal, 1 << 27),
+        ),
+        (
+            "pending_consolidations",
+            SSZType::list(pending_consolidation, 1 << 18),
+        ),
+    ]);
+    SSZType::container(&fields)
+}
+
+fn ceil_log2(x: usize) -> usize {
+    if x <= 1 {
+        0
+    } else {
+        (usize::BITS - (x - 1).leading_zeros()) as usize
+    }
+}
+
+fn concat_gindex(parent: u64, child: u64) -> u64 {
+    let child_depth = 63 - child.leading_zeros();
+    (parent << child_depth) + (child - (1u64 << child_depth))
+}
+
+fn hash_pair(left: H256, right: H256) -> H256 {
+    H256::from(sha256(&[left.0, right.0].concat()))
+}
+
+pub(crate) fn zero_hashes(depth: usize) -> Vec<H256> {
+    let mut zeros = vec![H256::zero()];
+    for i in 0..depth {
+        zeros.push(hash_pair(zeros[i], zeros[i]));
+    }
+    zeros
+}
+
+/// Packs serialized bytes into zero padded chunks.
+fn pack(bytes: &[u8]) -> Vec<H256> {
+    bytes
+        .chunks(BYTES_PER_CHUNK)
+        .map(|chunk| {
+            let mut padded = [0u8; BYTES_PER_CHUNK];
+            padded[..chunk.len()].copy_from_slice(chunk);
+            H256::from(padded)
+        })
+        .collect()
+}
+
+fn length_chunk(len: usize) -> H256 {
+    let mut chunk = [0u8; BYTES_PER_CHUNK];
+    chunk[..8].copy_from_slice(&(len as u64).to_le_bytes());
+    H256::from(chunk)
+}
+
+fn mix_in_length(root: H256, len: usize) -> H256 {
+    hash_pair(root, length_chunk(len))
+}
+
+/// Merkleizes the chunks into a tree with `limit` leaves, padding with zero chunks.
+fn merkleize(chunks: &[H256], limit: usize) -> H256 {
+    assert!(chunks.len() <= limit, "too many chunks for limit {}", limit);
+    let depth = ceil_log2(limit);
+    let zeros = zero_hashes(depth);
+    let mut layer = chunks.to_vec();
+    for zero in zeros.iter().take(depth) {
+        if layer.len() % 2 == 1 {
+            layer.push(*zero);
+        }
+        layer = layer
+            .chunks(2)
+            .map(|pair| hash_pair(pair[0], pair[1]))
+            .collect();
+    }
+    layer.first().copied().unwrap_or(zeros[depth])
+}
+
+/// Computes the merkle branch of the chunk at `index` in a tree with `limit` leaves.
+fn merkle_branch(chunks: &[H256], limit: usize, index: usize) -> Vec<H256> {
+    let depth = ceil_log2(limit);
+    let zeros = zero_hashes(depth);
+    let mut layer = chunks.to_vec();
+    let mut index = index;
+    let mut branch = Vec::new();
+    for zero in zeros.iter().take(depth) {
+        if layer.len() % 2 == 1 {
+            layer.push(*zero);
+        }
+        branch.push(layer.get(index ^ 1).copied().unwrap_or(*zero));
+        layer = layer
+            .chunks(2)
+            .map(|pair| hash_pair(pair[0], pair[1]))
+            .collect();
+        index /= 2;
+    }
+    branch
+}
+
+/// Removes the delimiter bit of a serialized bitlist, returning the bits and their count.
+fn bitlist_bits(bytes: &[u8]) -> (Vec<u8>, usize) {
+    let last = *bytes.last().expect("bitlist is missing its delimiter bit");
+    assert!(last != 0, "bitlist is missing its delimiter bit");
+    let msb = 7 - last.leading_zeros() as usize;
+    let len = (bytes.len() - 1) * 8 + msb;
+    let mut bits = bytes.to_vec();
+    bits[bytes.len() - 1] ^= 1 << msb;
+    if msb == 0 {
+        bits.pop();
+    }
+    (bits, len)
+}
+
+fn read_offset(bytes: &[u8], position: usize) -> usize {
+    let mut offset = [0u8; BYTES_PER_LENGTH_OFFSET];
+    offset.copy_from_slice(&bytes[position..position + BYTES_PER_LENGTH_OFFSET]);
+    u32::from_le_bytes(offset) as usize
+}
+
+/// Splits a serialized vector or list into its serialized elements.
+fn decode_elements<'a>(ty: &SSZType, bytes: &'a [u8]) -> Vec<&'a [u8]> {
+    match ty.fixed_size() {
+        Some(size) => bytes.chunks(size).collect(),
+        None => {
+            if bytes.is_empty() {
+                return Vec::new();
+            }
+            let len = read_offset(bytes, 0) / BYTES_PER_LENGTH_OFFSET;
+            let offsets = (0..len)
+                .map(|i| read_offset(bytes, i * BYTES_PER_LENGTH_OFFSET))
+                .chain([bytes.len()])
+                .collect::<Vec<_>>();
+            offsets.windows(2).map(|w| &bytes[w[0]..w[1]]).collect()
+        }
+    }
+}
+
+/// The chunks that are merkleized for a vector or list with the given element type.
+fn element_chunks(ty: &SSZType, bytes: &[u8]) -> Vec<H256> {
+    if ty.is_basic() {
+        pack(bytes)
+    } else {
+        decode_elements(ty, bytes)
+            .into_iter()
+            .map(|value| ty.hash_tree_root(value))
+            .collect()
+    }
+}
+
+/// Splits a serialized container into its serialized fields.
+fn decode_fields<'a>(fields: &[(String, SSZType)], bytes: &'a [u8]) -> Vec<&'a [u8]> {
+    let mut position = 0;
+    let mut values = Vec::new();
+    let mut variable = Vec::new();
+    for (i, (_, ty)) in fields.iter().enumerate() {
+        match ty.fixed_size() {
+            Some(size) => {
+                values.push(&bytes[position..position + size]);
+                position += size;
+            }
+            None => {
+                variable.push((i, read_offset(bytes, position)));
+                values.push(&bytes[0..0]);
+                position += BYTES_PER_LENGTH_OFFSET;
+            }
+        }
+    }
+    for (j, (i, offset)) in variable.iter().enumerate() {
+        let end = variable.get(j + 1).map_or(bytes.len(), |(_, end)| *end);
+        values[*i] = &bytes[*offset..end];
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn restore_root(leaf: H256, branch: &[H256], gindex: u64) -> H256 {
+        let mut hash = leaf;
+        for (i, node) in branch.iter().enumerate() {
+            hash = if (gindex >> i) & 1 == 1 {
+                hash_pair(*node, hash)
+            } else {
+                hash_pair(hash, *node)
+            };
+        }
+        hash
+    }
+
+    #[test]
+    fn test_ssz_prove_field() {
+        let checkpoint = SSZType::container(&[
+            ("epoch", SSZType::Uint(8)),
+            ("root", SSZType::ByteVector(32)),
+        ]);
+        let schema = SSZType::container(&[
+            ("slot", SSZType::Uint(8)),
+            ("extra_data", SSZType::ByteList(32)),
+            ("checkpoints", SSZType::list(checkpoint, 16)),
+        ]);
+
+        let mut bytes = Vec::new();
+        bytes.extend(7u64.to_le_bytes());
+        bytes.extend(16u32.to_le_bytes());
+        bytes.extend(19u32.to_le_bytes());
+        bytes.extend([0xaa, 0xbb, 0xcc]);
+        for epoch in 0..3u64 {
+            bytes.extend(epoch.to_le_bytes());
+            bytes.extend([epoch as u8; 32]);
+        }
+        let root = schema.hash_tree_root(&bytes);
+
+        let path = vec!["slot".to_string()];
+        let proof = schema.prove(&bytes, &path);
+        assert_eq!(proof.value, 7u64.to_le_bytes());
+        assert_eq!(proof.gindex, 4);
+        assert_eq!(restore_root(proof.leaf, &proof.branch, proof.gindex), root);
+
+        let path = vec!["extra_data".to_string()];
+        let proof = schema.prove(&bytes, &path);
+        assert_eq!(proof.value, vec![0xaa, 0xbb, 0xcc]);
+        assert_eq!(restore_root(proof.leaf, &proof.branch, proof.gindex), root);
+
+        let path = vec![
+            "checkpoints".to_string(),
+            "2".to_string(),
+            "root".to_string(),
+        ];
+        let proof = schema.prove(&bytes, &path);
+        assert_eq!(proof.value, vec![2u8; 32]);
+        assert_eq!(proof.branch.len() as u32, 63 - proof.gindex.leading_zeros());
+        assert_eq!(restore_root(proof.leaf, &proof.branch, proof.gindex), root);
+    }
+
+    #[test]
+    fn test_beacon_state_schema_gindices() {
+        let gindex = |fork: BeaconFork, path: &[&str]| {
+            let path = path.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+            beacon_state_schema(fork).gindex(&path)
+        };
+
+        // Up to deneb, the state has at most 32 fields.
+        for fork in [BeaconFork::Altair, BeaconFork::Capella, BeaconFork::Deneb] {
+            assert_eq!(gindex(fork, &["slot"]), 34);
+            assert_eq!(gindex(fork, &["validators"]), 43);
+            assert_eq!(gindex(fork, &["balances"]), 44);
+            assert_eq!(gindex(fork, &["finalized_checkpoint", "root"]), 105);
+            assert_eq!(gindex(fork, &["next_sync_committee"]), 55);
+        }
+
+        // The 37 fields of the electra state need one more level.
+        let electra = BeaconFork::Electra;
+        assert_eq!(gindex(electra, &["slot"]), 66);
+        assert_eq!(gindex(electra, &["validators"]), 75);
+        assert_eq!(gindex(electra, &["balances"]), 76);
+        assert_eq!(gindex(electra, &["finalized_checkpoint", "root"]), 169);
+        assert_eq!(gindex(electra, &["next_sync_committee"]), 87);
+        assert_eq!(gindex(electra, &["pending_consolidations"]), 100);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_beacon_state_schema_phase0() {
+        beacon_state_schema(BeaconFork::Phase0);
+    }
+}
@@ -4,6 +4,8 @@ use std::env;
 
 use ethers::providers::{Http, Provider};
 
+use crate::frontend::hint::asynchronous::rpc::RpcClient;
+
 pub mod beacon;
 
 #[derive(Debug, Clone)]
@@ -12,12 +14,22 @@ pub struct Address(pub [u8; 20]);
 #[derive(Debug, Clone)]
 pub struct BLSPubkey(pub [u8; 48]);
 
-pub fn get_provider(chain_id: u64) -> Provider<Http> {
+/// Returns the url of the execution RPC for `chain_id`, set by the `RPC_{chain_id}` variable.
+pub fn get_rpc_url(chain_id: u64) -> String {
     dotenv::dotenv().ok();
     let rpc_str = format!("RPC_{}", chain_id);
-    let rpc_url = env::var(rpc_str)
-        .unwrap_or_else(|_| format!("RPC_{} environment variable was not found", chain_id));
-    Provider::<Http>::try_from(rpc_url).unwrap()
+    env::var(rpc_str)
+        .unwrap_or_else(|_| format!("RPC_{} environment variable was not found", chain_id))
+}
+
+pub fn get_provider(chain_id: u64) -> Provider<Http> {
+    Provider::<Http>::try_from(get_rpc_url(chain_id)).unwrap()
+}
+
+/// Returns the size-limited JSON-RPC client for `chain_id`, which hints and generators use to
+/// query execution nodes.
+pub fn get_rpc_client(chain_id: u64) -> RpcClient {
+    RpcClient::new(get_rpc_url(chain_id))
 }
 
 pub fn concat_g_indices(gindexes: &[usize]) -> usize {
@@ -7,6 +7,7 @@ pub mod proof;
 pub mod reqwest;
 pub mod serde;
 pub mod stream;
+pub mod testing;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 use log::{set_max_level, LevelFilter};
@@ -2,19 +2,37 @@ use core::time::Duration;
 
 use anyhow::{anyhow, Result};
 use log::debug;
-use reqwest::blocking::Response;
+use serde::de::DeserializeOwned;
 
-#[derive(Debug, Clone, Default)]
+use crate::frontend::hint::asynchronous::rpc::{
+    read_blocking_response, read_response, DEFAULT_MAX_RESPONSE_SIZE,
+};
+
+/// The body of a response, read up to the maximum response size of the client.
+#[derive(Debug, Clone)]
+pub struct ResponseBody(pub Vec<u8>);
+
+impl ResponseBody {
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(serde_json::from_slice(&self.0)?)
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct ReqwestClient {
     pub client: reqwest::blocking::Client,
     pub client_async: reqwest::Client,
+    /// The maximum size in bytes of a response body, above which fetches fail.
+    pub max_response_size: usize,
 }
 
 impl ReqwestClient {
+    /// Creates a client whose responses are limited to `DEFAULT_MAX_RESPONSE_SIZE` bytes.
     pub fn new() -> Self {
         ReqwestClient {
             client: reqwest::blocking::Client::new(),
             client_async: reqwest::Client::new(),
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
         }
     }
 
@@ -22,10 +40,16 @@ impl ReqwestClient {
         ReqwestClient {
             client,
             client_async,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
         }
     }
 
-    pub async fn fetch_async(&self, endpoint: &str) -> Result<reqwest::Response> {
+    pub fn with_max_response_size(mut self, max_response_size: usize) -> Self {
+        self.max_response_size = max_response_size;
+        self
+    }
+
+    pub async fn fetch_async(&self, endpoint: &str) -> Result<ResponseBody> {
         const MAX_RETRIES: u32 = 2;
         const INITIAL_RETRY_DELAY: u64 = 5;
 
@@ -42,15 +66,14 @@ impl ReqwestClient {
                 .await;
             match response {
                 Ok(res) => {
-                    if res.status().is_success() {
-                        return Ok(res);
-                    } else if res.status().is_server_error() {
+                    if res.status().is_server_error() {
                         debug!("Server error: {:?}", res.status());
                         if retries >= MAX_RETRIES {
                             return Err(anyhow!("Maximum retries exceeded"));
                         }
                     } else {
-                        return Ok(res);
+                        let body = read_response(res, self.max_response_size).await?;
+                        return Ok(ResponseBody(body));
                     }
                 }
                 Err(err) => {
@@ -67,7 +90,7 @@ impl ReqwestClient {
         }
     }
 
-    pub fn fetch(&self, endpoint: &str) -> Result<Response> {
+    pub fn fetch(&self, endpoint: &str) -> Result<ResponseBody> {
         const MAX_RETRIES: u32 = 2;
         const INITIAL_RETRY_DELAY: u64 = 5;
 
@@ -83,15 +106,14 @@ impl ReqwestClient {
 
             match response {
                 Ok(res) => {
-                    if res.status().is_success() {
-                        return Ok(res);
-                    } else if res.status().is_server_error() {
+                    if res.status().is_server_error() {
                         debug!("Server error: {:?}", res.status());
                         if retries >= MAX_RETRIES {
                             return Err(anyhow!("Maximum retries exceeded"));
                         }
                     } else {
-                        return Ok(res);
+                        let body = read_blocking_response(res, self.max_response_size)?;
+                        return Ok(ResponseBody(body));
                     }
                 }
                 Err(err) => {
@@ -108,3 +130,9 @@ impl ReqwestClient {
         }
     }
 }
+
+impl Default for ReqwestClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -19,12 +19,25 @@ impl<T> Stream<T> {
         out_slice
     }
 
+    /// Read the next `len` elements without advancing the position
+    pub fn peek_exact(&self, len: usize) -> &[T] {
+        if (self.position + len) > self.data.len() {
+            panic!("Not enough elements in Stream");
+        }
+        self.data[self.position..self.position + len].as_ref()
+    }
+
     /// Read all remaining elements
     pub fn read_all(&self) -> &[T] {
         let length = self.data.len() - self.position;
         &self.data[self.position..self.position + length]
     }
 
+    /// All elements of the stream, including those already read
+    pub fn data(&self) -> &[T] {
+        &self.data
+    }
+
     /// Drain the stream and return the underlying data (including data already read)
     pub fn drain(self) -> Vec<T> {
         self.data
@@ -40,4 +53,12 @@ impl<T> Stream<T> {
     pub fn position(&self) -> usize {
         self.position
     }
+
+    /// Move the read position to `position`, which may be before or after the current one
+    pub fn seek(&mut self, position: usize) {
+        if position > self.data.len() {
+            panic!("Seek position out of bounds of Stream");
+        }
+        self.position = position;
+    }
 }
@@ -0,0 +1,173 @@
+//! Test support for pinning the definition of a circuit to a committed snapshot file.
+//!
+//! A snapshot records the structure digest, the gate count and the public inputs layout of a
+//! circuit, so that a test fails whenever a change to a gadget silently changes the circuits that
+//! use it. The digest hashes the serialized circuit, so it does not depend on how the circuit
+//! data is printed. Snapshots are regenerated by running the tests with
+//! `UPDATE_CIRCUIT_SNAPSHOTS=1`.
+
+use std::path::Path;
+use std::{env, fs};
+
+use plonky2::util::serialization::{GateSerializer, WitnessGeneratorSerializer};
+use serde::{Deserialize, Serialize};
+
+use crate::backend::circuit::{GateRegistry, HintRegistry};
+use crate::frontend::builder::PublicInputsLayout;
+use crate::prelude::{CircuitBuilder, PlonkParameters};
+
+/// The environment variable that makes snapshot assertions overwrite the snapshot files.
+pub const UPDATE_SNAPSHOTS_ENV: &str = "UPDATE_CIRCUIT_SNAPSHOTS";
+
+/// The parts of a circuit definition that are pinned by a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitSnapshot {
+    pub digest: String,
+    pub num_gates: usize,
+    pub public_inputs: PublicInputsLayout,
+}
+
+impl CircuitSnapshot {
+    /// Defines a circuit with `define` and captures its snapshot from a mock build.
+    pub fn capture<L: PlonkParameters<D>, const D: usize, F>(define: F) -> Self
+    where
+        F: FnOnce(&mut CircuitBuilder<L, D>),
+    {
+        Self::capture_with(
+            define,
+            &GateRegistry::<L, D>::new(),
+            &HintRegistry::<L, D>::new(),
+        )
+    }
+
+    /// Like [`CircuitSnapshot::capture`], with the serializers of circuits that use custom gates
+    /// or hints.
+    pub fn capture_with<L: PlonkParameters<D>, const D: usize, F>(
+        define: F,
+        gate_serializer: &impl GateSerializer<L::Field, D>,
+        hint_serializer: &impl WitnessGeneratorSerializer<L::Field, D>,
+    ) -> Self
+    where
+        F: FnOnce(&mut CircuitBuilder<L, D>),
+    {
+        let mut builder = CircuitBuilder::<L, D>::new();
+        define(&mut builder);
+        let public_inputs = builder.public_inputs_layout();
+        let circuit = builder.mock_build();
+        Self {
+            digest: circuit
+                .structure_digest(gate_serializer, hint_serializer)
+                .expect("failed to serialize circuit"),
+            num_gates: circuit.num_gates,
+            public_inputs,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+/// Asserts that the circuit defined by `define` matches the snapshot stored at `path`.
+///
+/// If `UPDATE_CIRCUIT_SNAPSHOTS` is set, the snapshot file is written instead.
+pub fn assert_circuit_snapshot<L: PlonkParameters<D>, const D: usize, F>(
+    path: impl AsRef<Path>,
+    define: F,
+) where
+    F: FnOnce(&mut CircuitBuilder<L, D>),
+{
+    let update = env::var(UPDATE_SNAPSHOTS_ENV).is_ok_and(|v| !v.is_empty() && v != "0");
+    check_circuit_snapshot(path.as_ref(), &CircuitSnapshot::capture(define), update);
+}
+
+fn check_circuit_snapshot(path: &Path, snapshot: &CircuitSnapshot, update: bool) {
+    if update {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(path, snapshot.to_json() + "\n").unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(path).unwrap_or_else(|_| {
+        panic!(
+            "missing circuit snapshot {}, rerun with {}=1 to create it",
+            path.display(),
+            UPDATE_SNAPSHOTS_ENV
+        )
+    });
+    let expected: CircuitSnapshot = serde_json::from_str(&expected).unwrap();
+    if &expected != snapshot {
+        panic!(
+            "circuit does not match snapshot {}, rerun with {}=1 if the change is intended\n\
+             expected: {}\nactual: {}",
+            path.display(),
+            UPDATE_SNAPSHOTS_ENV,
+            expected.to_json(),
+            snapshot.to_json()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::process;
+
+    use super::*;
+    use crate::prelude::{DefaultBuilder, Variable};
+
+    #[test]
+    fn test_circuit_snapshot() {
+        let path = env::temp_dir()
+            .join(format!("plonky2x-snapshot-{}", process::id()))
+            .join("add.json");
+
+        let add = |builder: &mut DefaultBuilder| {
+            let a = builder.read::<Variable>();
+            let b = builder.read::<Variable>();
+            let c = builder.add(a, b);
+            builder.write(c);
+        };
+        let mul = |builder: &mut DefaultBuilder| {
+            let a = builder.read::<Variable>();
+            let b = builder.read::<Variable>();
+            let c = builder.mul(a, b);
+            builder.write(c);
+        };
+
+        let snapshot = CircuitSnapshot::capture(add);
+        assert_eq!(snapshot, CircuitSnapshot::capture(add));
+        let mut builder = DefaultBuilder::new();
+        add(&mut builder);
+        let digest = builder
+            .mock_build()
+            .structure_digest(&GateRegistry::new(), &HintRegistry::new())
+            .unwrap();
+        assert_eq!(snapshot.digest, digest);
+        assert_eq!(snapshot.public_inputs.entries.len(), 3);
+
+        // Write the snapshot, then check it against the same and a different circuit.
+        check_circuit_snapshot(&path, &snapshot, true);
+        check_circuit_snapshot(&path, &CircuitSnapshot::capture(add), false);
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            check_circuit_snapshot(&path, &CircuitSnapshot::capture(mul), false)
+        }));
+        assert!(result.is_err());
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    /// Pins a circuit to the snapshot committed in `src/utils/snapshots`.
+    #[test]
+    fn test_committed_circuit_snapshot() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/utils/snapshots/add.json");
+        assert_circuit_snapshot(path, |builder: &mut DefaultBuilder| {
+            let a = builder.read::<Variable>();
+            let b = builder.read::<Variable>();
+            let c = builder.add(a, b);
+            builder.write(c);
+        });
+    }
+}